@@ -7,6 +7,7 @@ pub enum MemoryAddressKind {
     Data,
 }
 
+#[derive(Clone, Copy, Debug)]
 pub struct PhysicalAddress {
     pub bank: Nibble,
     pub base: Address,
@@ -16,13 +17,17 @@ impl PhysicalAddress {
     pub fn new(bank: Nibble, base: Address) -> Self {
         Self { bank, base }
     }
+
+    /// The 20-bit address formed by prepending the bank to the base address.
+    pub fn linear(&self) -> usize {
+        ((self.bank.as_inner() as usize) << 16) | self.base as usize
+    }
 }
 
 #[derive(Debug)]
 pub enum EnvironmentAction {
-    Halt,
+    Halt(Byte),
     Break,
-    WriteByte(Byte),
 }
 
 pub enum BusResult<T> {
@@ -55,11 +60,19 @@ pub trait Bus {
         data: Byte,
     ) -> BusResult<()>;
 
+    /// Advances the peripherals by one processor cycle. Called at the start of
+    /// every cycle, before the interrupt and reset lines are sampled.
+    fn clock(&mut self);
+
     fn is_rst_active(&self) -> bool;
 
     fn is_nmi_active(&mut self) -> bool;
 
     fn is_irq_active(&self) -> bool;
 
+    /// Tells the interrupt source that the processor is servicing the IRQ it
+    /// requested, so that it can report which request that was.
+    fn acknowledge_irq(&mut self);
+
     fn is_req_active(&self) -> bool;
 }
@@ -1,27 +1,8 @@
 use std::ops::{Index, IndexMut};
 
-use crate::*;
-
-#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
-pub enum Register8 {
-    A,
-    B,
-    C,
-    D,
-}
+use isa::{Pointer, Register16, Register8};
 
-#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
-pub enum Register16 {
-    X,
-    Y,
-}
-
-#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
-pub enum Pointer {
-    X,
-    Y,
-    SP,
-}
+use crate::*;
 
 #[derive(Default)]
 pub struct RegisterFile {
@@ -6,6 +6,12 @@ pub struct Trace {
     executed: HashMap<Instruction, usize>,
 }
 
+impl Default for Trace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Trace {
     pub fn new() -> Self {
         Self {
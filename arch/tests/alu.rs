@@ -0,0 +1,224 @@
+use arch::*;
+use isa::{encode, Alu1Op, Alu2Op, Alu2OpMode};
+
+/// A flat 64 KiB memory with no IO devices or interrupts.
+struct FlatBus {
+    memory: Box<[Byte; 0x1_0000]>,
+}
+
+impl FlatBus {
+    fn new() -> Self {
+        Self {
+            memory: Box::new([0; 0x1_0000]),
+        }
+    }
+
+    fn load(&mut self, address: Address, program: &[Instruction]) {
+        let mut address = address;
+        for &instruction in program {
+            for byte in encode(instruction).to_vec() {
+                self.memory[address as usize] = byte;
+                address += 1;
+            }
+        }
+    }
+}
+
+impl Bus for FlatBus {
+    fn memory_read(
+        &self,
+        _privilege: PrivilegeLevel,
+        _kind: MemoryAddressKind,
+        address: PhysicalAddress,
+    ) -> Byte {
+        self.memory[address.base as usize]
+    }
+
+    fn memory_write(
+        &mut self,
+        _privilege: PrivilegeLevel,
+        _kind: MemoryAddressKind,
+        address: PhysicalAddress,
+        data: Byte,
+    ) {
+        self.memory[address.base as usize] = data;
+    }
+
+    fn io_read(
+        &mut self,
+        _privilege: PrivilegeLevel,
+        _address: PhysicalAddress,
+    ) -> BusResult<Byte> {
+        BusResult::Data(0)
+    }
+
+    fn io_write(
+        &mut self,
+        _privilege: PrivilegeLevel,
+        _address: PhysicalAddress,
+        _data: Byte,
+    ) -> BusResult<()> {
+        BusResult::Data(())
+    }
+
+    fn clock(&mut self) {}
+
+    fn is_rst_active(&self) -> bool {
+        false
+    }
+
+    fn is_nmi_active(&mut self) -> bool {
+        false
+    }
+
+    fn is_irq_active(&self) -> bool {
+        false
+    }
+
+    fn acknowledge_irq(&mut self) {}
+
+    fn is_req_active(&self) -> bool {
+        false
+    }
+}
+
+/// The flags as a string of `CZVN`, with `-` for each one that is clear.
+fn flags(status: &Status) -> String {
+    [
+        (status.carry, 'C'),
+        (status.zero, 'Z'),
+        (status.overflow, 'V'),
+        (status.negative, 'N'),
+    ]
+    .iter()
+    .map(|&(set, name)| if set { name } else { '-' })
+    .collect()
+}
+
+/// Runs `instruction` with `a` and `b` in A and B and the carry flag set to
+/// `carry`, returning A and the flags. V is set beforehand, so that
+/// operations which clear it can be told from those which leave it alone.
+fn execute(instruction: Instruction, a: Byte, b: Byte, carry: bool) -> (Byte, String) {
+    let mut bus = FlatBus::new();
+    let mut cpu = CpuState::new();
+
+    bus.load(
+        0x0000,
+        &[
+            // 0x01 + 0x7F overflows into the sign bit, setting V.
+            Instruction::Alu2(Alu2Op::Addc, Register8::C, Alu2OpMode::Constant(0x7F)),
+            match carry {
+                true => Instruction::SetCarry,
+                false => Instruction::ClearCarry,
+            },
+            instruction,
+        ],
+    );
+    cpu[Architectural8::A] = a;
+    cpu[Architectural8::B] = b;
+    cpu[Architectural8::C] = 0x01;
+
+    for _ in 0..3 {
+        cpu.run(&mut bus, 1);
+    }
+
+    (cpu[Architectural8::A], flags(cpu.status()))
+}
+
+fn alu2(op: Alu2Op) -> Instruction {
+    Instruction::Alu2(op, Register8::A, Alu2OpMode::Register(Register8::B))
+}
+
+fn alu1(op: Alu1Op) -> Instruction {
+    Instruction::Alu1(op, Register8::A)
+}
+
+#[test]
+fn addc_sets_carry_out_and_signed_overflow() {
+    let add = alu2(Alu2Op::Addc);
+
+    assert_eq!(execute(add, 0x12, 0x34, false), (0x46, "----".into()));
+    assert_eq!(execute(add, 0x12, 0x34, true), (0x47, "----".into()));
+    assert_eq!(execute(add, 0xFF, 0x01, false), (0x00, "CZ--".into()));
+    assert_eq!(execute(add, 0xFF, 0x00, true), (0x00, "CZ--".into()));
+    assert_eq!(execute(add, 0x7F, 0x01, false), (0x80, "--VN".into()));
+    assert_eq!(execute(add, 0x80, 0x80, false), (0x00, "CZV-".into()));
+    assert_eq!(execute(add, 0xFF, 0xFF, false), (0xFE, "C--N".into()));
+}
+
+#[test]
+fn subb_and_cmp_set_carry_as_not_borrow() {
+    let sub = alu2(Alu2Op::Subb);
+    let cmp = alu2(Alu2Op::Cmp);
+
+    assert_eq!(execute(sub, 0x05, 0x03, true), (0x02, "C---".into()));
+    assert_eq!(execute(sub, 0x05, 0x03, false), (0x01, "C---".into()));
+    assert_eq!(execute(sub, 0x03, 0x03, true), (0x00, "CZ--".into()));
+    assert_eq!(execute(sub, 0x03, 0x03, false), (0xFF, "---N".into()));
+    assert_eq!(execute(sub, 0x03, 0x05, true), (0xFE, "---N".into()));
+
+    // Cmp ignores the carry in and leaves A alone.
+    assert_eq!(execute(cmp, 0x05, 0x03, false), (0x05, "C---".into()));
+    assert_eq!(execute(cmp, 0x03, 0x03, false), (0x03, "CZ--".into()));
+    assert_eq!(execute(cmp, 0x03, 0x05, true), (0x03, "---N".into()));
+}
+
+#[test]
+fn subb_and_cmp_set_signed_overflow() {
+    let sub = alu2(Alu2Op::Subb);
+    let cmp = alu2(Alu2Op::Cmp);
+
+    assert_eq!(execute(sub, 0x80, 0x01, true), (0x7F, "C-V-".into()));
+    assert_eq!(execute(sub, 0x7F, 0xFF, true), (0x80, "--VN".into()));
+    assert_eq!(execute(sub, 0xFF, 0x01, true), (0xFE, "C--N".into()));
+
+    // -128 < 1 signed, which N != V shows even though N is clear.
+    assert_eq!(execute(cmp, 0x80, 0x01, true), (0x80, "C-V-".into()));
+}
+
+#[test]
+fn logic_operations_clear_overflow_and_keep_carry() {
+    let and = alu2(Alu2Op::And);
+    let or = alu2(Alu2Op::Or);
+    let xor = alu2(Alu2Op::Xor);
+    let not = alu1(Alu1Op::Not);
+    let test = alu1(Alu1Op::Test);
+
+    assert_eq!(execute(and, 0xF0, 0x3C, true), (0x30, "C---".into()));
+    assert_eq!(execute(or, 0x80, 0x01, false), (0x81, "---N".into()));
+    assert_eq!(execute(xor, 0x5A, 0x5A, true), (0x00, "CZ--".into()));
+    assert_eq!(execute(not, 0x0F, 0, false), (0xF0, "---N".into()));
+    assert_eq!(execute(test, 0x00, 0, true), (0x00, "CZ--".into()));
+}
+
+#[test]
+fn shifts_carry_out_the_bit_shifted_out() {
+    let shl = alu1(Alu1Op::Shl);
+    let shr = alu1(Alu1Op::Shr);
+    let asr = alu1(Alu1Op::Asr);
+
+    assert_eq!(execute(shl, 0x81, 0, false), (0x02, "C-V-".into()));
+    assert_eq!(execute(shl, 0x40, 0, true), (0x80, "--VN".into()));
+    assert_eq!(execute(shl, 0xC0, 0, false), (0x80, "C--N".into()));
+    assert_eq!(execute(shr, 0x81, 0, false), (0x40, "C---".into()));
+    assert_eq!(execute(shr, 0x02, 0, true), (0x01, "----".into()));
+    assert_eq!(execute(asr, 0x81, 0, false), (0xC0, "C--N".into()));
+    assert_eq!(execute(asr, 0x01, 0, false), (0x00, "CZ--".into()));
+}
+
+#[test]
+fn neg_inc_and_dec_set_overflow_at_the_signed_limits() {
+    let neg = alu1(Alu1Op::Neg);
+    let inc = alu1(Alu1Op::Inc);
+    let dec = alu1(Alu1Op::Dec);
+
+    assert_eq!(execute(neg, 0x00, 0, false), (0x00, "CZ--".into()));
+    assert_eq!(execute(neg, 0x01, 0, true), (0xFF, "---N".into()));
+    assert_eq!(execute(neg, 0x80, 0, false), (0x80, "--VN".into()));
+
+    // Inc and dec leave the carry alone.
+    assert_eq!(execute(inc, 0x7F, 0, false), (0x80, "--VN".into()));
+    assert_eq!(execute(inc, 0xFF, 0, true), (0x00, "CZ--".into()));
+    assert_eq!(execute(dec, 0x80, 0, true), (0x7F, "C-V-".into()));
+    assert_eq!(execute(dec, 0x00, 0, false), (0xFF, "---N".into()));
+}
@@ -0,0 +1,164 @@
+use arch::*;
+use isa::{encode, Alu2Op, Alu2OpMode, Condition, JumpMode};
+
+/// A flat 64 KiB memory with no IO devices or interrupts.
+struct FlatBus {
+    memory: Box<[Byte; 0x1_0000]>,
+}
+
+impl FlatBus {
+    fn new() -> Self {
+        Self {
+            memory: Box::new([0; 0x1_0000]),
+        }
+    }
+
+    fn load(&mut self, address: Address, program: &[Instruction]) -> Address {
+        let mut address = address;
+        for &instruction in program {
+            for byte in encode(instruction).to_vec() {
+                self.memory[address as usize] = byte;
+                address += 1;
+            }
+        }
+        address
+    }
+}
+
+impl Bus for FlatBus {
+    fn memory_read(
+        &self,
+        _privilege: PrivilegeLevel,
+        _kind: MemoryAddressKind,
+        address: PhysicalAddress,
+    ) -> Byte {
+        self.memory[address.base as usize]
+    }
+
+    fn memory_write(
+        &mut self,
+        _privilege: PrivilegeLevel,
+        _kind: MemoryAddressKind,
+        address: PhysicalAddress,
+        data: Byte,
+    ) {
+        self.memory[address.base as usize] = data;
+    }
+
+    fn io_read(
+        &mut self,
+        _privilege: PrivilegeLevel,
+        _address: PhysicalAddress,
+    ) -> BusResult<Byte> {
+        BusResult::Data(0)
+    }
+
+    fn io_write(
+        &mut self,
+        _privilege: PrivilegeLevel,
+        _address: PhysicalAddress,
+        _data: Byte,
+    ) -> BusResult<()> {
+        BusResult::Data(())
+    }
+
+    fn clock(&mut self) {}
+
+    fn is_rst_active(&self) -> bool {
+        false
+    }
+
+    fn is_nmi_active(&mut self) -> bool {
+        false
+    }
+
+    fn is_irq_active(&self) -> bool {
+        false
+    }
+
+    fn acknowledge_irq(&mut self) {}
+
+    fn is_req_active(&self) -> bool {
+        false
+    }
+}
+
+const CONDITIONS: [Condition; 11] = [
+    Condition::Always,
+    Condition::Equal,
+    Condition::NotEqual,
+    Condition::LessThan,
+    Condition::GreaterThan,
+    Condition::LessEqual,
+    Condition::GreaterEqual,
+    Condition::LessThanSigned,
+    Condition::GreaterThanSigned,
+    Condition::LessEqualSigned,
+    Condition::GreaterEqualSigned,
+];
+
+const MODES: [JumpMode; 3] = [
+    JumpMode::Relative(0x40),
+    JumpMode::Absolute(0x1000),
+    JumpMode::Indirect(Register16::X, 0x10),
+];
+
+fn expected(condition: Condition, left: Byte, right: Byte) -> bool {
+    let (signed_left, signed_right) = (left as i8, right as i8);
+
+    match condition {
+        Condition::Always => true,
+        Condition::Equal => left == right,
+        Condition::NotEqual => left != right,
+        Condition::LessThan => left < right,
+        Condition::GreaterThan => left > right,
+        Condition::LessEqual => left <= right,
+        Condition::GreaterEqual => left >= right,
+        Condition::LessThanSigned => signed_left < signed_right,
+        Condition::GreaterThanSigned => signed_left > signed_right,
+        Condition::LessEqualSigned => signed_left <= signed_right,
+        Condition::GreaterEqualSigned => signed_left >= signed_right,
+    }
+}
+
+#[test]
+fn branch_after_cmp() {
+    let mut bus = FlatBus::new();
+    let mut cpu = CpuState::new();
+
+    for condition in CONDITIONS {
+        for mode in MODES {
+            let program = [
+                Instruction::Alu2(
+                    Alu2Op::Cmp,
+                    Register8::A,
+                    Alu2OpMode::Register(Register8::B),
+                ),
+                Instruction::Jmp(condition, mode),
+            ];
+            let fall_through = bus.load(0x0000, &program);
+            let cycles = program.iter().map(Instruction::cycles).sum();
+
+            for left in Byte::MIN..=Byte::MAX {
+                for right in Byte::MIN..=Byte::MAX {
+                    cpu[Architectural16::PC] = 0x0000;
+                    cpu[Architectural16::X] = 0x2000;
+                    cpu[Architectural8::A] = left;
+                    cpu[Architectural8::B] = right;
+
+                    cpu.run(&mut bus, cycles);
+
+                    assert_eq!(
+                        cpu[Architectural16::PC] != fall_through,
+                        expected(condition, left, right),
+                        "{:?} with {:?} after cmp 0x{:02x}, 0x{:02x}",
+                        condition,
+                        mode,
+                        left,
+                        right,
+                    );
+                }
+            }
+        }
+    }
+}
@@ -273,13 +273,13 @@
     mv x, sp  => 0xf6
     mv sp, x  => 0xf7
 
-    mv x, x   => 0xf8
+    mv x, usp => 0xf8
     mv x, y   => 0xf9
     mv x, ab  => 0xfa
     mv x, cd  => 0xfb
 
     mv y, x   => 0xfc
-    mv y, y   => 0xfd
+    mv usp, x => 0xfd
     mv y, ab  => 0xfe
     mv y, cd  => 0xff
 
@@ -1,9 +1,83 @@
 #const EMULATOR_HALT = 0x00
 #const EMULATOR_PUTCHAR = 0x01
-#const EMULATOR_IRQ_ACK = 0x02
 #const EMULATOR_BREAKPOINT = 0x03
 
 #const IO_VGA_BASE = 0x8000
 #const IO_TILEMAP_BASE = IO_VGA_BASE + 0x0000
 #const IO_BITMAP_BASE = IO_VGA_BASE + 0x4000
-#const IO_PALETTE_BASE = IO_VGA_BASE + 0x6000
\ No newline at end of file
+#const IO_PALETTE_BASE = IO_VGA_BASE + 0x6000
+#const IO_SPRITE_BASE = IO_VGA_BASE + 0x7000
+#const IO_UART_BASE = 0x10
+#const IO_UART_DATA = IO_UART_BASE + 0
+#const IO_UART_STATUS = IO_UART_BASE + 1
+#const IO_UART_CONTROL = IO_UART_BASE + 2
+#const IO_UART_DIVISOR = IO_UART_BASE + 3
+
+#const UART_STATUS_RX_READY = 0b0000_0001
+#const UART_STATUS_TX_READY = 0b0000_0010
+#const UART_STATUS_TX_EMPTY = 0b0000_0100
+#const UART_STATUS_RX_OVERRUN = 0b0000_1000
+#const UART_CONTROL_RX_IRQ_ENABLE = 0b0000_0001
+
+#const IO_PIT_BASE = 0x20
+#const IO_PIT_CONTROL = IO_PIT_BASE + 0
+#const IO_PIT_STATUS = IO_PIT_BASE + 1
+#const IO_PIT_PRESCALER = IO_PIT_BASE + 2
+#const IO_PIT_RELOAD_LOW = IO_PIT_BASE + 3
+#const IO_PIT_RELOAD_HIGH = IO_PIT_BASE + 4
+#const IO_PIT_COUNT_LOW = IO_PIT_BASE + 5
+#const IO_PIT_COUNT_HIGH = IO_PIT_BASE + 6
+
+#const PIT_CONTROL_ENABLE = 0b0000_0001
+#const PIT_CONTROL_PERIODIC = 0b0000_0010
+#const PIT_CONTROL_IRQ_ENABLE = 0b0000_0100
+#const PIT_STATUS_EXPIRED = 0b0000_0001
+
+#const IO_INTC_BASE = 0x30
+#const IO_INTC_PENDING = IO_INTC_BASE + 0
+#const IO_INTC_MASK = IO_INTC_BASE + 1
+#const IO_INTC_CURRENT = IO_INTC_BASE + 2
+
+#const IRQ_TIMER = 0
+#const IRQ_UART = 1
+#const IRQ_VBLANK = 2
+#const IRQ_KEYBOARD = 3
+#const IRQ_EXTERNAL = 7
+#const IRQ_NONE = 0xFF
+
+#const IO_VGA_REGISTERS = 0x40
+#const IO_VGA_STATUS = IO_VGA_REGISTERS + 0
+#const IO_VGA_CONTROL = IO_VGA_REGISTERS + 1
+#const IO_VGA_LINE_LOW = IO_VGA_REGISTERS + 2
+#const IO_VGA_LINE_HIGH = IO_VGA_REGISTERS + 3
+#const IO_VGA_SCROLL_X_LOW = IO_VGA_REGISTERS + 4
+#const IO_VGA_SCROLL_X_HIGH = IO_VGA_REGISTERS + 5
+#const IO_VGA_SCROLL_Y_LOW = IO_VGA_REGISTERS + 6
+#const IO_VGA_SCROLL_Y_HIGH = IO_VGA_REGISTERS + 7
+
+#const VGA_STATUS_VBLANK = 0b0000_0001
+#const VGA_STATUS_HBLANK = 0b0000_0010
+#const VGA_STATUS_SPRITE_OVERFLOW = 0b0000_0100
+#const VGA_CONTROL_VBLANK_IRQ_ENABLE = 0b0000_0001
+
+#const SPRITE_X_LOW = 0
+#const SPRITE_X_HIGH = 1
+#const SPRITE_Y_LOW = 2
+#const SPRITE_Y_HIGH = 3
+#const SPRITE_BITMAP = 4
+#const SPRITE_PALETTE = 5
+#const SPRITE_FLAGS = 6
+#const SPRITE_SIZE = 8
+#const SPRITE_ENABLE = 0b0000_0001
+#const SPRITE_FLIP_X = 0b0000_0010
+#const SPRITE_FLIP_Y = 0b0000_0100
+
+#const IO_KEYBOARD_BASE = 0x50
+#const IO_KEYBOARD_DATA = IO_KEYBOARD_BASE + 0
+#const IO_KEYBOARD_STATUS = IO_KEYBOARD_BASE + 1
+#const IO_KEYBOARD_CONTROL = IO_KEYBOARD_BASE + 2
+
+#const KEYBOARD_STATUS_READY = 0b0000_0001
+#const KEYBOARD_CONTROL_IRQ_ENABLE = 0b0000_0001
+#const KEYBOARD_BREAK_PREFIX = 0xF0
+#const KEYBOARD_EXTENDED_PREFIX = 0xE0
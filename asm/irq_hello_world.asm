@@ -37,10 +37,19 @@ spin:
     jmp.abs spin
 
 isr:
-    out [EMULATOR_IRQ_ACK], a
+    ; Save the registers the handler uses, other than the count in `c`, so
+    ; the interrupted code carries on unaffected.
+    push a
+    push x
+
+    ld a, #1 << IRQ_EXTERNAL
+    out [IO_INTC_PENDING], a
     call.abs print_hello_world
     inc c
     out [0x03], c
+
+    pop x
+    pop a
     reti
 
 print_hello_world:
@@ -0,0 +1,742 @@
+use std::collections::HashMap;
+
+use isa::*;
+
+use crate::ast::*;
+use crate::Error;
+
+/// Upper bound on layout passes. Layout only repeats while the size of some
+/// instruction depends on a symbol whose value has not settled yet.
+const MAX_PASSES: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Value {
+    pub value: i64,
+    pub width: Option<u32>,
+}
+
+pub struct Assembly {
+    /// The binary image, starting at address 0.
+    pub bytes: Vec<Byte>,
+    /// Every label and constant, keyed by its fully qualified name.
+    pub symbols: HashMap<String, Value>,
+    /// The names of the labels among `symbols`, in the order they are
+    /// defined.
+    pub labels: Vec<String>,
+}
+
+impl Assembly {
+    pub fn symbol(&self, name: &str) -> Option<i64> {
+        self.symbols.get(name).map(|symbol| symbol.value)
+    }
+
+    /// Lists the labels for tools that name addresses, such as the
+    /// emulator: one per line, as the address in hexadecimal and the name,
+    /// ordered by address.
+    pub fn symbol_file(&self) -> String {
+        let mut labels: Vec<(i64, &str)> = self
+            .labels
+            .iter()
+            .filter_map(|name| Some((self.symbol(name)?, name.as_str())))
+            .filter(|(address, _)| *address <= 0xFFFF)
+            .collect();
+        labels.sort_by_key(|(address, _)| *address);
+
+        labels
+            .iter()
+            .map(|(address, name)| format!("{:04X} {}\n", address, name))
+            .collect()
+    }
+}
+
+/// Assembles a parsed program into a flat binary image starting at address 0.
+///
+/// The first pass lays the program out, assigning an address to every label.
+/// Forward references are unknown during the first layout, so layout repeats
+/// until every symbol settles; the final pass then emits the machine code,
+/// reporting any symbol or operand that is still invalid.
+pub fn assemble(statements: &[(Location, Statement)]) -> Result<Assembly, Error> {
+    let mut symbols = HashMap::new();
+
+    for _ in 0..MAX_PASSES {
+        let layout = Pass::new(&symbols, false).run(statements)?;
+
+        if layout.symbols == symbols {
+            return Pass::new(&symbols, true).run(statements);
+        }
+
+        symbols = layout.symbols;
+    }
+
+    Err(Error {
+        location: statements
+            .first()
+            .map(|(location, _)| location.clone())
+            .unwrap_or(Location {
+                file: String::new(),
+                line: 0,
+            }),
+        message: "symbol addresses did not converge".to_owned(),
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Width {
+    U8,
+    S8,
+    I8,
+    U16,
+    I16,
+}
+
+impl Width {
+    fn range(self) -> std::ops::RangeInclusive<i64> {
+        match self {
+            Width::U8 => 0..=0xFF,
+            Width::S8 => -0x80..=0x7F,
+            Width::I8 => -0x80..=0xFF,
+            Width::U16 => 0..=0xFFFF,
+            Width::I16 => -0x8000..=0xFFFF,
+        }
+    }
+}
+
+/// One encoding an instruction statement may assemble to. Statements like
+/// `jmp label` have several, and the smallest one that fits is chosen.
+struct Form<'a> {
+    operand: Option<(&'a Expression, Width)>,
+    build: Box<dyn Fn(Word) -> Instruction + 'a>,
+}
+
+impl<'a> Form<'a> {
+    fn fixed(instruction: Instruction) -> Self {
+        Self {
+            operand: None,
+            build: Box::new(move |_| instruction),
+        }
+    }
+
+    fn byte(
+        expression: &'a Expression,
+        width: Width,
+        build: impl Fn(Byte) -> Instruction + 'a,
+    ) -> Self {
+        Self {
+            operand: Some((expression, width)),
+            build: Box::new(move |word| build(word as Byte)),
+        }
+    }
+
+    fn word(
+        expression: &'a Expression,
+        width: Width,
+        build: impl Fn(Word) -> Instruction + 'a,
+    ) -> Self {
+        Self {
+            operand: Some((expression, width)),
+            build: Box::new(build),
+        }
+    }
+}
+
+enum MemoryOperand<'a> {
+    /// `[address]`
+    Absolute(&'a Expression),
+    /// `[base, #offset]`
+    Immediate(Register, &'a Expression),
+    /// `[base, offset]`
+    Offset(Register, &'a Expression),
+    /// `[base, register]`
+    Register(Register, Register8),
+}
+
+fn memory_operand(operands: &[Operand]) -> Option<MemoryOperand<'_>> {
+    Some(match operands {
+        [Operand::Value(address)] => MemoryOperand::Absolute(address),
+        [Operand::Register(base), Operand::Immediate(offset)] => {
+            MemoryOperand::Immediate(*base, offset)
+        }
+        [Operand::Register(base), Operand::Value(offset)] => MemoryOperand::Offset(*base, offset),
+        [Operand::Register(base), Operand::Register(Register::Byte(offset))] => {
+            MemoryOperand::Register(*base, *offset)
+        }
+        _ => return None,
+    })
+}
+
+fn pointer(register: Register) -> Option<Pointer> {
+    match register {
+        Register::Word(Register16::X) => Some(Pointer::X),
+        Register::Word(Register16::Y) => Some(Pointer::Y),
+        Register::StackPointer => Some(Pointer::SP),
+        _ => None,
+    }
+}
+
+fn register16(register: Register) -> Option<Register16> {
+    match register {
+        Register::Word(register) => Some(register),
+        _ => None,
+    }
+}
+
+fn condition(name: &str) -> Option<Condition> {
+    Some(match name {
+        "eq" => Condition::Equal,
+        "ne" => Condition::NotEqual,
+        "lt" => Condition::LessThan,
+        "gt" => Condition::GreaterThan,
+        "le" => Condition::LessEqual,
+        "ge" => Condition::GreaterEqual,
+        "lts" => Condition::LessThanSigned,
+        "gts" => Condition::GreaterThanSigned,
+        "les" => Condition::LessEqualSigned,
+        "ges" => Condition::GreaterEqualSigned,
+        _ => return None,
+    })
+}
+
+fn alu2_op(name: &str) -> Option<Alu2Op> {
+    Some(match name {
+        "addc" => Alu2Op::Addc,
+        "subb" => Alu2Op::Subb,
+        "and" => Alu2Op::And,
+        "or" => Alu2Op::Or,
+        "xor" => Alu2Op::Xor,
+        "cmp" => Alu2Op::Cmp,
+        _ => return None,
+    })
+}
+
+fn alu1_op(name: &str) -> Option<Alu1Op> {
+    Some(match name {
+        "shl" => Alu1Op::Shl,
+        "shr" => Alu1Op::Shr,
+        "asr" => Alu1Op::Asr,
+        "not" => Alu1Op::Not,
+        "neg" => Alu1Op::Neg,
+        "inc" => Alu1Op::Inc,
+        "dec" => Alu1Op::Dec,
+        "test" => Alu1Op::Test,
+        _ => return None,
+    })
+}
+
+/// Lists the encodings that `mnemonic operands` can assemble to, following
+/// the syntax of the customasm rules in `bw8.asm`.
+fn forms<'a>(mnemonic: &str, operands: &'a [Operand]) -> Option<Vec<Form<'a>>> {
+    use Instruction as Inst;
+    use Operand::{Immediate as Imm, Indexed, Memory as Mem, Register as Reg, Value};
+    use Register::{Bank, Byte as R8, Pair, StackPointer, UserStackPointer, Word as R16};
+
+    let forms = match (mnemonic, operands) {
+        ("nop", []) => vec![Form::fixed(Inst::Nop)],
+        ("set.c", []) => vec![Form::fixed(Inst::SetCarry)],
+        ("clr.c", []) => vec![Form::fixed(Inst::ClearCarry)],
+        ("set.i", []) => vec![Form::fixed(Inst::SetInterruptEnable)],
+        ("clr.i", []) => vec![Form::fixed(Inst::ClearInterruptEnable)],
+        ("set.b", []) => vec![Form::fixed(Inst::SetBankEnable)],
+        ("clr.b", []) => vec![Form::fixed(Inst::ClearBankEnable)],
+
+        ("mv", [Reg(R8(Register8::A)), Reg(Bank)]) => vec![Form::fixed(Inst::ReadBankRegister)],
+        ("mv", [Reg(Bank), Reg(R8(Register8::A))]) => vec![Form::fixed(Inst::WriteBankRegister)],
+        ("mv", [Reg(R8(dst)), Reg(R8(src))]) => vec![Form::fixed(Inst::Move8(*dst, *src))],
+        ("mv", [Reg(R16(Register16::X)), Reg(StackPointer)]) => {
+            vec![Form::fixed(Inst::ReadStackPointer)]
+        }
+        ("mv", [Reg(StackPointer), Reg(R16(Register16::X))]) => {
+            vec![Form::fixed(Inst::WriteStackPointer)]
+        }
+        ("mv", [Reg(R16(Register16::X)), Reg(UserStackPointer)]) => {
+            vec![Form::fixed(Inst::ReadUserStackPointer)]
+        }
+        ("mv", [Reg(UserStackPointer), Reg(R16(Register16::X))]) => {
+            vec![Form::fixed(Inst::WriteUserStackPointer)]
+        }
+        // `mv x, x` and `mv y, y` have no encoding of their own.
+        ("mv", [Reg(R16(dst)), Reg(R16(src))]) if dst != src => {
+            vec![Form::fixed(Inst::Move16(*dst, *src))]
+        }
+        ("mv", [Reg(R16(dst)), Reg(Pair(src))]) => {
+            vec![Form::fixed(Inst::Move16FromPair(*dst, *src))]
+        }
+        ("mv", [Reg(Pair(dst)), Reg(R16(src))]) => {
+            vec![Form::fixed(Inst::Move16ToPair(*dst, *src))]
+        }
+
+        ("ld", [Reg(R8(dst)), Imm(value)]) => {
+            let dst = *dst;
+            vec![Form::byte(value, Width::I8, move |byte| {
+                Inst::Load8Immediate(dst, byte)
+            })]
+        }
+        ("ld", [Reg(R8(dst)), Mem(address)]) => {
+            let dst = *dst;
+            match memory_operand(address)? {
+                MemoryOperand::Absolute(address) => {
+                    vec![Form::word(address, Width::U16, move |word| {
+                        Inst::Load8(dst, Memory8Mode::Absolute(word))
+                    })]
+                }
+                MemoryOperand::Immediate(base, offset) => {
+                    let base = pointer(base)?;
+                    vec![Form::byte(offset, Width::S8, move |byte| {
+                        Inst::Load8(dst, Memory8Mode::ConstantOffset(base, byte))
+                    })]
+                }
+                MemoryOperand::Register(base, offset) => vec![Form::fixed(Inst::Load8(
+                    dst,
+                    Memory8Mode::RegisterOffset(pointer(base)?, offset),
+                ))],
+                MemoryOperand::Offset(..) => return None,
+            }
+        }
+        ("ld", [Reg(R16(dst)), Imm(value)]) => {
+            let dst = *dst;
+            vec![Form::word(value, Width::I16, move |word| {
+                Inst::Load16Immediate(dst, word)
+            })]
+        }
+        ("ld", [Reg(R16(dst)), Mem(address)]) => {
+            let dst = *dst;
+            match memory_operand(address)? {
+                MemoryOperand::Absolute(address) => {
+                    vec![Form::word(address, Width::U16, move |word| {
+                        Inst::Load16(dst, Memory16Mode::Absolute(word))
+                    })]
+                }
+                MemoryOperand::Offset(base, offset) => {
+                    let base = pointer(base)?;
+                    vec![Form::byte(offset, Width::S8, move |byte| {
+                        Inst::Load16(dst, Memory16Mode::ConstantOffset(base, byte))
+                    })]
+                }
+                _ => return None,
+            }
+        }
+
+        ("st", [Mem(address), Reg(R8(src))]) => {
+            let src = *src;
+            match memory_operand(address)? {
+                MemoryOperand::Absolute(address) => {
+                    vec![Form::word(address, Width::U16, move |word| {
+                        Inst::Store8(Memory8Mode::Absolute(word), src)
+                    })]
+                }
+                MemoryOperand::Immediate(base, offset) => {
+                    let base = pointer(base)?;
+                    vec![Form::byte(offset, Width::S8, move |byte| {
+                        Inst::Store8(Memory8Mode::ConstantOffset(base, byte), src)
+                    })]
+                }
+                MemoryOperand::Register(base, offset) => vec![Form::fixed(Inst::Store8(
+                    Memory8Mode::RegisterOffset(pointer(base)?, offset),
+                    src,
+                ))],
+                MemoryOperand::Offset(..) => return None,
+            }
+        }
+        ("st", [Mem(address), Reg(R16(src))]) => {
+            let src = *src;
+            match memory_operand(address)? {
+                MemoryOperand::Absolute(address) => {
+                    vec![Form::word(address, Width::U16, move |word| {
+                        Inst::Store16(Memory16Mode::Absolute(word), src)
+                    })]
+                }
+                MemoryOperand::Offset(base, offset) => {
+                    let base = pointer(base)?;
+                    vec![Form::byte(offset, Width::S8, move |byte| {
+                        Inst::Store16(Memory16Mode::ConstantOffset(base, byte), src)
+                    })]
+                }
+                _ => return None,
+            }
+        }
+
+        ("in", [Reg(R8(dst)), Mem(address)]) => {
+            let dst = *dst;
+            match memory_operand(address)? {
+                MemoryOperand::Absolute(port) => vec![Form::byte(port, Width::U8, move |byte| {
+                    Inst::In(dst, IOMode::Port(byte))
+                })],
+                MemoryOperand::Offset(base, offset) => {
+                    let base = register16(base)?;
+                    vec![Form::byte(offset, Width::S8, move |byte| {
+                        Inst::In(dst, IOMode::ConstantOffset(base, byte))
+                    })]
+                }
+                MemoryOperand::Register(base, offset) => vec![Form::fixed(Inst::In(
+                    dst,
+                    IOMode::RegisterOffset(register16(base)?, offset),
+                ))],
+                MemoryOperand::Immediate(..) => return None,
+            }
+        }
+        ("out", [Mem(address), Reg(R8(src))]) => {
+            let src = *src;
+            match memory_operand(address)? {
+                MemoryOperand::Absolute(port) => vec![Form::byte(port, Width::U8, move |byte| {
+                    Inst::Out(IOMode::Port(byte), src)
+                })],
+                MemoryOperand::Offset(base, offset) => {
+                    let base = register16(base)?;
+                    vec![Form::byte(offset, Width::S8, move |byte| {
+                        Inst::Out(IOMode::ConstantOffset(base, byte), src)
+                    })]
+                }
+                MemoryOperand::Register(base, offset) => vec![Form::fixed(Inst::Out(
+                    IOMode::RegisterOffset(register16(base)?, offset),
+                    src,
+                ))],
+                MemoryOperand::Immediate(..) => return None,
+            }
+        }
+
+        ("lea", [Mem(address)]) => match memory_operand(address)? {
+            MemoryOperand::Offset(base, offset) => {
+                let base = pointer(base)?;
+                vec![Form::byte(offset, Width::S8, move |byte| {
+                    Inst::Lea(base, LeaMode::Constant(byte))
+                })]
+            }
+            MemoryOperand::Register(base, offset) => vec![Form::fixed(Inst::Lea(
+                pointer(base)?,
+                LeaMode::Register(offset),
+            ))],
+            _ => return None,
+        },
+
+        ("inc", [Reg(R16(dst))]) => vec![Form::fixed(Inst::Inc16(*dst))],
+        ("dec", [Reg(R16(dst))]) => vec![Form::fixed(Inst::Dec16(*dst))],
+
+        ("push", [Reg(R8(src))]) => vec![Form::fixed(Inst::Push8(*src))],
+        ("push", [Reg(R16(src))]) => vec![Form::fixed(Inst::Push16(*src))],
+        ("pop", [Reg(R8(dst))]) => vec![Form::fixed(Inst::Pop8(*dst))],
+        ("pop", [Reg(R16(dst))]) => vec![Form::fixed(Inst::Pop16(*dst))],
+
+        ("ret", []) => vec![Form::fixed(Inst::Ret)],
+        ("swi", []) => vec![Form::fixed(Inst::Swi)],
+        ("reti", []) => vec![Form::fixed(Inst::Reti)],
+
+        (mnemonic, [Reg(R8(dst)), operand]) if alu2_op(mnemonic).is_some() => {
+            let op = alu2_op(mnemonic)?;
+            let dst = *dst;
+            match operand {
+                Reg(R8(src)) => vec![Form::fixed(Inst::Alu2(op, dst, Alu2OpMode::Register(*src)))],
+                Value(value) => vec![Form::byte(value, Width::I8, move |byte| {
+                    Inst::Alu2(op, dst, Alu2OpMode::Constant(byte))
+                })],
+                _ => return None,
+            }
+        }
+        (mnemonic, [Reg(R8(dst))]) if alu1_op(mnemonic).is_some() => {
+            vec![Form::fixed(Inst::Alu1(alu1_op(mnemonic)?, *dst))]
+        }
+
+        (mnemonic, operands) => {
+            // Control flow: `call`, `jmp` and `br.<condition>`, each optionally
+            // suffixed with `.abs` to force the absolute addressing form.
+            let (mnemonic, absolute_only) = match mnemonic.strip_suffix(".abs") {
+                Some(mnemonic) => (mnemonic, true),
+                None => (mnemonic, false),
+            };
+
+            let build: Box<dyn Fn(JumpMode) -> Instruction> = match mnemonic {
+                "call" => Box::new(Inst::Call),
+                "jmp" => Box::new(|mode| Inst::Jmp(Condition::Always, mode)),
+                _ => {
+                    let condition = condition(mnemonic.strip_prefix("br.")?)?;
+                    Box::new(move |mode| Inst::Jmp(condition, mode))
+                }
+            };
+            let build: std::rc::Rc<dyn Fn(JumpMode) -> Instruction> = build.into();
+
+            match operands {
+                [Value(target)] => {
+                    let absolute = {
+                        let build = build.clone();
+                        Form::word(target, Width::U16, move |word| {
+                            build(JumpMode::Absolute(word))
+                        })
+                    };
+
+                    if absolute_only {
+                        vec![absolute]
+                    } else {
+                        vec![
+                            Form::byte(target, Width::S8, move |byte| {
+                                build(JumpMode::Relative(byte))
+                            }),
+                            absolute,
+                        ]
+                    }
+                }
+                [Indexed(index)] if !absolute_only => match index.as_slice() {
+                    [Reg(R16(base)), Value(offset)] => {
+                        let base = *base;
+                        vec![Form::byte(offset, Width::S8, move |byte| {
+                            build(JumpMode::Indirect(base, byte))
+                        })]
+                    }
+                    _ => return None,
+                },
+                _ => return None,
+            }
+        }
+    };
+
+    Some(forms)
+}
+
+struct Pass<'a> {
+    previous: &'a HashMap<String, Value>,
+    symbols: HashMap<String, Value>,
+    labels: Vec<String>,
+    bytes: Vec<Byte>,
+    address: usize,
+    emit: bool,
+}
+
+impl<'a> Pass<'a> {
+    fn new(previous: &'a HashMap<String, Value>, emit: bool) -> Self {
+        Self {
+            previous,
+            symbols: HashMap::new(),
+            labels: Vec::new(),
+            bytes: Vec::new(),
+            address: 0,
+            emit,
+        }
+    }
+
+    fn run(mut self, statements: &[(Location, Statement)]) -> Result<Assembly, Error> {
+        for (location, statement) in statements {
+            self.statement(statement).map_err(|message| Error {
+                location: location.clone(),
+                message,
+            })?;
+        }
+
+        Ok(Assembly {
+            bytes: self.bytes,
+            symbols: self.symbols,
+            labels: self.labels,
+        })
+    }
+
+    fn statement(&mut self, statement: &Statement) -> Result<(), String> {
+        match statement {
+            Statement::Label(name) => {
+                self.labels.push(name.clone());
+                self.define(
+                    name,
+                    Value {
+                        value: self.address as i64,
+                        width: None,
+                    },
+                )
+            }
+            Statement::Constant(name, expression) => match self.evaluate(expression)? {
+                Some(value) => self.define(name, value),
+                None => Ok(()),
+            },
+            Statement::Address(expression) => {
+                let Some(Value { value, .. }) = self.evaluate(expression)? else {
+                    return Ok(());
+                };
+
+                if value < self.address as i64 {
+                    return Err(format!(
+                        "`#addr` moves backwards from 0x{:04x} to 0x{:04x}",
+                        self.address, value
+                    ));
+                }
+                if value > 0x1_0000 {
+                    return Err(format!("address 0x{:x} is out of range", value));
+                }
+
+                self.address = value as usize;
+                Ok(())
+            }
+            Statement::Data(width, items) => {
+                for item in items {
+                    match item {
+                        DataItem::Bytes(bytes) => self.write(bytes)?,
+                        DataItem::Value(expression) => {
+                            let value = self.evaluate(expression)?;
+
+                            let Some(width) = width.or(value.and_then(|value| value.width)) else {
+                                if value.is_none() {
+                                    continue;
+                                }
+                                return Err("cannot infer the width of data; \
+                                    use a sized literal or an explicit `#d8`/`#d16`"
+                                    .to_owned());
+                            };
+
+                            if width % 8 != 0 {
+                                return Err(format!(
+                                    "data width of {} bits is not whole bytes",
+                                    width
+                                ));
+                            }
+
+                            let value = value.map_or(0, |value| value.value);
+                            let bytes: Vec<Byte> = (0..width / 8)
+                                .rev()
+                                .map(|index| (value >> (8 * index)) as Byte)
+                                .collect();
+
+                            self.write(&bytes)?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+            Statement::Instruction(mnemonic, operands) => {
+                let bytes = self.instruction(mnemonic, operands)?;
+                self.write(&bytes.to_vec())
+            }
+        }
+    }
+
+    fn instruction(
+        &mut self,
+        mnemonic: &str,
+        operands: &[Operand],
+    ) -> Result<InstructionBytes, String> {
+        let forms = forms(mnemonic, operands)
+            .ok_or_else(|| format!("invalid instruction or operands for `{}`", mnemonic))?;
+
+        let mut candidates = Vec::new();
+        let mut resolved = true;
+
+        for form in forms.iter() {
+            let word = match form.operand {
+                None => 0,
+                Some((expression, width)) => match self.evaluate(expression)? {
+                    Some(Value { value, .. }) if width.range().contains(&value) => value as Word,
+                    Some(_) => continue,
+                    None => {
+                        resolved = false;
+                        0
+                    }
+                },
+            };
+
+            candidates.push(encode((form.build)(word)));
+        }
+
+        // Until every operand is known, assume the largest encoding so the
+        // layout only ever shrinks between passes.
+        let candidate = if resolved {
+            candidates
+                .into_iter()
+                .min_by_key(|bytes| bytes.to_vec().len())
+        } else {
+            candidates
+                .into_iter()
+                .max_by_key(|bytes| bytes.to_vec().len())
+        };
+
+        match candidate {
+            Some(bytes) => Ok(bytes),
+            None if !self.emit => Ok(encode((forms.last().unwrap().build)(0))),
+            None => Err(format!("operand out of range for `{}`", mnemonic)),
+        }
+    }
+
+    fn define(&mut self, name: &str, value: Value) -> Result<(), String> {
+        match self.symbols.insert(name.to_owned(), value) {
+            Some(_) => Err(format!("`{}` is defined more than once", name)),
+            None => Ok(()),
+        }
+    }
+
+    fn write(&mut self, bytes: &[Byte]) -> Result<(), String> {
+        if self.address + bytes.len() > 0x1_0000 {
+            return Err("program does not fit in the address space".to_owned());
+        }
+
+        if self.bytes.len() < self.address {
+            self.bytes.resize(self.address, 0x00);
+        }
+        self.bytes.extend_from_slice(bytes);
+        self.address += bytes.len();
+
+        Ok(())
+    }
+
+    /// Evaluates an expression. During layout, expressions that depend on a
+    /// symbol which has not been defined yet evaluate to `None`.
+    fn evaluate(&self, expression: &Expression) -> Result<Option<Value>, String> {
+        let value = match expression {
+            Expression::Integer(value, width) => Value {
+                value: *value,
+                width: *width,
+            },
+            Expression::Symbol(name) => {
+                match self.symbols.get(name).or_else(|| self.previous.get(name)) {
+                    Some(value) => *value,
+                    None if self.emit => return Err(format!("unknown symbol `{}`", name)),
+                    None => return Ok(None),
+                }
+            }
+            Expression::Unary(op, operand) => {
+                let Some(Value { value, .. }) = self.evaluate(operand)? else {
+                    return Ok(None);
+                };
+
+                Value {
+                    value: match op {
+                        UnaryOp::Negate => value.wrapping_neg(),
+                        UnaryOp::Not => !value,
+                    },
+                    width: None,
+                }
+            }
+            Expression::Binary(op, lhs, rhs) => {
+                let (Some(lhs), Some(rhs)) = (self.evaluate(lhs)?, self.evaluate(rhs)?) else {
+                    return Ok(None);
+                };
+                let (lhs, rhs) = (lhs.value, rhs.value);
+
+                Value {
+                    value: match op {
+                        BinaryOp::Add => lhs.wrapping_add(rhs),
+                        BinaryOp::Subtract => lhs.wrapping_sub(rhs),
+                        BinaryOp::Multiply => lhs.wrapping_mul(rhs),
+                        BinaryOp::Divide => lhs
+                            .checked_div(rhs)
+                            .ok_or_else(|| "division by zero".to_owned())?,
+                        BinaryOp::Remainder => lhs
+                            .checked_rem(rhs)
+                            .ok_or_else(|| "division by zero".to_owned())?,
+                        BinaryOp::And => lhs & rhs,
+                        BinaryOp::Or => lhs | rhs,
+                        BinaryOp::Xor => lhs ^ rhs,
+                        BinaryOp::ShiftLeft => lhs.wrapping_shl(rhs as u32),
+                        BinaryOp::ShiftRight => lhs.wrapping_shr(rhs as u32),
+                    },
+                    width: None,
+                }
+            }
+            Expression::Sized(operand, width) => {
+                let Some(Value { value, .. }) = self.evaluate(operand)? else {
+                    return Ok(None);
+                };
+
+                Value {
+                    value: match width {
+                        64.. => value,
+                        width => value & ((1 << width) - 1),
+                    },
+                    width: Some(*width),
+                }
+            }
+        };
+
+        Ok(Some(value))
+    }
+}
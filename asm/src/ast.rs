@@ -0,0 +1,103 @@
+use isa::{Register16, Register8, RegisterPair};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Location {
+    pub file: String,
+    pub line: usize,
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UnaryOp {
+    Negate,
+    Not,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BinaryOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    And,
+    Or,
+    Xor,
+    ShiftLeft,
+    ShiftRight,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expression {
+    /// An integer literal, along with the width implied by its digits if it has one.
+    Integer(i64, Option<u32>),
+    /// A fully qualified symbol name; local labels have already been scoped.
+    Symbol(String),
+    Unary(UnaryOp, Box<Expression>),
+    Binary(BinaryOp, Box<Expression>, Box<Expression>),
+    /// `value`width`, truncating the value to the given number of bits.
+    Sized(Box<Expression>, u32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Register {
+    Byte(Register8),
+    Word(Register16),
+    Pair(RegisterPair),
+    StackPointer,
+    UserStackPointer,
+    Bank,
+}
+
+impl Register {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "a" => Self::Byte(Register8::A),
+            "b" => Self::Byte(Register8::B),
+            "c" => Self::Byte(Register8::C),
+            "d" => Self::Byte(Register8::D),
+            "x" => Self::Word(Register16::X),
+            "y" => Self::Word(Register16::Y),
+            "ab" => Self::Pair(RegisterPair::Ab),
+            "cd" => Self::Pair(RegisterPair::Cd),
+            "sp" => Self::StackPointer,
+            "usp" => Self::UserStackPointer,
+            "br" => Self::Bank,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Operand {
+    Register(Register),
+    /// `#expression`
+    Immediate(Expression),
+    /// A bare expression, such as a port, an offset or a jump target.
+    Value(Expression),
+    /// `[...]`
+    Memory(Vec<Operand>),
+    /// `(register, offset)`
+    Indexed(Vec<Operand>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DataItem {
+    Bytes(Vec<u8>),
+    Value(Expression),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Statement {
+    Label(String),
+    Constant(String, Expression),
+    Address(Expression),
+    /// `#d`, with the explicit element width of `#d8`/`#d16`/... if one was given.
+    Data(Option<u32>, Vec<DataItem>),
+    Instruction(String, Vec<Operand>),
+}
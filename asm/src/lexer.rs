@@ -0,0 +1,206 @@
+#[derive(Clone, Debug, PartialEq)]
+pub enum Token {
+    Identifier(String),
+    Integer(i64, Option<u32>),
+    String(Vec<u8>),
+    Hash,
+    Comma,
+    Colon,
+    At,
+    Backtick,
+    Equals,
+    Bang,
+    Tilde,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Ampersand,
+    Pipe,
+    Caret,
+    Less,
+    Greater,
+    ShiftLeft,
+    ShiftRight,
+    LeftParen,
+    RightParen,
+    LeftBracket,
+    RightBracket,
+    LeftBrace,
+    RightBrace,
+    Newline,
+}
+
+pub struct Lexer<'a> {
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+    source: &'a str,
+    line: usize,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Self {
+            chars: source.char_indices().peekable(),
+            source,
+            line: 1,
+        }
+    }
+
+    /// Tokenizes the whole source, pairing every token with the line it starts on.
+    pub fn tokenize(mut self) -> Result<Vec<(usize, Token)>, (usize, String)> {
+        let mut tokens = Vec::new();
+
+        while let Some(token) = self.next_token()? {
+            tokens.push(token);
+        }
+
+        Ok(tokens)
+    }
+
+    fn next_token(&mut self) -> Result<Option<(usize, Token)>, (usize, String)> {
+        loop {
+            match self.chars.peek() {
+                Some((_, ';')) => {
+                    while !matches!(self.chars.peek(), None | Some((_, '\n'))) {
+                        self.chars.next();
+                    }
+                }
+                Some((_, c)) if *c != '\n' && c.is_whitespace() => {
+                    self.chars.next();
+                }
+                _ => break,
+            }
+        }
+
+        let Some((start, c)) = self.chars.next() else {
+            return Ok(None);
+        };
+
+        let line = self.line;
+
+        let token = match c {
+            '\n' => {
+                self.line += 1;
+                Token::Newline
+            }
+            '#' => Token::Hash,
+            ',' => Token::Comma,
+            ':' => Token::Colon,
+            '@' => Token::At,
+            '`' => Token::Backtick,
+            '=' => Token::Equals,
+            '!' => Token::Bang,
+            '~' => Token::Tilde,
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '*' => Token::Star,
+            '/' => Token::Slash,
+            '%' => Token::Percent,
+            '&' => Token::Ampersand,
+            '|' => Token::Pipe,
+            '^' => Token::Caret,
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            '[' => Token::LeftBracket,
+            ']' => Token::RightBracket,
+            '{' => Token::LeftBrace,
+            '}' => Token::RightBrace,
+            '<' => match self.chars.next_if(|(_, c)| *c == '<') {
+                Some(_) => Token::ShiftLeft,
+                None => Token::Less,
+            },
+            '>' => match self.chars.next_if(|(_, c)| *c == '>') {
+                Some(_) => Token::ShiftRight,
+                None => Token::Greater,
+            },
+            '"' => Token::String(self.string(line)?),
+            '0'..='9' => {
+                let end = self.take_while(|c| c.is_ascii_alphanumeric() || c == '_');
+                let (value, width) = parse_integer(&self.source[start..end]).ok_or_else(|| {
+                    (
+                        line,
+                        format!("invalid integer `{}`", &self.source[start..end]),
+                    )
+                })?;
+                Token::Integer(value, width)
+            }
+            c if c.is_ascii_alphabetic() || c == '_' || c == '.' => {
+                let end = self.take_while(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+                Token::Identifier(self.source[start..end].to_owned())
+            }
+            c => return Err((line, format!("unexpected character `{}`", c))),
+        };
+
+        Ok(Some((line, token)))
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> usize {
+        while self.chars.next_if(|(_, c)| predicate(*c)).is_some() {}
+
+        match self.chars.peek() {
+            Some((index, _)) => *index,
+            None => self.source.len(),
+        }
+    }
+
+    fn string(&mut self, line: usize) -> Result<Vec<u8>, (usize, String)> {
+        let mut bytes = Vec::new();
+
+        loop {
+            let c = match self.chars.next() {
+                Some((_, '"')) => return Ok(bytes),
+                Some((_, '\n')) | None => return Err((line, "unterminated string".to_owned())),
+                Some((_, '\\')) => match self.chars.next() {
+                    Some((_, '0')) => '\0',
+                    Some((_, 'n')) => '\n',
+                    Some((_, 'r')) => '\r',
+                    Some((_, 't')) => '\t',
+                    Some((_, '\\')) => '\\',
+                    Some((_, '"')) => '"',
+                    Some((_, '\'')) => '\'',
+                    Some((_, 'x')) => {
+                        let mut digits = String::new();
+                        for _ in 0..2 {
+                            if let Some((_, c)) = self.chars.next_if(|(_, c)| c.is_ascii_hexdigit())
+                            {
+                                digits.push(c);
+                            }
+                        }
+                        match u8::from_str_radix(&digits, 16) {
+                            Ok(byte) => {
+                                bytes.push(byte);
+                                continue;
+                            }
+                            Err(_) => return Err((line, "invalid `\\x` escape".to_owned())),
+                        }
+                    }
+                    _ => return Err((line, "invalid escape sequence".to_owned())),
+                },
+                Some((_, c)) => c,
+            };
+
+            let mut buffer = [0; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+        }
+    }
+}
+
+/// Parses a numeric literal. Hexadecimal and binary literals also carry the
+/// bit width implied by their digit count, which `#d` uses to size its output.
+fn parse_integer(text: &str) -> Option<(i64, Option<u32>)> {
+    let text = text.replace('_', "");
+
+    let (digits, radix, bits_per_digit) = if let Some(digits) = text.strip_prefix("0x") {
+        (digits, 16, Some(4))
+    } else if let Some(digits) = text.strip_prefix("0b") {
+        (digits, 2, Some(1))
+    } else {
+        (text.as_str(), 10, None)
+    };
+
+    let value = i64::from_str_radix(digits, radix).ok()?;
+    let width = bits_per_digit.map(|bits| bits * digits.len() as u32);
+
+    Some((value, width))
+}
@@ -0,0 +1,36 @@
+mod assembler;
+mod ast;
+mod lexer;
+mod parser;
+
+use std::path::Path;
+
+pub use assembler::{Assembly, Value};
+pub use ast::Location;
+
+#[derive(Debug)]
+pub struct Error {
+    pub location: Location,
+    pub message: String,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Assembles a source file, along with everything it `#include`s.
+pub fn assemble_file(path: &Path) -> Result<Assembly, Error> {
+    let statements = parser::Parser::new().parse(path)?;
+    assembler::assemble(&statements)
+}
+
+/// Assembles source text held in memory. `path` names the source in errors and
+/// is where relative `#include`s are resolved from; it need not exist.
+pub fn assemble_source(path: &Path, source: &str) -> Result<Assembly, Error> {
+    let statements = parser::Parser::new().parse_str(path, source)?;
+    assembler::assemble(&statements)
+}
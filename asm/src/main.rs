@@ -1,8 +1,46 @@
-pub enum Statement {
-    
+use std::path::PathBuf;
+
+fn usage() -> ! {
+    eprintln!("usage: asm <source> [-o <output>] [-s <symbols>]");
+    std::process::exit(2);
 }
 
 fn main() {
-    let path = std::env::args().nth(1).unwrap();
-    println!("{}", std::fs::read_to_string(path).unwrap());
+    let mut args = std::env::args().skip(1);
+    let mut source = None;
+    let mut output = None;
+    let mut symbols = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" => output = Some(PathBuf::from(args.next().unwrap_or_else(|| usage()))),
+            "-s" => symbols = Some(PathBuf::from(args.next().unwrap_or_else(|| usage()))),
+            _ if source.is_none() => source = Some(PathBuf::from(arg)),
+            _ => usage(),
+        }
+    }
+
+    let source = source.unwrap_or_else(|| usage());
+    let output = output.unwrap_or_else(|| source.with_extension("bin"));
+
+    let assembly = asm::assemble_file(&source);
+
+    match assembly {
+        Ok(assembly) => {
+            if let Err(error) = std::fs::write(&output, &assembly.bytes) {
+                eprintln!("error: {}: {}", output.display(), error);
+                std::process::exit(1);
+            }
+            if let Some(symbols) = symbols {
+                if let Err(error) = std::fs::write(&symbols, assembly.symbol_file()) {
+                    eprintln!("error: {}: {}", symbols.display(), error);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Err(error) => {
+            eprintln!("error: {}", error);
+            std::process::exit(1);
+        }
+    }
 }
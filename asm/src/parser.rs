@@ -0,0 +1,452 @@
+use std::path::{Path, PathBuf};
+
+use crate::ast::*;
+use crate::lexer::{Lexer, Token};
+use crate::Error;
+
+pub struct Parser {
+    statements: Vec<(Location, Statement)>,
+    /// The enclosing label names; local labels (`.name`) nest one level below
+    /// the closest label with one fewer leading dot.
+    scope: Vec<String>,
+    includes: Vec<PathBuf>,
+}
+
+struct Cursor {
+    tokens: Vec<(usize, Token)>,
+    position: usize,
+    file: String,
+}
+
+impl Cursor {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(_, token)| token)
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<&Token> {
+        self.tokens.get(self.position + n).map(|(_, token)| token)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .map(|(_, token)| token.clone());
+        self.position += 1;
+        token
+    }
+
+    fn next_if(&mut self, expected: &Token) -> bool {
+        if self.peek() == Some(expected) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn at_end_of_line(&self) -> bool {
+        matches!(self.peek(), None | Some(Token::Newline))
+    }
+
+    fn location(&self) -> Location {
+        let line = match self.tokens.get(self.position) {
+            Some((line, _)) => *line,
+            None => self.tokens.last().map_or(1, |(line, _)| *line),
+        };
+
+        Location {
+            file: self.file.clone(),
+            line,
+        }
+    }
+
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, Error> {
+        Err(Error {
+            location: self.location(),
+            message: message.into(),
+        })
+    }
+
+    fn expect(&mut self, expected: Token, description: &str) -> Result<(), Error> {
+        if self.next_if(&expected) {
+            Ok(())
+        } else {
+            self.error(format!("expected {}", description))
+        }
+    }
+
+    fn expect_identifier(&mut self) -> Result<String, Error> {
+        match self.peek() {
+            Some(Token::Identifier(name)) => {
+                let name = name.clone();
+                self.position += 1;
+                Ok(name)
+            }
+            _ => self.error("expected identifier"),
+        }
+    }
+
+    fn expect_end_of_line(&mut self) -> Result<(), Error> {
+        match self.next() {
+            None | Some(Token::Newline) => Ok(()),
+            _ => {
+                self.position -= 1;
+                self.error("expected end of line")
+            }
+        }
+    }
+}
+
+impl Parser {
+    pub fn new() -> Self {
+        Self {
+            statements: Vec::new(),
+            scope: Vec::new(),
+            includes: Vec::new(),
+        }
+    }
+
+    /// Parses a source file, along with everything it `#include`s, into a flat
+    /// list of statements.
+    pub fn parse(self, path: &Path) -> Result<Vec<(Location, Statement)>, Error> {
+        let source = std::fs::read_to_string(path).map_err(|error| Error {
+            location: Location {
+                file: path.display().to_string(),
+                line: 0,
+            },
+            message: error.to_string(),
+        })?;
+
+        self.parse_str(path, &source)
+    }
+
+    /// Parses source text that has already been read. `path` names the source
+    /// in errors and is where relative `#include`s are resolved from.
+    pub fn parse_str(
+        mut self,
+        path: &Path,
+        source: &str,
+    ) -> Result<Vec<(Location, Statement)>, Error> {
+        self.parse_source(path, source)?;
+        Ok(self.statements)
+    }
+
+    fn parse_source(&mut self, path: &Path, source: &str) -> Result<(), Error> {
+        let file = path.display().to_string();
+
+        let tokens = Lexer::new(source)
+            .tokenize()
+            .map_err(|(line, message)| Error {
+                location: Location {
+                    file: file.clone(),
+                    line,
+                },
+                message,
+            })?;
+
+        let mut cursor = Cursor {
+            tokens,
+            position: 0,
+            file,
+        };
+
+        self.includes.push(path.to_owned());
+
+        loop {
+            match cursor.peek() {
+                None => break,
+                Some(Token::Newline) => {
+                    cursor.next();
+                }
+                Some(Token::Hash) => {
+                    cursor.next();
+                    self.directive(&mut cursor, path)?;
+                }
+                Some(Token::Identifier(_)) if cursor.peek_nth(1) == Some(&Token::Colon) => {
+                    let location = cursor.location();
+                    let name = cursor.expect_identifier()?;
+                    cursor.next();
+
+                    let name = self.define_label(&cursor, &name)?;
+                    self.statements.push((location, Statement::Label(name)));
+                }
+                Some(Token::Identifier(_)) => {
+                    let location = cursor.location();
+                    let mnemonic = cursor.expect_identifier()?;
+
+                    let mut operands = Vec::new();
+                    if !cursor.at_end_of_line() {
+                        operands.push(self.operand(&mut cursor)?);
+                        while cursor.next_if(&Token::Comma) {
+                            operands.push(self.operand(&mut cursor)?);
+                        }
+                    }
+                    cursor.expect_end_of_line()?;
+
+                    self.statements
+                        .push((location, Statement::Instruction(mnemonic, operands)));
+                }
+                Some(_) => return cursor.error("expected label, instruction or directive"),
+            }
+        }
+
+        self.includes.pop();
+        Ok(())
+    }
+
+    fn directive(&mut self, cursor: &mut Cursor, path: &Path) -> Result<(), Error> {
+        let location = cursor.location();
+        let name = cursor.expect_identifier()?;
+
+        let statement = match name.as_str() {
+            "include" => {
+                let Some(Token::String(bytes)) = cursor.next() else {
+                    return cursor.error("expected file name");
+                };
+                cursor.expect_end_of_line()?;
+
+                let included = path
+                    .parent()
+                    .unwrap_or(Path::new(""))
+                    .join(String::from_utf8_lossy(&bytes).as_ref());
+
+                if self.includes.contains(&included) {
+                    return cursor.error(format!("recursive include of `{}`", included.display()));
+                }
+
+                let source = std::fs::read_to_string(&included).map_err(|error| Error {
+                    location: location.clone(),
+                    message: format!("cannot read `{}`: {}", included.display(), error),
+                })?;
+
+                return self.parse_source(&included, &source);
+            }
+            "ruledef" => {
+                // Instruction rules are built into the assembler, so the
+                // customasm rule definitions are skipped over entirely.
+                while !matches!(cursor.peek(), None | Some(Token::LeftBrace)) {
+                    cursor.next();
+                }
+                cursor.expect(Token::LeftBrace, "`{`")?;
+
+                let mut depth = 1;
+                while depth > 0 {
+                    match cursor.next() {
+                        Some(Token::LeftBrace) => depth += 1,
+                        Some(Token::RightBrace) => depth -= 1,
+                        Some(_) => {}
+                        None => return cursor.error("unterminated `#ruledef`"),
+                    }
+                }
+                return Ok(());
+            }
+            "const" => {
+                if cursor.next_if(&Token::LeftParen) {
+                    cursor.expect_identifier()?;
+                    cursor.expect(Token::RightParen, "`)`")?;
+                }
+
+                let name = cursor.expect_identifier()?;
+                let name = self.qualify(cursor, &name)?;
+                cursor.expect(Token::Equals, "`=`")?;
+
+                Statement::Constant(name, self.expression(cursor)?)
+            }
+            "addr" => Statement::Address(self.expression(cursor)?),
+            "d" | "d8" | "d16" | "d24" | "d32" | "d64" => {
+                let width = name[1..].parse().ok();
+
+                let mut items = vec![self.data_item(cursor)?];
+                while cursor.next_if(&Token::Comma) {
+                    items.push(self.data_item(cursor)?);
+                }
+
+                Statement::Data(width, items)
+            }
+            _ => return cursor.error(format!("unsupported directive `#{}`", name)),
+        };
+
+        cursor.expect_end_of_line()?;
+        self.statements.push((location, statement));
+
+        Ok(())
+    }
+
+    fn data_item(&self, cursor: &mut Cursor) -> Result<DataItem, Error> {
+        if let Some(Token::String(bytes)) = cursor.peek() {
+            let bytes = bytes.clone();
+            cursor.next();
+            Ok(DataItem::Bytes(bytes))
+        } else {
+            Ok(DataItem::Value(self.expression(cursor)?))
+        }
+    }
+
+    fn define_label(&mut self, cursor: &Cursor, name: &str) -> Result<String, Error> {
+        let depth = name.chars().take_while(|c| *c == '.').count();
+
+        if self.scope.len() < depth {
+            return cursor.error(format!("local label `{}` has no parent label", name));
+        }
+
+        self.scope.truncate(depth);
+        self.scope.push(name[depth..].to_owned());
+
+        Ok(self.scope.join("."))
+    }
+
+    /// Expands a local (leading `.`) name into its fully qualified form.
+    fn qualify(&self, cursor: &Cursor, name: &str) -> Result<String, Error> {
+        let depth = name.chars().take_while(|c| *c == '.').count();
+
+        if depth == 0 {
+            return Ok(name.to_owned());
+        }
+
+        if self.scope.len() < depth {
+            return cursor.error(format!("local name `{}` has no parent label", name));
+        }
+
+        let mut components = self.scope[..depth].to_vec();
+        components.push(name[depth..].to_owned());
+
+        Ok(components.join("."))
+    }
+
+    fn operand(&self, cursor: &mut Cursor) -> Result<Operand, Error> {
+        match cursor.peek() {
+            Some(Token::Hash) => {
+                cursor.next();
+                Ok(Operand::Immediate(self.expression(cursor)?))
+            }
+            Some(Token::LeftBracket) => {
+                cursor.next();
+                let operands = self.operand_list(cursor, Token::RightBracket, "`]`")?;
+                Ok(Operand::Memory(operands))
+            }
+            Some(Token::LeftParen) if self.starts_indexed(cursor) => {
+                cursor.next();
+                let operands = self.operand_list(cursor, Token::RightParen, "`)`")?;
+                Ok(Operand::Indexed(operands))
+            }
+            Some(Token::Identifier(name)) if Register::from_name(name).is_some() => {
+                let register = Register::from_name(name).unwrap();
+                cursor.next();
+                Ok(Operand::Register(register))
+            }
+            _ => Ok(Operand::Value(self.expression(cursor)?)),
+        }
+    }
+
+    /// `(x, 3)` is an indexed operand, whereas `(LABEL) + 3` is an expression.
+    fn starts_indexed(&self, cursor: &Cursor) -> bool {
+        match (cursor.peek_nth(1), cursor.peek_nth(2)) {
+            (Some(Token::Identifier(name)), Some(Token::Comma)) => {
+                Register::from_name(name).is_some()
+            }
+            _ => false,
+        }
+    }
+
+    fn operand_list(
+        &self,
+        cursor: &mut Cursor,
+        terminator: Token,
+        description: &str,
+    ) -> Result<Vec<Operand>, Error> {
+        let mut operands = vec![self.operand(cursor)?];
+        while cursor.next_if(&Token::Comma) {
+            operands.push(self.operand(cursor)?);
+        }
+        cursor.expect(terminator, description)?;
+
+        Ok(operands)
+    }
+
+    fn expression(&self, cursor: &mut Cursor) -> Result<Expression, Error> {
+        self.binary_expression(cursor, 0)
+    }
+
+    fn binary_expression(&self, cursor: &mut Cursor, level: usize) -> Result<Expression, Error> {
+        const LEVELS: &[&[(Token, BinaryOp)]] = &[
+            &[(Token::Pipe, BinaryOp::Or)],
+            &[(Token::Caret, BinaryOp::Xor)],
+            &[(Token::Ampersand, BinaryOp::And)],
+            &[
+                (Token::ShiftLeft, BinaryOp::ShiftLeft),
+                (Token::ShiftRight, BinaryOp::ShiftRight),
+            ],
+            &[
+                (Token::Plus, BinaryOp::Add),
+                (Token::Minus, BinaryOp::Subtract),
+            ],
+            &[
+                (Token::Star, BinaryOp::Multiply),
+                (Token::Slash, BinaryOp::Divide),
+                (Token::Percent, BinaryOp::Remainder),
+            ],
+        ];
+
+        let Some(operators) = LEVELS.get(level) else {
+            return self.unary_expression(cursor);
+        };
+
+        let mut lhs = self.binary_expression(cursor, level + 1)?;
+
+        'operators: loop {
+            for (token, op) in operators.iter() {
+                if cursor.next_if(token) {
+                    let rhs = self.binary_expression(cursor, level + 1)?;
+                    lhs = Expression::Binary(*op, Box::new(lhs), Box::new(rhs));
+                    continue 'operators;
+                }
+            }
+
+            return Ok(lhs);
+        }
+    }
+
+    fn unary_expression(&self, cursor: &mut Cursor) -> Result<Expression, Error> {
+        let op = match cursor.peek() {
+            Some(Token::Minus) => UnaryOp::Negate,
+            Some(Token::Tilde) | Some(Token::Bang) => UnaryOp::Not,
+            _ => return self.postfix_expression(cursor),
+        };
+        cursor.next();
+
+        Ok(Expression::Unary(
+            op,
+            Box::new(self.unary_expression(cursor)?),
+        ))
+    }
+
+    fn postfix_expression(&self, cursor: &mut Cursor) -> Result<Expression, Error> {
+        let mut expression = self.primary_expression(cursor)?;
+
+        while cursor.next_if(&Token::Backtick) {
+            let Some(Token::Integer(width, _)) = cursor.next() else {
+                return cursor.error("expected bit width after '`'");
+            };
+            expression = Expression::Sized(Box::new(expression), width as u32);
+        }
+
+        Ok(expression)
+    }
+
+    fn primary_expression(&self, cursor: &mut Cursor) -> Result<Expression, Error> {
+        match cursor.next() {
+            Some(Token::Integer(value, width)) => Ok(Expression::Integer(value, width)),
+            Some(Token::Identifier(name)) => Ok(Expression::Symbol(self.qualify(cursor, &name)?)),
+            Some(Token::LeftParen) => {
+                let expression = self.expression(cursor)?;
+                cursor.expect(Token::RightParen, "`)`")?;
+                Ok(expression)
+            }
+            _ => {
+                cursor.position -= 1;
+                cursor.error("expected expression")
+            }
+        }
+    }
+}
@@ -0,0 +1,50 @@
+//! Checks the assembler against binaries customasm 0.13 built from the same
+//! sources with the rules in `asm/bw8.asm`.
+
+use std::path::Path;
+use std::process::Command;
+
+fn assert_matches_customasm(name: &str) {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let source = root.join(format!("{}.asm", name));
+    let reference = root.join(format!("tests/customasm/{}.bin", name));
+    let output = Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("{}.bin", name));
+
+    let status = Command::new(env!("CARGO_BIN_EXE_asm"))
+        .arg(&source)
+        .arg("-o")
+        .arg(&output)
+        .status()
+        .unwrap();
+    assert!(status.success(), "{}: assembler failed", name);
+
+    let bytes = std::fs::read(&output).unwrap();
+    let expected = std::fs::read(&reference).unwrap();
+
+    if let Some(offset) = (0..expected.len().max(bytes.len()))
+        .find(|&offset| expected.get(offset) != bytes.get(offset))
+    {
+        panic!(
+            "{}: differs from customasm at {:#06x}: {:02x?} != {:02x?}",
+            name,
+            offset,
+            bytes.get(offset),
+            expected.get(offset)
+        );
+    }
+}
+
+#[test]
+fn hello_world_matches_customasm() {
+    assert_matches_customasm("hello_world");
+}
+
+#[test]
+fn irq_hello_world_matches_customasm() {
+    assert_matches_customasm("irq_hello_world");
+}
+
+#[test]
+fn vga_test_matches_customasm() {
+    assert_matches_customasm("vga_test");
+}
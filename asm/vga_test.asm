@@ -166,7 +166,8 @@ irq_palette_foolery:
     push b
     push c
 
-    out [EMULATOR_IRQ_ACK], a
+    ld a, #1 << IRQ_EXTERNAL
+    out [IO_INTC_PENDING], a
 
     ld b, #BLACK
     ld c, #WHITE
@@ -0,0 +1,373 @@
+//! Disassembles bw8 machine code. Code is told from data by following
+//! control flow from a set of entry points; every byte no path reaches is
+//! data, shown as text where it reads as text.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use isa::{decode, Address, Byte, ExtensionMode, Instruction, JumpMode, Memory16Mode, Memory8Mode};
+
+/// The interrupt vectors, each holding a jump to its handler, and the names
+/// their labels take.
+pub const VECTORS: [(Address, &str); 5] = [
+    (0x0000, "reset"),
+    (0x0004, "nmi"),
+    (0x0008, "irq"),
+    (0x000C, "swi"),
+    (0x0010, "fault"),
+];
+
+/// How many bytes of data a line shows.
+const DATA_LINE: usize = 8;
+
+/// The shortest run of text shown as a string.
+const MIN_TEXT: usize = 4;
+
+/// The shortest run of one repeated byte collapsed into a single line.
+const MIN_FILL: usize = 16;
+
+/// The instruction at `address` in `image`, which is loaded at `origin`.
+pub fn decode_at(image: &[Byte], origin: Address, address: Address) -> Option<Instruction> {
+    let offset = address.checked_sub(origin)? as usize;
+    decode(
+        ExtensionMode::Normal,
+        &mut image.get(offset..)?.iter().copied(),
+    )
+}
+
+/// Where the instruction at `address` transfers control to, if it is a
+/// jump or call whose target is known without running it.
+pub fn target(address: Address, instruction: &Instruction) -> Option<Address> {
+    let mode = match *instruction {
+        Instruction::Jmp(_, mode) | Instruction::Call(mode) => mode,
+        _ => return None,
+    };
+    let next = address.wrapping_add(instruction.size() as Address);
+
+    match mode {
+        // Offsets are added zero-extended, as `arch` does.
+        JumpMode::Relative(offset) => Some(next.wrapping_add(offset as Address)),
+        JumpMode::Absolute(address) => Some(address),
+        JumpMode::Indirect(..) => None,
+    }
+}
+
+/// The instructions around `address` in memory read through `read`: up to
+/// `before` instructions that end where it starts, then the one at `address`
+/// and `after` more.
+///
+/// Instructions vary in length, so the ones before `address` are found by
+/// decoding from each earlier address in turn, farthest first, and keeping
+/// the first run that lands on `address` exactly.
+pub fn around(
+    read: impl Fn(Address) -> Byte,
+    address: Address,
+    before: usize,
+    after: usize,
+) -> Vec<(Address, Instruction)> {
+    let decode = |address: Address| {
+        let mut bytes = (0..4).map(|offset| read(address.wrapping_add(offset)));
+        decode(ExtensionMode::Normal, &mut bytes).expect("four bytes hold any instruction")
+    };
+
+    let mut instructions = Vec::new();
+    for distance in (1..=before * 4).rev() {
+        let start = address.wrapping_sub(distance as Address);
+        let mut run = Vec::new();
+        let mut offset = 0;
+        while offset < distance {
+            let at = start.wrapping_add(offset as Address);
+            let instruction = decode(at);
+            run.push((at, instruction));
+            offset += instruction.size();
+        }
+
+        if offset == distance {
+            instructions = run.split_off(run.len().saturating_sub(before));
+            break;
+        }
+    }
+
+    let mut at = address;
+    for _ in 0..=after {
+        let instruction = decode(at);
+        instructions.push((at, instruction));
+        at = at.wrapping_add(instruction.size() as Address);
+    }
+
+    instructions
+}
+
+/// The data address an instruction names outright, if it has one.
+fn data_address(instruction: &Instruction) -> Option<Address> {
+    match *instruction {
+        Instruction::Load8(_, Memory8Mode::Absolute(address))
+        | Instruction::Store8(Memory8Mode::Absolute(address), _)
+        | Instruction::Load16(_, Memory16Mode::Absolute(address))
+        | Instruction::Store16(Memory16Mode::Absolute(address), _)
+        | Instruction::Load16Immediate(_, address) => Some(address),
+        _ => None,
+    }
+}
+
+/// Whether execution can continue with the instruction that follows.
+fn falls_through(instruction: &Instruction) -> bool {
+    !matches!(
+        instruction,
+        Instruction::Jmp(isa::Condition::Always, _) | Instruction::Ret | Instruction::Reti
+    )
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Content {
+    Instruction(Instruction),
+    /// Bytes that read as text.
+    Text,
+    /// One byte, repeated.
+    Fill,
+    Data,
+}
+
+/// An instruction, or a run of data, and the bytes it occupies.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Line {
+    pub address: Address,
+    pub bytes: Vec<Byte>,
+    pub content: Content,
+}
+
+pub struct Disassembly {
+    pub lines: Vec<Line>,
+    /// The names recovered for jump and call targets, entry points and the
+    /// data addresses instructions name.
+    pub labels: BTreeMap<Address, String>,
+}
+
+impl Disassembly {
+    pub fn label(&self, address: Address) -> Option<&str> {
+        self.labels.get(&address).map(String::as_str)
+    }
+}
+
+/// Disassembles `image`, loaded at `origin`, following control flow from
+/// `entries`, each named by the label it is given.
+pub fn disassemble(image: &[Byte], origin: Address, entries: &[(Address, &str)]) -> Disassembly {
+    // Whatever runs past the top of the address space is unreachable.
+    let image = &image[..image.len().min(0x1_0000 - origin as usize)];
+    let end = origin as usize + image.len();
+    let contains = |address: Address| (origin as usize..end).contains(&(address as usize));
+
+    let mut instructions: Vec<Option<Instruction>> = vec![None; image.len()];
+    let mut covered = vec![false; image.len()];
+    let mut labels = BTreeMap::new();
+    let mut data = Vec::new();
+
+    let mut pending: Vec<Address> = Vec::new();
+    for (address, name) in entries {
+        if contains(*address) {
+            labels.insert(*address, name.to_string());
+            pending.push(*address);
+        }
+    }
+
+    while let Some(start) = pending.pop() {
+        let mut address = start;
+
+        // Stops where the path leaves the image or runs into bytes already
+        // decoded, whether or not it does so at an instruction boundary.
+        while contains(address) && !covered[(address - origin) as usize] {
+            let offset = (address - origin) as usize;
+            let Some(instruction) = decode_at(image, origin, address) else {
+                break;
+            };
+            let size = instruction.size();
+            if covered[offset..offset + size]
+                .iter()
+                .any(|&covered| covered)
+            {
+                break;
+            }
+
+            covered[offset..offset + size].fill(true);
+            instructions[offset] = Some(instruction);
+
+            if let Some(target) = target(address, &instruction) {
+                if contains(target) {
+                    let prefix = match instruction {
+                        Instruction::Call(_) => "sub",
+                        _ => "loc",
+                    };
+                    labels
+                        .entry(target)
+                        .or_insert_with(|| format!("{}_{:04x}", prefix, target));
+                    pending.push(target);
+                }
+            }
+            if let Some(address) = data_address(&instruction) {
+                data.push(address);
+            }
+
+            if !falls_through(&instruction) {
+                break;
+            }
+            match address.checked_add(size as Address) {
+                Some(next) => address = next,
+                None => break,
+            }
+        }
+    }
+
+    for address in data {
+        if contains(address) && !covered[(address - origin) as usize] {
+            labels
+                .entry(address)
+                .or_insert_with(|| format!("data_{:04x}", address));
+        }
+    }
+
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    while offset < image.len() {
+        let address = origin + offset as Address;
+
+        if let Some(instruction) = instructions[offset] {
+            let size = instruction.size();
+            lines.push(Line {
+                address,
+                bytes: image[offset..offset + size].to_vec(),
+                content: Content::Instruction(instruction),
+            });
+            offset += size;
+            continue;
+        }
+
+        // Data runs until the next code or label.
+        let mut limit = offset + 1;
+        while limit < image.len()
+            && !covered[limit]
+            && !labels.contains_key(&(origin + limit as Address))
+        {
+            limit += 1;
+        }
+
+        let (content, length) = data_line(&image[offset..limit]);
+        lines.push(Line {
+            address,
+            bytes: image[offset..offset + length].to_vec(),
+            content,
+        });
+        offset += length;
+    }
+
+    Disassembly { lines, labels }
+}
+
+/// The first line of a run of data, and how many of its bytes it takes.
+fn data_line(bytes: &[Byte]) -> (Content, usize) {
+    let text = text_length(bytes);
+    if text >= MIN_TEXT {
+        return (Content::Text, text);
+    }
+
+    let fill = fill_length(bytes);
+    if fill >= MIN_FILL {
+        return (Content::Fill, fill);
+    }
+
+    // Stops short where text or a fill begins, so it gets a line of its own.
+    let mut length = 1;
+    while length < bytes.len().min(DATA_LINE)
+        && text_length(&bytes[length..]) < MIN_TEXT
+        && fill_length(&bytes[length..]) < MIN_FILL
+    {
+        length += 1;
+    }
+    (Content::Data, length)
+}
+
+/// The length of the text at the start of `bytes`, along with the NUL that
+/// terminates it if there is one.
+fn text_length(bytes: &[Byte]) -> usize {
+    let text = bytes
+        .iter()
+        .take_while(|&&byte| byte.is_ascii_graphic() || b" \t\r\n".contains(&byte))
+        .count();
+
+    match bytes.get(text) {
+        Some(0) if text > 0 => text + 1,
+        _ => text,
+    }
+}
+
+fn fill_length(bytes: &[Byte]) -> usize {
+    match bytes.first() {
+        Some(first) => bytes.iter().take_while(|&byte| byte == first).count(),
+        None => 0,
+    }
+}
+
+/// Formats the lines in columns of address, bytes and source, with a label
+/// line before each labelled address. Jump and call targets are followed by
+/// their label.
+impl std::fmt::Display for Disassembly {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in &self.lines {
+            if let Some(label) = self.label(line.address) {
+                writeln!(f, "\n{}:", label)?;
+            }
+
+            let mut hex = String::new();
+            for byte in line.bytes.iter().take(DATA_LINE) {
+                write!(hex, "{:02x} ", byte)?;
+            }
+            if line.bytes.len() > DATA_LINE {
+                hex.push_str("...");
+            }
+
+            write!(f, "{:04x}:  {:<27} ", line.address, hex)?;
+            match &line.content {
+                Content::Instruction(instruction) => {
+                    write!(f, "{}", instruction)?;
+                    let label = target(line.address, instruction)
+                        .or_else(|| data_address(instruction))
+                        .and_then(|address| self.label(address));
+                    if let Some(label) = label {
+                        write!(f, " <{}>", label)?;
+                    }
+                }
+                Content::Text => write!(f, "#d \"{}\"", escape(&line.bytes))?,
+                Content::Fill => {
+                    write!(f, "; {} bytes of {:#04x}", line.bytes.len(), line.bytes[0])?
+                }
+                Content::Data => {
+                    let bytes: Vec<String> = line
+                        .bytes
+                        .iter()
+                        .map(|byte| format!("{:#04x}", byte))
+                        .collect();
+                    write!(f, "#d {}", bytes.join(", "))?;
+                }
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Escapes text for a string literal of the assembler.
+fn escape(bytes: &[Byte]) -> String {
+    let mut text = String::new();
+    for &byte in bytes {
+        match byte {
+            0 => text.push_str("\\0"),
+            b'\n' => text.push_str("\\n"),
+            b'\r' => text.push_str("\\r"),
+            b'\t' => text.push_str("\\t"),
+            b'"' => text.push_str("\\\""),
+            b'\\' => text.push_str("\\\\"),
+            _ => text.push(byte as char),
+        }
+    }
+    text
+}
@@ -0,0 +1,66 @@
+use std::path::PathBuf;
+
+use isa::Address;
+
+fn usage() -> ! {
+    eprintln!("usage: bw8-objdump <binary> [--origin <address>] [--entry <address>]...");
+    std::process::exit(2);
+}
+
+fn address(text: &str) -> Address {
+    let parsed = match text.strip_prefix("0x") {
+        Some(digits) => Address::from_str_radix(digits, 16),
+        None => text.parse(),
+    };
+
+    parsed.unwrap_or_else(|_| {
+        eprintln!("error: invalid address `{}`", text);
+        std::process::exit(2);
+    })
+}
+
+/// Disassembles a binary image. Control flow is followed from each
+/// `--entry`, or else from the interrupt vectors when the image is loaded at
+/// address 0 and from its first byte otherwise.
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let mut binary = None;
+    let mut origin = 0;
+    let mut entries = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--origin" => origin = address(&args.next().unwrap_or_else(|| usage())),
+            "--entry" => entries.push(address(&args.next().unwrap_or_else(|| usage()))),
+            _ if binary.is_none() => binary = Some(PathBuf::from(arg)),
+            _ => usage(),
+        }
+    }
+
+    let binary = binary.unwrap_or_else(|| usage());
+    let image = match std::fs::read(&binary) {
+        Ok(image) => image,
+        Err(error) => {
+            eprintln!("error: {}: {}", binary.display(), error);
+            std::process::exit(1);
+        }
+    };
+
+    let names: Vec<String> = entries
+        .iter()
+        .map(|entry| format!("entry_{:04x}", entry))
+        .collect();
+    let entries: Vec<(Address, &str)> = if !entries.is_empty() {
+        entries
+            .iter()
+            .copied()
+            .zip(names.iter().map(String::as_str))
+            .collect()
+    } else if origin == 0 {
+        disasm::VECTORS.to_vec()
+    } else {
+        vec![(origin, "start")]
+    };
+
+    print!("{}", disasm::disassemble(&image, origin, &entries));
+}
@@ -0,0 +1,165 @@
+use std::path::Path;
+
+use disasm::{around, disassemble, Content, VECTORS};
+use isa::*;
+
+fn assemble(source: &str) -> Vec<Byte> {
+    match asm::assemble_source(Path::new("test.asm"), source) {
+        Ok(assembly) => assembly.bytes,
+        Err(error) => panic!("{}", error),
+    }
+}
+
+#[test]
+fn every_opcode_reassembles_from_its_text() {
+    let mut expected = Vec::new();
+    let mut source = String::new();
+
+    for (_, opcode) in OPCODES {
+        let mut bytes = opcode.encode().to_vec();
+        bytes.extend([0x85, 0x34, 0x12]);
+
+        let instruction = decode(ExtensionMode::Normal, &mut bytes.iter().copied()).unwrap();
+        expected.extend(&bytes[..instruction.size()]);
+        source.push_str(&format!("{}\n", instruction));
+    }
+
+    assert_eq!(assemble(&source), expected);
+}
+
+#[test]
+fn instructions_use_the_assembler_syntax() {
+    let text = |instruction: Instruction| instruction.to_string();
+
+    assert_eq!(
+        text(Instruction::Load8(
+            Register8::A,
+            Memory8Mode::ConstantOffset(Pointer::X, 3)
+        )),
+        "ld a, [x, #3]"
+    );
+    assert_eq!(
+        text(Instruction::Jmp(
+            Condition::Equal,
+            JumpMode::Absolute(0x1234)
+        )),
+        "br.eq.abs 0x1234"
+    );
+    assert_eq!(
+        text(Instruction::Jmp(
+            Condition::Always,
+            JumpMode::Relative(0xFE)
+        )),
+        "jmp -2"
+    );
+    assert_eq!(
+        text(Instruction::In(Register8::B, IOMode::Port(0x20))),
+        "in b, [0x20]"
+    );
+    assert_eq!(
+        text(Instruction::Alu2(
+            Alu2Op::Cmp,
+            Register8::C,
+            Alu2OpMode::Constant(0x0A)
+        )),
+        "cmp c, 0x0a"
+    );
+}
+
+#[test]
+fn control_flow_separates_code_from_data() {
+    let image = assemble(
+        "
+        #addr 0x0000
+            jmp.abs boot
+        #addr 0x0004
+            jmp.abs spin
+        #addr 0x0008
+            jmp.abs spin
+        #addr 0x000C
+            jmp.abs spin
+        #addr 0x0010
+            jmp.abs spin
+
+        boot:
+            call.abs print
+        spin:
+            jmp.abs spin
+
+        print:
+            ld x, #message
+            ret
+
+        table:
+            #d 0x01, 0x02, 0x03
+
+        message:
+            #d \"Hi there\\n\\0\"
+        ",
+    );
+
+    let disassembly = disassemble(&image, 0, &VECTORS);
+
+    assert_eq!(disassembly.label(0x0014), Some("loc_0014"));
+    assert_eq!(disassembly.label(0x0018), Some("loc_0018"));
+    assert_eq!(disassembly.label(0x001c), Some("sub_001c"));
+    assert_eq!(disassembly.label(0x0025), Some("data_0025"));
+
+    let contents: Vec<&Content> = disassembly.lines.iter().map(|line| &line.content).collect();
+    assert_eq!(contents[8], &Content::Instruction(Instruction::Ret));
+    assert_eq!(contents[9], &Content::Data);
+    assert_eq!(contents[10], &Content::Text);
+    assert_eq!(disassembly.lines[9].bytes, [0x01, 0x02, 0x03]);
+
+    let listing = disassembly.to_string();
+    assert!(
+        listing.contains("call.abs 0x001c <sub_001c>"),
+        "{}",
+        listing
+    );
+    assert!(listing.contains("#d 0x01, 0x02, 0x03"), "{}", listing);
+    assert!(listing.contains("#d \"Hi there\\n\\0\""), "{}", listing);
+}
+
+#[test]
+fn windows_fall_in_step_with_the_instruction_they_surround() {
+    let assembly = asm::assemble_source(
+        Path::new("test.asm"),
+        "
+        first:
+            ld x, #0x1234
+        second:
+            ld a, [x, #3]
+        third:
+            nop
+        fourth:
+            jmp.abs first
+        fifth:
+            addc a, b
+        sixth:
+            ret
+        ",
+    )
+    .unwrap();
+    let address = |name| assembly.symbol(name).unwrap() as Address;
+    let read = |address: Address| assembly.bytes.get(address as usize).copied().unwrap_or(0);
+
+    let window: Vec<Address> = around(read, address("fourth"), 3, 2)
+        .into_iter()
+        .map(|(address, _)| address)
+        .collect();
+    let expected: Vec<Address> = ["first", "second", "third", "fourth", "fifth", "sixth"]
+        .into_iter()
+        .map(address)
+        .collect();
+    assert_eq!(window, expected);
+
+    // Nothing precedes the first instruction but the zeros of unmapped
+    // memory, which decode as `nop`.
+    let window = around(read, address("second"), 3, 0);
+    assert_eq!(window.len(), 4);
+    assert_eq!(window[2].0, address("first"));
+    assert_eq!(window[3].0, address("second"));
+
+    assert_eq!(assembly.symbol_file().lines().next(), Some("0000 first"));
+}
@@ -0,0 +1,63 @@
+//! Measures how many frames per second the emulator sustains, for a program
+//! that leaves the screen alone and for one that changes the palette every
+//! frame, which makes every line dirty.
+//!
+//! `cargo run -p emu --example frame_rate [-- <frames>]`
+
+use std::path::Path;
+use std::time::Instant;
+
+use emu::{vga, Bw8};
+
+const STATIC: &str = r#"
+#include "emu.asm"
+
+spin:
+    jmp.abs spin
+"#;
+
+const PALETTE_CYCLING: &str = r#"
+#include "emu.asm"
+
+    ld x, #IO_PALETTE_BASE
+    ld b, #0
+frame:
+    in a, [IO_VGA_STATUS]
+    and a, VGA_STATUS_VBLANK
+    br.eq.abs frame
+    inc b
+    out [x, 0], b
+wait:
+    in a, [IO_VGA_STATUS]
+    and a, VGA_STATUS_VBLANK
+    br.ne.abs wait
+    jmp.abs frame
+"#;
+
+fn main() {
+    let frames = std::env::args()
+        .nth(1)
+        .map(|frames| frames.parse().expect("frame count is not a number"))
+        .unwrap_or(600);
+
+    for (name, source) in [("static", STATIC), ("palette cycling", PALETTE_CYCLING)] {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../asm/frame_rate.asm");
+        let assembly = asm::assemble_source(&path, source).expect("benchmark assembles");
+        let mut system = Bw8::from_image(&assembly.bytes).expect("benchmark fits in memory");
+        system.reset();
+
+        let start = Instant::now();
+        for _ in 0..frames {
+            system.run(vga::CYCLES_PER_FRAME);
+        }
+        let elapsed = start.elapsed().as_secs_f64();
+
+        println!(
+            "{}: {} frames in {:.2} s, {:.0} fps",
+            name,
+            frames,
+            elapsed,
+            frames as f64 / elapsed
+        );
+    }
+}
@@ -0,0 +1,228 @@
+use crate::intc::{InterruptController, INTC_BASE, INTC_CURRENT, SOURCE_EXTERNAL};
+use crate::intc::{SOURCE_KEYBOARD, SOURCE_TIMER, SOURCE_UART, SOURCE_VBLANK};
+use crate::keyboard::{Keyboard, KEYBOARD_BASE, KEYBOARD_CONTROL};
+use crate::pit::{Pit, PIT_BASE, PIT_COUNT_HIGH};
+use crate::uart::{Uart, UART_BASE, UART_DIVISOR};
+use crate::vga::{self, Vga, VGA_BASE, VGA_SCROLL_Y_HIGH};
+use arch::{self, Address, Byte, PhysicalAddress};
+
+pub const BANK_COUNT: usize = 16;
+pub const BANK_SIZE: usize = 0x1_0000;
+
+/// Every bank maps ROM below this address and RAM from it to the end of the
+/// bank. Writes to ROM are ignored.
+pub const RAM_BASE: Address = 0x8000;
+
+pub struct Bw8Bus {
+    /// Physical memory, indexed by `PhysicalAddress::linear`.
+    memory: Box<[Byte]>,
+    pub uart: Uart,
+    pit: Pit,
+    intc: InterruptController,
+    pub vga: Vga,
+    pub keyboard: Keyboard,
+    /// Bytes written to the `EMULATOR_PUTCHAR` port and not yet taken.
+    pub output: Vec<Byte>,
+    pending_rst: bool,
+    pending_nmi: bool,
+    /// The processor's clock, in cycles per second.
+    clock_rate: u64,
+    /// Accumulates `vga::CLOCK_RATE` each processor cycle; the VGA is clocked
+    /// each time it reaches `clock_rate`.
+    vga_phase: u64,
+}
+
+impl Bw8Bus {
+    /// Loads `image` into bank 0, and also into the ROM of every other bank,
+    /// so that code in ROM can run from any bank.
+    pub fn from_image(image: &[Byte]) -> Self {
+        let mut memory = vec![0x00; BANK_COUNT * BANK_SIZE].into_boxed_slice();
+        memory[..image.len()].copy_from_slice(image);
+
+        let rom_image = &image[..image.len().min(RAM_BASE as usize)];
+        for bank in memory.chunks_exact_mut(BANK_SIZE).skip(1) {
+            bank[..rom_image.len()].copy_from_slice(rom_image);
+        }
+
+        Self {
+            memory,
+            uart: Uart::new(),
+            pit: Pit::new(),
+            intc: InterruptController::new(),
+            vga: Vga::new(),
+            keyboard: Keyboard::new(),
+            output: Vec::new(),
+            pending_rst: false,
+            pending_nmi: false,
+            clock_rate: vga::CLOCK_RATE,
+            vga_phase: 0,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.pending_rst = false;
+        self.pending_nmi = false;
+        self.uart.reset();
+        self.pit.reset();
+        self.intc.reset();
+        self.vga.reset();
+        self.keyboard.reset();
+    }
+
+    pub fn inspect_memory(&self, address: PhysicalAddress) -> Byte {
+        self.memory[address.linear()]
+    }
+
+    pub fn patch_memory(&mut self, address: PhysicalAddress, data: Byte) {
+        self.memory[address.linear()] = data;
+    }
+
+    pub fn set_reset(&mut self, state: bool) {
+        self.pending_rst = state
+    }
+
+    /// Requests an IRQ from the external source, or withdraws the request.
+    pub fn set_irq(&mut self, state: bool) {
+        match state {
+            true => self.intc.sample(SOURCE_EXTERNAL, true),
+            false => self.intc.clear(1 << SOURCE_EXTERNAL),
+        }
+    }
+
+    pub fn set_nmi(&mut self, state: bool) {
+        self.pending_nmi = state
+    }
+
+    pub fn clock_rate(&self) -> u64 {
+        self.clock_rate
+    }
+
+    pub fn set_clock_rate(&mut self, rate: u64) {
+        self.clock_rate = rate.max(1);
+        self.vga_phase = 0;
+    }
+}
+
+impl arch::Bus for Bw8Bus {
+    fn memory_read(
+        &self,
+        _privilege: arch::PrivilegeLevel,
+        _kind: arch::MemoryAddressKind,
+        address: arch::PhysicalAddress,
+    ) -> arch::Byte {
+        self.memory[address.linear()]
+    }
+
+    fn memory_write(
+        &mut self,
+        _privilege: arch::PrivilegeLevel,
+        _kind: arch::MemoryAddressKind,
+        address: arch::PhysicalAddress,
+        data: arch::Byte,
+    ) {
+        if address.base >= RAM_BASE {
+            self.memory[address.linear()] = data;
+        }
+    }
+
+    fn io_read(
+        &mut self,
+        _privilege: arch::PrivilegeLevel,
+        address: arch::PhysicalAddress,
+    ) -> arch::BusResult<arch::Byte> {
+        match address.base {
+            UART_BASE..=UART_DIVISOR => arch::BusResult::Data(self.uart.read(address.base)),
+            PIT_BASE..=PIT_COUNT_HIGH => arch::BusResult::Data(self.pit.read(address.base)),
+            INTC_BASE..=INTC_CURRENT => arch::BusResult::Data(self.intc.read(address.base)),
+            VGA_BASE..=VGA_SCROLL_Y_HIGH => arch::BusResult::Data(self.vga.read(address.base)),
+            KEYBOARD_BASE..=KEYBOARD_CONTROL => {
+                arch::BusResult::Data(self.keyboard.read(address.base))
+            }
+            vga::MEMORY_BASE..=vga::MEMORY_END => {
+                arch::BusResult::Data(self.vga.inspect_memory(address.base - vga::MEMORY_BASE))
+            }
+            _ => arch::BusResult::Data(0),
+        }
+    }
+
+    fn io_write(
+        &mut self,
+        _privilege: arch::PrivilegeLevel,
+        address: arch::PhysicalAddress,
+        data: arch::Byte,
+    ) -> arch::BusResult<()> {
+        match address.base {
+            0x00 => arch::BusResult::Action(arch::EnvironmentAction::Halt(data)),
+            0x01 => {
+                self.output.push(data);
+                arch::BusResult::Data(())
+            }
+            0x03 => arch::BusResult::Action(arch::EnvironmentAction::Break),
+            UART_BASE..=UART_DIVISOR => {
+                self.uart.write(address.base, data);
+                arch::BusResult::Data(())
+            }
+            PIT_BASE..=PIT_COUNT_HIGH => {
+                self.pit.write(address.base, data);
+                arch::BusResult::Data(())
+            }
+            INTC_BASE..=INTC_CURRENT => {
+                self.intc.write(address.base, data);
+                arch::BusResult::Data(())
+            }
+            VGA_BASE..=VGA_SCROLL_Y_HIGH => {
+                self.vga.write(address.base, data);
+                arch::BusResult::Data(())
+            }
+            KEYBOARD_BASE..=KEYBOARD_CONTROL => {
+                self.keyboard.write(address.base, data);
+                arch::BusResult::Data(())
+            }
+            vga::MEMORY_BASE..=vga::MEMORY_END => {
+                self.vga.write_memory(address.base - vga::MEMORY_BASE, data);
+                arch::BusResult::Data(())
+            }
+            _ => arch::BusResult::Data(()),
+        }
+    }
+
+    fn clock(&mut self) {
+        self.uart.clock();
+        self.pit.clock();
+        self.keyboard.clock();
+
+        self.intc.sample(SOURCE_TIMER, self.pit.irq());
+        self.intc.sample(SOURCE_UART, self.uart.irq());
+        self.intc.sample(SOURCE_KEYBOARD, self.keyboard.irq());
+
+        // The vblank IRQ lasts one VGA cycle, so it is sampled after each.
+        self.vga_phase += vga::CLOCK_RATE;
+        while self.vga_phase >= self.clock_rate {
+            self.vga_phase -= self.clock_rate;
+            self.vga.clock();
+            self.intc.sample(SOURCE_VBLANK, self.vga.irq());
+        }
+    }
+
+    fn is_rst_active(&self) -> bool {
+        self.pending_rst
+    }
+
+    fn is_nmi_active(&mut self) -> bool {
+        let rv = self.pending_nmi;
+        self.pending_nmi = false;
+        rv
+    }
+
+    fn is_irq_active(&self) -> bool {
+        self.intc.is_active()
+    }
+
+    fn acknowledge_irq(&mut self) {
+        self.intc.acknowledge();
+    }
+
+    fn is_req_active(&self) -> bool {
+        false
+    }
+}
@@ -1,7 +0,0 @@
-pub struct Uart;
-
-impl Uart {
-    pub fn new() -> Self {
-        Self {}
-    }
-}
@@ -0,0 +1,27 @@
+use arch::Byte;
+
+/// The letters A to Z from the sheet in `res/vga_bitmap_font.png`, one
+/// glyph after another in the same layout as [`Glyph`], so that the emulator
+/// needn't decode the PNG.
+const LETTERS: &[u8; LETTER_COUNT * GLYPH_SIZE] = include_bytes!("../res/vga_bitmap_font.bin");
+const LETTER_COUNT: usize = 26;
+
+pub const GLYPH_SIZE: usize = 8;
+pub const GLYPH_COUNT: usize = 256;
+
+/// A glyph's eight rows, top first, with the leftmost pixel in the most
+/// significant bit.
+pub type Glyph = [Byte; GLYPH_SIZE];
+
+/// The font's glyphs, indexed by CP437 code. Lowercase letters share the
+/// uppercase glyphs, and every other code is blank.
+pub fn glyphs() -> [Glyph; GLYPH_COUNT] {
+    let mut glyphs = [[0; GLYPH_SIZE]; GLYPH_COUNT];
+    for (letter, glyph) in LETTERS.chunks_exact(GLYPH_SIZE).enumerate() {
+        let glyph = glyph.try_into().unwrap();
+        glyphs[b'A' as usize + letter] = glyph;
+        glyphs[b'a' as usize + letter] = glyph;
+    }
+
+    glyphs
+}
@@ -0,0 +1,48 @@
+use std::io::Write;
+
+use arch::StopReason;
+
+use emu::{vga, Bw8};
+
+/// Cycles executed between checks of the cycle limit; one frame's worth at
+/// the default clock rate, matching the windowed front end.
+const CYCLES_PER_SLICE: usize = vga::CYCLES_PER_FRAME;
+
+/// Exit status used when the cycle limit is reached before the program halts
+/// or hits a breakpoint, following the convention of coreutils' `timeout`.
+pub const EXIT_CYCLE_LIMIT: i32 = 124;
+
+/// Runs the system without opening a window, writing the program's output to
+/// stdout. Returns the process exit status: the byte written to the halt port,
+/// zero for a breakpoint, or `EXIT_CYCLE_LIMIT` when `cycle_limit` runs out.
+pub fn run(mut system: Bw8, cycle_limit: Option<usize>) -> i32 {
+    system.reset();
+
+    let mut remaining = cycle_limit;
+
+    let status = loop {
+        let cycles = match remaining {
+            Some(0) => break EXIT_CYCLE_LIMIT,
+            Some(remaining) => remaining.min(CYCLES_PER_SLICE),
+            None => CYCLES_PER_SLICE,
+        };
+
+        let (_trace, stop, elapsed) = system.run(cycles);
+        std::io::stdout().write_all(&system.take_output()).ok();
+        match stop {
+            StopReason::CycleLimit => {}
+            StopReason::Breakpoint => {
+                eprintln!("Reached breakpoint!");
+                break 0;
+            }
+            StopReason::Halt(code) => break code as i32,
+        }
+
+        if let Some(remaining) = remaining.as_mut() {
+            *remaining = remaining.saturating_sub(elapsed);
+        }
+    };
+
+    std::io::stdout().flush().ok();
+    status
+}
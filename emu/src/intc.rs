@@ -0,0 +1,91 @@
+use arch::{Address, Byte};
+
+/// The interrupt controller's registers occupy three I/O addresses from here.
+pub const INTC_BASE: Address = 0x0030;
+
+/// Reads return a bit per source that has requested an interrupt. Writing
+/// ones acknowledges those sources; a source whose line is still asserted
+/// requests again on the next cycle.
+pub const INTC_PENDING: Address = INTC_BASE;
+/// A set bit lets the source's pending requests interrupt the processor.
+/// Every source is enabled after reset.
+pub const INTC_MASK: Address = INTC_BASE + 1;
+/// Read-only. The number of the source the processor last took an IRQ for,
+/// or `NO_SOURCE` once that source has been acknowledged.
+pub const INTC_CURRENT: Address = INTC_BASE + 2;
+
+/// Sources, numbered by bit in the pending and mask registers. Lower numbers
+/// have higher priority.
+pub const SOURCE_TIMER: u8 = 0;
+pub const SOURCE_UART: u8 = 1;
+pub const SOURCE_VBLANK: u8 = 2;
+pub const SOURCE_KEYBOARD: u8 = 3;
+/// Raised by `Bw8::inject_irq`, such as from the emulator's IRQ button.
+pub const SOURCE_EXTERNAL: u8 = 7;
+
+pub const NO_SOURCE: Byte = 0xFF;
+
+pub(crate) struct InterruptController {
+    pending: Byte,
+    mask: Byte,
+    current: Byte,
+}
+
+impl InterruptController {
+    pub fn new() -> Self {
+        Self {
+            pending: 0,
+            mask: 0xFF,
+            current: NO_SOURCE,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Latches a request from `source` while its line is asserted.
+    pub fn sample(&mut self, source: u8, line: bool) {
+        if line {
+            self.pending |= 1 << source;
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.pending & self.mask != 0
+    }
+
+    /// Records the highest priority source as the one being serviced, when
+    /// the processor takes an IRQ.
+    pub fn acknowledge(&mut self) {
+        let active = self.pending & self.mask;
+        if active != 0 {
+            self.current = active.trailing_zeros() as Byte;
+        }
+    }
+
+    /// Withdraws pending requests from the sources in `sources`.
+    pub fn clear(&mut self, sources: Byte) {
+        self.pending &= !sources;
+        if self.current != NO_SOURCE && sources & (1 << self.current) != 0 {
+            self.current = NO_SOURCE;
+        }
+    }
+
+    pub fn read(&mut self, address: Address) -> Byte {
+        match address {
+            INTC_PENDING => self.pending,
+            INTC_MASK => self.mask,
+            INTC_CURRENT => self.current,
+            _ => 0,
+        }
+    }
+
+    pub fn write(&mut self, address: Address, data: Byte) {
+        match address {
+            INTC_PENDING => self.clear(data),
+            INTC_MASK => self.mask = data,
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,311 @@
+use std::collections::VecDeque;
+
+use arch::{Address, Byte};
+
+/// The keyboard's registers occupy three I/O addresses from here.
+pub const KEYBOARD_BASE: Address = 0x0050;
+
+/// Reads pop the scancode FIFO, returning zero when it is empty.
+pub const KEYBOARD_DATA: Address = KEYBOARD_BASE;
+/// Read-only; see the `STATUS_` bits.
+pub const KEYBOARD_STATUS: Address = KEYBOARD_BASE + 1;
+/// See the `CONTROL_` bits.
+pub const KEYBOARD_CONTROL: Address = KEYBOARD_BASE + 2;
+
+/// The scancode FIFO holds at least one byte.
+pub const STATUS_READY: Byte = 0b0000_0001;
+
+/// Raise an IRQ while the scancode FIFO holds at least one byte.
+pub const CONTROL_IRQ_ENABLE: Byte = 0b0000_0001;
+
+pub const FIFO_DEPTH: usize = 16;
+
+/// Sent before the scancode of a key that is released.
+pub const BREAK_PREFIX: Byte = 0xF0;
+/// Sent before the scancode of each key in the extended set.
+pub const EXTENDED_PREFIX: Byte = 0xE0;
+
+/// A PS/2 keyboard sends a byte in eleven bits of a roughly 12 kHz clock,
+/// which is about this many cycles.
+const CYCLES_PER_BYTE: usize = 1_000;
+
+/// A key, identified by its PS/2 scancode in set 2.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Key {
+    extended: bool,
+    code: Byte,
+}
+
+impl Key {
+    const fn new(code: Byte) -> Self {
+        Self {
+            extended: false,
+            code,
+        }
+    }
+
+    const fn extended(code: Byte) -> Self {
+        Self {
+            extended: true,
+            code,
+        }
+    }
+
+    pub const A: Key = Key::new(0x1C);
+    pub const B: Key = Key::new(0x32);
+    pub const C: Key = Key::new(0x21);
+    pub const D: Key = Key::new(0x23);
+    pub const E: Key = Key::new(0x24);
+    pub const F: Key = Key::new(0x2B);
+    pub const G: Key = Key::new(0x34);
+    pub const H: Key = Key::new(0x33);
+    pub const I: Key = Key::new(0x43);
+    pub const J: Key = Key::new(0x3B);
+    pub const K: Key = Key::new(0x42);
+    pub const L: Key = Key::new(0x4B);
+    pub const M: Key = Key::new(0x3A);
+    pub const N: Key = Key::new(0x31);
+    pub const O: Key = Key::new(0x44);
+    pub const P: Key = Key::new(0x4D);
+    pub const Q: Key = Key::new(0x15);
+    pub const R: Key = Key::new(0x2D);
+    pub const S: Key = Key::new(0x1B);
+    pub const T: Key = Key::new(0x2C);
+    pub const U: Key = Key::new(0x3C);
+    pub const V: Key = Key::new(0x2A);
+    pub const W: Key = Key::new(0x1D);
+    pub const X: Key = Key::new(0x22);
+    pub const Y: Key = Key::new(0x35);
+    pub const Z: Key = Key::new(0x1A);
+
+    pub const DIGIT_0: Key = Key::new(0x45);
+    pub const DIGIT_1: Key = Key::new(0x16);
+    pub const DIGIT_2: Key = Key::new(0x1E);
+    pub const DIGIT_3: Key = Key::new(0x26);
+    pub const DIGIT_4: Key = Key::new(0x25);
+    pub const DIGIT_5: Key = Key::new(0x2E);
+    pub const DIGIT_6: Key = Key::new(0x36);
+    pub const DIGIT_7: Key = Key::new(0x3D);
+    pub const DIGIT_8: Key = Key::new(0x3E);
+    pub const DIGIT_9: Key = Key::new(0x46);
+
+    pub const BACKQUOTE: Key = Key::new(0x0E);
+    pub const MINUS: Key = Key::new(0x4E);
+    pub const EQUAL: Key = Key::new(0x55);
+    pub const BRACKET_LEFT: Key = Key::new(0x54);
+    pub const BRACKET_RIGHT: Key = Key::new(0x5B);
+    pub const BACKSLASH: Key = Key::new(0x5D);
+    pub const SEMICOLON: Key = Key::new(0x4C);
+    pub const QUOTE: Key = Key::new(0x52);
+    pub const COMMA: Key = Key::new(0x41);
+    pub const PERIOD: Key = Key::new(0x49);
+    pub const SLASH: Key = Key::new(0x4A);
+
+    pub const SPACE: Key = Key::new(0x29);
+    pub const ENTER: Key = Key::new(0x5A);
+    pub const TAB: Key = Key::new(0x0D);
+    pub const BACKSPACE: Key = Key::new(0x66);
+    pub const ESCAPE: Key = Key::new(0x76);
+    pub const CAPS_LOCK: Key = Key::new(0x58);
+    pub const SHIFT_LEFT: Key = Key::new(0x12);
+    pub const SHIFT_RIGHT: Key = Key::new(0x59);
+    pub const CONTROL_LEFT: Key = Key::new(0x14);
+    pub const CONTROL_RIGHT: Key = Key::extended(0x14);
+    pub const ALT_LEFT: Key = Key::new(0x11);
+    pub const ALT_RIGHT: Key = Key::extended(0x11);
+
+    pub const F1: Key = Key::new(0x05);
+    pub const F2: Key = Key::new(0x06);
+    pub const F3: Key = Key::new(0x04);
+    pub const F4: Key = Key::new(0x0C);
+    pub const F5: Key = Key::new(0x03);
+    pub const F6: Key = Key::new(0x0B);
+    pub const F7: Key = Key::new(0x83);
+    pub const F8: Key = Key::new(0x0A);
+    pub const F9: Key = Key::new(0x01);
+    pub const F10: Key = Key::new(0x09);
+    pub const F11: Key = Key::new(0x78);
+    pub const F12: Key = Key::new(0x07);
+
+    pub const INSERT: Key = Key::extended(0x70);
+    pub const DELETE: Key = Key::extended(0x71);
+    pub const HOME: Key = Key::extended(0x6C);
+    pub const END: Key = Key::extended(0x69);
+    pub const PAGE_UP: Key = Key::extended(0x7D);
+    pub const PAGE_DOWN: Key = Key::extended(0x7A);
+    pub const ARROW_UP: Key = Key::extended(0x75);
+    pub const ARROW_DOWN: Key = Key::extended(0x72);
+    pub const ARROW_LEFT: Key = Key::extended(0x6B);
+    pub const ARROW_RIGHT: Key = Key::extended(0x74);
+
+    /// The key's scancode, without any prefix.
+    pub const fn code(self) -> Byte {
+        self.code
+    }
+
+    /// Whether the key's scancodes are sent after `EXTENDED_PREFIX`.
+    pub const fn is_extended(self) -> bool {
+        self.extended
+    }
+
+    /// The key that types `character` on a US layout, and whether shift must
+    /// be held for it.
+    pub fn for_char(character: char) -> Option<(Key, bool)> {
+        US_LAYOUT.iter().find_map(|&(key, plain, shifted)| {
+            if character == plain {
+                Some((key, false))
+            } else if character == shifted {
+                Some((key, true))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// Each key with the characters it types, without and with shift.
+const US_LAYOUT: [(Key, char, char); 50] = [
+    (Key::A, 'a', 'A'),
+    (Key::B, 'b', 'B'),
+    (Key::C, 'c', 'C'),
+    (Key::D, 'd', 'D'),
+    (Key::E, 'e', 'E'),
+    (Key::F, 'f', 'F'),
+    (Key::G, 'g', 'G'),
+    (Key::H, 'h', 'H'),
+    (Key::I, 'i', 'I'),
+    (Key::J, 'j', 'J'),
+    (Key::K, 'k', 'K'),
+    (Key::L, 'l', 'L'),
+    (Key::M, 'm', 'M'),
+    (Key::N, 'n', 'N'),
+    (Key::O, 'o', 'O'),
+    (Key::P, 'p', 'P'),
+    (Key::Q, 'q', 'Q'),
+    (Key::R, 'r', 'R'),
+    (Key::S, 's', 'S'),
+    (Key::T, 't', 'T'),
+    (Key::U, 'u', 'U'),
+    (Key::V, 'v', 'V'),
+    (Key::W, 'w', 'W'),
+    (Key::X, 'x', 'X'),
+    (Key::Y, 'y', 'Y'),
+    (Key::Z, 'z', 'Z'),
+    (Key::DIGIT_0, '0', ')'),
+    (Key::DIGIT_1, '1', '!'),
+    (Key::DIGIT_2, '2', '@'),
+    (Key::DIGIT_3, '3', '#'),
+    (Key::DIGIT_4, '4', '$'),
+    (Key::DIGIT_5, '5', '%'),
+    (Key::DIGIT_6, '6', '^'),
+    (Key::DIGIT_7, '7', '&'),
+    (Key::DIGIT_8, '8', '*'),
+    (Key::DIGIT_9, '9', '('),
+    (Key::BACKQUOTE, '`', '~'),
+    (Key::MINUS, '-', '_'),
+    (Key::EQUAL, '=', '+'),
+    (Key::BRACKET_LEFT, '[', '{'),
+    (Key::BRACKET_RIGHT, ']', '}'),
+    (Key::BACKSLASH, '\\', '|'),
+    (Key::SEMICOLON, ';', ':'),
+    (Key::QUOTE, '\'', '"'),
+    (Key::COMMA, ',', '<'),
+    (Key::PERIOD, '.', '>'),
+    (Key::SLASH, '/', '?'),
+    (Key::SPACE, ' ', ' '),
+    (Key::ENTER, '\n', '\n'),
+    (Key::TAB, '\t', '\t'),
+];
+
+pub(crate) struct Keyboard {
+    /// Scancode bytes the keyboard has yet to send.
+    outgoing: VecDeque<Byte>,
+    fifo: VecDeque<Byte>,
+    control: Byte,
+    /// Cycles remaining until the next byte is sent.
+    countdown: usize,
+}
+
+impl Keyboard {
+    pub fn new() -> Self {
+        Self {
+            outgoing: VecDeque::new(),
+            fifo: VecDeque::with_capacity(FIFO_DEPTH),
+            control: 0,
+            countdown: CYCLES_PER_BYTE,
+        }
+    }
+
+    /// Empties the FIFO and resets the registers. Keys still to be sent are
+    /// held by the keyboard itself, so they survive, and can be queued before
+    /// the system is first reset.
+    pub fn reset(&mut self) {
+        self.fifo.clear();
+        self.control = 0;
+        self.countdown = CYCLES_PER_BYTE;
+    }
+
+    pub fn press(&mut self, key: Key) {
+        if key.extended {
+            self.outgoing.push_back(EXTENDED_PREFIX);
+        }
+        self.outgoing.push_back(key.code);
+    }
+
+    pub fn release(&mut self, key: Key) {
+        if key.extended {
+            self.outgoing.push_back(EXTENDED_PREFIX);
+        }
+        self.outgoing.extend([BREAK_PREFIX, key.code]);
+    }
+
+    /// Presses and releases the keys that type `text`, holding shift where
+    /// needed. Characters with no key are skipped.
+    pub fn type_text(&mut self, text: &str) {
+        for (key, shift) in text.chars().filter_map(Key::for_char) {
+            if shift {
+                self.press(Key::SHIFT_LEFT);
+            }
+            self.press(key);
+            self.release(key);
+            if shift {
+                self.release(Key::SHIFT_LEFT);
+            }
+        }
+    }
+
+    pub fn clock(&mut self) {
+        self.countdown -= 1;
+        if self.countdown > 0 {
+            return;
+        }
+        self.countdown = CYCLES_PER_BYTE;
+
+        // Like a real keyboard, hold on to bytes while the host can't take
+        // them, rather than dropping them.
+        if self.fifo.len() < FIFO_DEPTH {
+            if let Some(data) = self.outgoing.pop_front() {
+                self.fifo.push_back(data);
+            }
+        }
+    }
+
+    pub fn irq(&self) -> bool {
+        self.control & CONTROL_IRQ_ENABLE != 0 && !self.fifo.is_empty()
+    }
+
+    pub fn read(&mut self, address: Address) -> Byte {
+        match address {
+            KEYBOARD_DATA => self.fifo.pop_front().unwrap_or(0),
+            KEYBOARD_STATUS if !self.fifo.is_empty() => STATUS_READY,
+            KEYBOARD_CONTROL => self.control,
+            _ => 0,
+        }
+    }
+
+    pub fn write(&mut self, address: Address, data: Byte) {
+        if address == KEYBOARD_CONTROL {
+            self.control = data;
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use winit::keyboard::KeyCode;
+
+use emu::keyboard::Key;
+
+/// The bw8 key at the same position as the host's `code`, if it has one.
+pub fn key(code: KeyCode) -> Option<Key> {
+    let key = match code {
+        KeyCode::KeyA => Key::A,
+        KeyCode::KeyB => Key::B,
+        KeyCode::KeyC => Key::C,
+        KeyCode::KeyD => Key::D,
+        KeyCode::KeyE => Key::E,
+        KeyCode::KeyF => Key::F,
+        KeyCode::KeyG => Key::G,
+        KeyCode::KeyH => Key::H,
+        KeyCode::KeyI => Key::I,
+        KeyCode::KeyJ => Key::J,
+        KeyCode::KeyK => Key::K,
+        KeyCode::KeyL => Key::L,
+        KeyCode::KeyM => Key::M,
+        KeyCode::KeyN => Key::N,
+        KeyCode::KeyO => Key::O,
+        KeyCode::KeyP => Key::P,
+        KeyCode::KeyQ => Key::Q,
+        KeyCode::KeyR => Key::R,
+        KeyCode::KeyS => Key::S,
+        KeyCode::KeyT => Key::T,
+        KeyCode::KeyU => Key::U,
+        KeyCode::KeyV => Key::V,
+        KeyCode::KeyW => Key::W,
+        KeyCode::KeyX => Key::X,
+        KeyCode::KeyY => Key::Y,
+        KeyCode::KeyZ => Key::Z,
+
+        KeyCode::Digit0 => Key::DIGIT_0,
+        KeyCode::Digit1 => Key::DIGIT_1,
+        KeyCode::Digit2 => Key::DIGIT_2,
+        KeyCode::Digit3 => Key::DIGIT_3,
+        KeyCode::Digit4 => Key::DIGIT_4,
+        KeyCode::Digit5 => Key::DIGIT_5,
+        KeyCode::Digit6 => Key::DIGIT_6,
+        KeyCode::Digit7 => Key::DIGIT_7,
+        KeyCode::Digit8 => Key::DIGIT_8,
+        KeyCode::Digit9 => Key::DIGIT_9,
+
+        KeyCode::Backquote => Key::BACKQUOTE,
+        KeyCode::Minus => Key::MINUS,
+        KeyCode::Equal => Key::EQUAL,
+        KeyCode::BracketLeft => Key::BRACKET_LEFT,
+        KeyCode::BracketRight => Key::BRACKET_RIGHT,
+        KeyCode::Backslash => Key::BACKSLASH,
+        KeyCode::Semicolon => Key::SEMICOLON,
+        KeyCode::Quote => Key::QUOTE,
+        KeyCode::Comma => Key::COMMA,
+        KeyCode::Period => Key::PERIOD,
+        KeyCode::Slash => Key::SLASH,
+
+        KeyCode::Space => Key::SPACE,
+        KeyCode::Enter => Key::ENTER,
+        KeyCode::Tab => Key::TAB,
+        KeyCode::Backspace => Key::BACKSPACE,
+        KeyCode::Escape => Key::ESCAPE,
+        KeyCode::CapsLock => Key::CAPS_LOCK,
+        KeyCode::ShiftLeft => Key::SHIFT_LEFT,
+        KeyCode::ShiftRight => Key::SHIFT_RIGHT,
+        KeyCode::ControlLeft => Key::CONTROL_LEFT,
+        KeyCode::ControlRight => Key::CONTROL_RIGHT,
+        KeyCode::AltLeft => Key::ALT_LEFT,
+        KeyCode::AltRight => Key::ALT_RIGHT,
+
+        KeyCode::F1 => Key::F1,
+        KeyCode::F2 => Key::F2,
+        KeyCode::F3 => Key::F3,
+        KeyCode::F4 => Key::F4,
+        KeyCode::F5 => Key::F5,
+        KeyCode::F6 => Key::F6,
+        KeyCode::F7 => Key::F7,
+        KeyCode::F8 => Key::F8,
+        KeyCode::F9 => Key::F9,
+        KeyCode::F10 => Key::F10,
+        KeyCode::F11 => Key::F11,
+        KeyCode::F12 => Key::F12,
+
+        KeyCode::Insert => Key::INSERT,
+        KeyCode::Delete => Key::DELETE,
+        KeyCode::Home => Key::HOME,
+        KeyCode::End => Key::END,
+        KeyCode::PageUp => Key::PAGE_UP,
+        KeyCode::PageDown => Key::PAGE_DOWN,
+        KeyCode::ArrowUp => Key::ARROW_UP,
+        KeyCode::ArrowDown => Key::ARROW_DOWN,
+        KeyCode::ArrowLeft => Key::ARROW_LEFT,
+        KeyCode::ArrowRight => Key::ARROW_RIGHT,
+
+        _ => return None,
+    };
+
+    Some(key)
+}
@@ -0,0 +1,185 @@
+mod bus;
+pub mod font;
+pub mod intc;
+pub mod keyboard;
+pub mod pit;
+pub mod serial;
+pub mod uart;
+pub mod vga;
+
+use arch::{Address, Byte, CpuState, Nibble, PhysicalAddress, PrivilegeLevel, StopReason};
+
+pub use bus::{BANK_COUNT, BANK_SIZE, RAM_BASE};
+
+/// The size of the largest image `Bw8::from_image` accepts; one bank.
+pub const MAX_IMAGE_SIZE: usize = BANK_SIZE;
+
+#[derive(Debug)]
+pub struct ImageTooLarge {
+    pub size: usize,
+}
+
+impl std::fmt::Display for ImageTooLarge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "image is {} bytes but at most {} bytes can be loaded",
+            self.size, MAX_IMAGE_SIZE
+        )
+    }
+}
+
+impl std::error::Error for ImageTooLarge {}
+
+/// The complete bw8 computer: the processor, its memory and peripherals.
+pub struct Bw8 {
+    cpu: CpuState,
+    bus: bus::Bw8Bus,
+}
+
+impl Bw8 {
+    /// Creates a system whose memory starts with `image`, loaded at address 0
+    /// of bank 0. The part of the image below `RAM_BASE` is ROM, and is also
+    /// mapped into every other bank. The system must be reset before it is run.
+    pub fn from_image(image: &[Byte]) -> Result<Self, ImageTooLarge> {
+        if image.len() > MAX_IMAGE_SIZE {
+            return Err(ImageTooLarge { size: image.len() });
+        }
+
+        Ok(Self {
+            cpu: CpuState::new(),
+            bus: bus::Bw8Bus::from_image(image),
+        })
+    }
+
+    /// Runs for `cycles` clock cycles, stopping early at a breakpoint or halt.
+    /// The last instruction is completed even if it runs past `cycles`, so
+    /// the number of cycles that elapsed is returned.
+    pub fn run(&mut self, cycles: usize) -> (arch::trace::Trace, StopReason, usize) {
+        self.cpu.run(&mut self.bus, cycles)
+    }
+
+    /// Runs a single instruction or interrupt entry.
+    pub fn step(&mut self) -> StopReason {
+        self.run(1).1
+    }
+
+    /// The processor's clock, in cycles per second. It starts at
+    /// `vga::CLOCK_RATE`.
+    pub fn clock_rate(&self) -> f64 {
+        self.bus.clock_rate() as f64
+    }
+
+    /// Sets the processor's clock. The other peripherals count processor
+    /// cycles and speed up or slow down with it, but the VGA keeps its own
+    /// clock, so it still scans `vga::FRAMERATE` frames each emulated second.
+    pub fn set_clock_rate(&mut self, rate: f64) {
+        self.bus.set_clock_rate(rate.round() as u64);
+    }
+
+    pub fn reset(&mut self) {
+        self.bus.set_reset(true);
+        self.cpu.run(&mut self.bus, 1);
+        self.bus.set_reset(false);
+        self.bus.reset();
+    }
+
+    pub fn inject_irq(&mut self) {
+        self.bus.set_irq(true);
+    }
+
+    pub fn clear_irq(&mut self) {
+        self.bus.set_irq(false);
+    }
+
+    pub fn inject_nmi(&mut self) {
+        self.bus.set_nmi(true);
+    }
+
+    /// Queues the scancode for pressing `key`. The keyboard sends queued
+    /// bytes one at a time, as fast as a real keyboard would.
+    pub fn press_key(&mut self, key: keyboard::Key) {
+        self.bus.keyboard.press(key);
+    }
+
+    pub fn release_key(&mut self, key: keyboard::Key) {
+        self.bus.keyboard.release(key);
+    }
+
+    /// Queues the scancodes for typing `text` on a US layout, pressing and
+    /// releasing a key for each character.
+    pub fn type_text(&mut self, text: &str) {
+        self.bus.keyboard.type_text(text);
+    }
+
+    /// Connects the UART's serial line to `backend`, replacing whatever was
+    /// connected before. The line starts out disconnected.
+    pub fn connect_serial(&mut self, backend: Box<dyn serial::SerialBackend>) {
+        self.bus.uart.connect(backend);
+    }
+
+    /// Takes the bytes the program has written to the `EMULATOR_PUTCHAR`
+    /// port since the last call, exactly as written.
+    pub fn take_output(&mut self) -> Vec<Byte> {
+        std::mem::take(&mut self.bus.output)
+    }
+
+    /// Fills the VGA's bitmaps with the built-in font, so that tile `n` is
+    /// the glyph for CP437 character `n`, drawn in color 1 of its palette.
+    pub fn load_font(&mut self) {
+        self.bus.vga.load_font();
+    }
+
+    pub fn cpu(&self) -> &CpuState {
+        &self.cpu
+    }
+
+    /// Gives direct access to the processor, for example to set up registers
+    /// before running a snippet.
+    pub fn cpu_mut(&mut self) -> &mut CpuState {
+        &mut self.cpu
+    }
+
+    /// Reads memory in bank 0, where the kernel runs.
+    pub fn read_memory(&self, address: Address) -> Byte {
+        self.read_physical_memory(PhysicalAddress::new(Nibble::new(0).unwrap(), address))
+    }
+
+    /// Reads memory in the bank the processor currently fetches code from: the
+    /// bank register in user mode, bank 0 in kernel mode.
+    pub fn read_code_memory(&self, address: Address) -> Byte {
+        let bank = match self.cpu.status().privilege_level {
+            PrivilegeLevel::User => *self.cpu.br(),
+            PrivilegeLevel::Kernel => Nibble::new(0).unwrap(),
+        };
+        self.read_physical_memory(PhysicalAddress::new(bank, address))
+    }
+
+    /// Writes memory in bank 0 directly, bypassing the bus, so that ROM can be
+    /// patched.
+    pub fn write_memory(&mut self, address: Address, data: Byte) {
+        self.write_physical_memory(PhysicalAddress::new(Nibble::new(0).unwrap(), address), data);
+    }
+
+    pub fn read_physical_memory(&self, address: PhysicalAddress) -> Byte {
+        self.bus.inspect_memory(address)
+    }
+
+    /// Writes memory in any bank directly, bypassing the bus, so that ROM can
+    /// be patched.
+    pub fn write_physical_memory(&mut self, address: PhysicalAddress, data: Byte) {
+        self.bus.patch_memory(address, data);
+    }
+
+    /// Reads the VGA's memory, relative to the start of its IO window.
+    pub fn read_framebuffer(&self, address: Address) -> Byte {
+        self.bus.vga.inspect_memory(address)
+    }
+
+    /// The picture on the screen, as RGBA pixels in rows of
+    /// `vga::COLUMN_COUNT`. Each line is drawn as the beam scans it, so while
+    /// the system is running the picture may hold parts of two frames.
+    pub fn vga_frame(&self) -> &[u8] {
+        self.bus.vga.pixel_data()
+    }
+}
@@ -1,11 +1,24 @@
-mod emu;
+mod headless;
+#[cfg(feature = "gui")]
+mod keymap;
+#[cfg(feature = "gui")]
+mod ui;
 
+#[cfg(feature = "gui")]
 use egui_wgpu::winit::Painter;
+use std::collections::BTreeMap;
+#[cfg(feature = "gui")]
 use std::sync::Arc;
+#[cfg(feature = "gui")]
 use winit::window::Window;
 
-use emu::{Bw8, EmulatorState};
+use arch::Address;
+use emu::serial::{self, SerialBackend};
+use emu::Bw8;
+#[cfg(feature = "gui")]
+use ui::EmulatorState;
 
+#[cfg(feature = "gui")]
 struct AppState {
     window: Arc<Window>,
     ui_context: egui::Context,
@@ -15,14 +28,149 @@ struct AppState {
     emu_state: EmulatorState,
 }
 
+struct Args {
+    binary_path: String,
+    headless: bool,
+    cycle_limit: Option<usize>,
+    serial: Option<String>,
+    font: bool,
+    typed_text: Option<String>,
+    symbols: Option<String>,
+}
+
+fn usage() -> ! {
+    eprintln!(
+        "usage: emu [--headless [--cycles <count>]] [--serial stdio|pty|unix:<path>] [--font] [--type <text>] [--symbols <path>] <binary>"
+    );
+    std::process::exit(2);
+}
+
+fn parse_args() -> Args {
+    let mut args = std::env::args().skip(1);
+    let mut binary_path = None;
+    let mut headless = false;
+    let mut cycle_limit = None;
+    let mut serial = None;
+    let mut font = false;
+    let mut typed_text = None;
+    let mut symbols = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--headless" => headless = true,
+            "--cycles" => {
+                let count = args.next().and_then(|count| count.parse().ok());
+                cycle_limit = Some(count.unwrap_or_else(|| usage()));
+            }
+            "--serial" => serial = Some(args.next().unwrap_or_else(|| usage())),
+            "--font" => font = true,
+            "--type" => typed_text = Some(args.next().unwrap_or_else(|| usage())),
+            "--symbols" => symbols = Some(args.next().unwrap_or_else(|| usage())),
+            _ if binary_path.is_none() => binary_path = Some(arg),
+            _ => usage(),
+        }
+    }
+
+    Args {
+        binary_path: binary_path.unwrap_or_else(|| usage()),
+        headless,
+        cycle_limit,
+        serial,
+        font,
+        typed_text,
+        symbols,
+    }
+}
+
+/// Reads a symbol file written by `asm -s`: an address in hexadecimal and a
+/// name on each line. The first name given to an address is kept.
+fn read_symbols(path: &str) -> Result<BTreeMap<Address, String>, Box<dyn std::error::Error>> {
+    let mut symbols = BTreeMap::new();
+
+    for (number, line) in std::fs::read_to_string(path)?.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let symbol = line
+            .split_once(' ')
+            .and_then(|(address, name)| Some((Address::from_str_radix(address, 16).ok()?, name)));
+        let Some((address, name)) = symbol else {
+            return Err(format!("{}:{}: malformed symbol", path, number + 1).into());
+        };
+        symbols
+            .entry(address)
+            .or_insert_with(|| name.trim().to_string());
+    }
+
+    Ok(symbols)
+}
+
+fn open_serial(name: &str) -> Result<Box<dyn SerialBackend>, Box<dyn std::error::Error>> {
+    match name {
+        "stdio" => Ok(Box::new(serial::Stdio::new())),
+        #[cfg(unix)]
+        "pty" => {
+            let pty = serial::Pty::open()?;
+            eprintln!("serial: {}", pty.path().display());
+            Ok(Box::new(pty))
+        }
+        #[cfg(unix)]
+        _ if name.starts_with("unix:") => Ok(Box::new(serial::UnixSocket::bind(&name[5..])?)),
+        _ => usage(),
+    }
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = parse_args();
+
+    let image = std::fs::read(&args.binary_path)?;
+    let mut system = Bw8::from_image(&image)?;
+    let symbols = args.symbols.as_deref().map(read_symbols).transpose()?;
+
+    if let Some(name) = &args.serial {
+        system.connect_serial(open_serial(name)?);
+    }
+
+    if args.font {
+        system.load_font();
+    }
+
+    if let Some(text) = &args.typed_text {
+        system.type_text(text);
+    }
+
+    if args.headless {
+        std::process::exit(headless::run(system, args.cycle_limit));
+    }
+
+    run_window(system, symbols.unwrap_or_default())
+}
+
+#[cfg(not(feature = "gui"))]
+fn run_window(
+    _system: Bw8,
+    _symbols: BTreeMap<Address, String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    Err("built without the `gui` feature; pass --headless".into())
+}
+
+#[cfg(feature = "gui")]
+fn run_window(
+    system: Bw8,
+    symbols: BTreeMap<Address, String>,
+) -> Result<(), Box<dyn std::error::Error>> {
     use egui_wgpu::WgpuConfiguration;
     use std::num::NonZeroU32;
     use winit::dpi::PhysicalSize;
-    use winit::event::{Event, WindowEvent};
+    use winit::event::{ElementState, Event, WindowEvent};
     use winit::event_loop::{ControlFlow, EventLoop};
+    use winit::keyboard::PhysicalKey;
     use winit::window::WindowBuilder;
 
+    let mut system = Some(system);
+    let mut symbols = Some(symbols);
+
     let event_loop = EventLoop::new()?;
     let mut app_state = None;
 
@@ -31,6 +179,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         match event {
             Event::Resumed => {
+                let Some(mut system) = system.take() else {
+                    return;
+                };
+                let symbols = symbols.take().unwrap_or_default();
+
                 let window = Arc::new(
                     WindowBuilder::new()
                         .with_title("bw8 Emulator")
@@ -67,8 +220,6 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     ..Default::default()
                 });
 
-                let binary_path = std::env::args().nth(1).unwrap();
-                let mut system = Bw8::new(binary_path);
                 system.reset();
 
                 // if let Some(program) = args.run.as_deref() {
@@ -83,7 +234,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 )
                 .unwrap();
 
-                let emu_state = EmulatorState::new(&ui_context);
+                let emu_state = EmulatorState::new(&ui_context, symbols);
 
                 app_state = Some(AppState {
                     window,
@@ -96,60 +247,72 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
             Event::WindowEvent { window_id, event } => {
                 if let Some(app_state) = app_state.as_mut() {
-                    if window_id == app_state.window.id() {
-                        if !app_state
+                    if window_id == app_state.window.id()
+                        && !app_state
                             .ui_state
                             .on_window_event(&app_state.window, &event)
                             .consumed
-                        {
-                            match event {
-                                WindowEvent::CloseRequested => {
-                                    window_target.exit();
-                                    app_state.emu_state.quit(&mut app_state.system);
-                                }
-                                WindowEvent::Resized(size) => {
-                                    let width =
-                                        NonZeroU32::new(size.width).unwrap_or(NonZeroU32::MIN);
-                                    let height =
-                                        NonZeroU32::new(size.height).unwrap_or(NonZeroU32::MIN);
-                                    app_state.ui_painter.on_window_resized(
-                                        app_state.ui_context.viewport_id(),
-                                        width,
-                                        height,
-                                    )
+                    {
+                        match event {
+                            WindowEvent::CloseRequested => {
+                                window_target.exit();
+                                app_state.emu_state.quit(&mut app_state.system);
+                            }
+                            WindowEvent::KeyboardInput { event, .. } => {
+                                if let PhysicalKey::Code(code) = event.physical_key {
+                                    if let Some(key) = keymap::key(code) {
+                                        match event.state {
+                                            ElementState::Pressed => {
+                                                app_state.system.press_key(key)
+                                            }
+                                            ElementState::Released => {
+                                                app_state.system.release_key(key)
+                                            }
+                                        }
+                                    }
                                 }
-                                WindowEvent::RedrawRequested => {
-                                    app_state.emu_state.update(&mut app_state.system);
-                                    let ui_input =
-                                        app_state.ui_state.take_egui_input(&app_state.window);
-                                    let ui_output = app_state.ui_context.run(ui_input, |ctx| {
-                                        egui::CentralPanel::default().show(ctx, |ui| {
-                                            app_state.emu_state.draw(&mut app_state.system, ui);
-                                        });
+                            }
+                            WindowEvent::Resized(size) => {
+                                let width = NonZeroU32::new(size.width).unwrap_or(NonZeroU32::MIN);
+                                let height =
+                                    NonZeroU32::new(size.height).unwrap_or(NonZeroU32::MIN);
+                                app_state.ui_painter.on_window_resized(
+                                    app_state.ui_context.viewport_id(),
+                                    width,
+                                    height,
+                                )
+                            }
+                            WindowEvent::RedrawRequested => {
+                                app_state.emu_state.update(&mut app_state.system);
+                                let ui_input =
+                                    app_state.ui_state.take_egui_input(&app_state.window);
+                                let ui_output = app_state.ui_context.run(ui_input, |ctx| {
+                                    egui::CentralPanel::default().show(ctx, |ui| {
+                                        app_state.emu_state.draw(&mut app_state.system, ui);
                                     });
+                                });
 
-                                    app_state.ui_state.handle_platform_output(
-                                        &app_state.window,
-                                        ui_output.platform_output,
-                                    );
-
-                                    let ui_primitives = app_state.ui_context.tessellate(
-                                        ui_output.shapes,
-                                        app_state.ui_context.pixels_per_point(),
-                                    );
-                                    app_state.ui_painter.paint_and_update_textures(
-                                        app_state.ui_context.viewport_id(),
-                                        app_state.ui_context.pixels_per_point(),
-                                        egui::Rgba::BLACK.to_array(),
-                                        &ui_primitives,
-                                        &ui_output.textures_delta,
-                                        false,
-                                    );
-
-                                    app_state.window.request_redraw();
-                                }
-                                _ => {}
+                                app_state.ui_state.handle_platform_output(
+                                    &app_state.window,
+                                    ui_output.platform_output,
+                                );
+
+                                let ui_primitives = app_state.ui_context.tessellate(
+                                    ui_output.shapes,
+                                    app_state.ui_context.pixels_per_point(),
+                                );
+                                app_state.ui_painter.paint_and_update_textures(
+                                    app_state.ui_context.viewport_id(),
+                                    app_state.ui_context.pixels_per_point(),
+                                    egui::Rgba::BLACK.to_array(),
+                                    &ui_primitives,
+                                    &ui_output.textures_delta,
+                                    false,
+                                );
+
+                                app_state.window.request_redraw();
                             }
+                            _ => {}
                         }
                     }
                 }
@@ -0,0 +1,127 @@
+use arch::{Address, Byte};
+
+/// The timer's registers occupy seven I/O addresses from here.
+pub const PIT_BASE: Address = 0x0020;
+
+/// See the `CONTROL_` bits. Writing a value with `CONTROL_ENABLE` set loads
+/// the counter from the reload registers.
+pub const PIT_CONTROL: Address = PIT_BASE;
+/// Reads return the `STATUS_` bits; any write acknowledges the timer's IRQ.
+pub const PIT_STATUS: Address = PIT_BASE + 1;
+/// The counter decrements once every `prescaler + 1` cycles.
+pub const PIT_PRESCALER: Address = PIT_BASE + 2;
+/// The number of counter ticks between expiries; zero means 65536.
+pub const PIT_RELOAD_LOW: Address = PIT_BASE + 3;
+pub const PIT_RELOAD_HIGH: Address = PIT_BASE + 4;
+/// Read-only. Reading the low byte latches the high byte, so that the two
+/// reads see the same count.
+pub const PIT_COUNT_LOW: Address = PIT_BASE + 5;
+pub const PIT_COUNT_HIGH: Address = PIT_BASE + 6;
+
+/// The counter is running.
+pub const CONTROL_ENABLE: Byte = 0b0000_0001;
+/// Reload and keep counting after each expiry, rather than stopping and
+/// clearing `CONTROL_ENABLE`.
+pub const CONTROL_PERIODIC: Byte = 0b0000_0010;
+/// Raise an IRQ while `STATUS_EXPIRED` is set.
+pub const CONTROL_IRQ_ENABLE: Byte = 0b0000_0100;
+
+/// The counter has expired since the last acknowledgement.
+pub const STATUS_EXPIRED: Byte = 0b0000_0001;
+
+pub(crate) struct Pit {
+    control: Byte,
+    prescaler: Byte,
+    reload: u16,
+    count: u16,
+    /// Cycles remaining until the counter next decrements.
+    prescale_count: usize,
+    expired: bool,
+    latched_count_high: Byte,
+}
+
+impl Pit {
+    pub fn new() -> Self {
+        Self {
+            control: 0,
+            prescaler: 0,
+            reload: 0,
+            count: 0,
+            prescale_count: 1,
+            expired: false,
+            latched_count_high: 0,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    pub fn clock(&mut self) {
+        if self.control & CONTROL_ENABLE == 0 {
+            return;
+        }
+
+        self.prescale_count -= 1;
+        if self.prescale_count > 0 {
+            return;
+        }
+        self.prescale_count = self.prescaler as usize + 1;
+
+        self.count = self.count.wrapping_sub(1);
+        if self.count > 0 {
+            return;
+        }
+
+        self.expired = true;
+
+        if self.control & CONTROL_PERIODIC != 0 {
+            self.count = self.reload;
+        } else {
+            self.control &= !CONTROL_ENABLE;
+        }
+    }
+
+    pub fn irq(&self) -> bool {
+        self.control & CONTROL_IRQ_ENABLE != 0 && self.expired
+    }
+
+    pub fn read(&mut self, address: Address) -> Byte {
+        let [count_low, count_high] = self.count.to_le_bytes();
+        let [reload_low, reload_high] = self.reload.to_le_bytes();
+
+        match address {
+            PIT_CONTROL => self.control,
+            PIT_STATUS => match self.expired {
+                true => STATUS_EXPIRED,
+                false => 0,
+            },
+            PIT_PRESCALER => self.prescaler,
+            PIT_RELOAD_LOW => reload_low,
+            PIT_RELOAD_HIGH => reload_high,
+            PIT_COUNT_LOW => {
+                self.latched_count_high = count_high;
+                count_low
+            }
+            PIT_COUNT_HIGH => self.latched_count_high,
+            _ => 0,
+        }
+    }
+
+    pub fn write(&mut self, address: Address, data: Byte) {
+        match address {
+            PIT_CONTROL => {
+                self.control = data;
+                if data & CONTROL_ENABLE != 0 {
+                    self.count = self.reload;
+                    self.prescale_count = self.prescaler as usize + 1;
+                }
+            }
+            PIT_STATUS => self.expired = false,
+            PIT_PRESCALER => self.prescaler = data,
+            PIT_RELOAD_LOW => self.reload = (self.reload & 0xFF00) | data as u16,
+            PIT_RELOAD_HIGH => self.reload = (self.reload & 0x00FF) | (data as u16) << 8,
+            _ => {}
+        }
+    }
+}
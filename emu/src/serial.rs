@@ -0,0 +1,251 @@
+//! Host-side ends of the UART's serial line.
+
+use std::io::{Read, Write};
+use std::sync::mpsc::{self, Receiver};
+
+use arch::Byte;
+
+/// The host end of the serial line. The UART polls it for received bytes and
+/// hands it every transmitted byte, once per character period.
+pub trait SerialBackend {
+    /// Returns the next byte sent by the host, if one has arrived. Must not
+    /// block.
+    fn receive(&mut self) -> Option<Byte>;
+
+    fn transmit(&mut self, data: Byte);
+}
+
+/// An unplugged line: transmitted bytes are discarded and nothing is ever
+/// received.
+pub struct Disconnected;
+
+impl SerialBackend for Disconnected {
+    fn receive(&mut self) -> Option<Byte> {
+        None
+    }
+
+    fn transmit(&mut self, _data: Byte) {}
+}
+
+/// Connects the line to the emulator's stdin and stdout.
+pub struct Stdio {
+    received: Receiver<Byte>,
+}
+
+impl Stdio {
+    pub fn new() -> Self {
+        Self {
+            received: spawn_reader(std::io::stdin()),
+        }
+    }
+}
+
+impl Default for Stdio {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SerialBackend for Stdio {
+    fn receive(&mut self) -> Option<Byte> {
+        self.received.try_recv().ok()
+    }
+
+    fn transmit(&mut self, data: Byte) {
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(&[data]).ok();
+        stdout.flush().ok();
+    }
+}
+
+/// Forwards everything read from `reader` to the returned channel, from a
+/// background thread, so that the UART can poll for input without blocking.
+fn spawn_reader(mut reader: impl Read + Send + 'static) -> Receiver<Byte> {
+    let (sender, receiver) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        let mut buffer = [0; 256];
+        while let Ok(count @ 1..) = reader.read(&mut buffer) {
+            for &byte in &buffer[..count] {
+                if sender.send(byte).is_err() {
+                    return;
+                }
+            }
+        }
+    });
+
+    receiver
+}
+
+#[cfg(unix)]
+pub use unix::{Pty, UnixSocket};
+
+#[cfg(unix)]
+mod unix {
+    use std::fs::File;
+    use std::io::{self, Write};
+    use std::os::fd::FromRawFd;
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::{Path, PathBuf};
+    use std::sync::mpsc::{self, Receiver};
+    use std::sync::{Arc, Mutex};
+
+    use arch::Byte;
+
+    use super::{spawn_reader, SerialBackend};
+
+    /// Listens on a Unix domain socket and connects the line to one client at
+    /// a time, for example `socat - UNIX-CONNECT:<path>`. Bytes transmitted
+    /// while no client is connected are discarded.
+    pub struct UnixSocket {
+        received: Receiver<Byte>,
+        client: Arc<Mutex<Option<UnixStream>>>,
+    }
+
+    impl UnixSocket {
+        pub fn bind(path: impl AsRef<Path>) -> io::Result<Self> {
+            let listener = UnixListener::bind(path)?;
+            let (sender, received) = mpsc::channel();
+            let client = Arc::new(Mutex::new(None));
+
+            let accepted = Arc::clone(&client);
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let Ok(stream) = stream else { continue };
+                    let Ok(writer) = stream.try_clone() else {
+                        continue;
+                    };
+                    *accepted.lock().unwrap() = Some(writer);
+
+                    for byte in spawn_reader(stream) {
+                        if sender.send(byte).is_err() {
+                            return;
+                        }
+                    }
+
+                    *accepted.lock().unwrap() = None;
+                }
+            });
+
+            Ok(Self { received, client })
+        }
+    }
+
+    impl SerialBackend for UnixSocket {
+        fn receive(&mut self) -> Option<Byte> {
+            self.received.try_recv().ok()
+        }
+
+        fn transmit(&mut self, data: Byte) {
+            let mut client = self.client.lock().unwrap();
+            if let Some(stream) = client.as_mut() {
+                if stream.write_all(&[data]).is_err() {
+                    *client = None;
+                }
+            }
+        }
+    }
+
+    /// Connects the line to a new pseudo-terminal, whose device can be opened
+    /// with a terminal program such as `screen` or `picocom`.
+    pub struct Pty {
+        received: Receiver<Byte>,
+        master: File,
+        // Held open so that the master doesn't report a hangup while no
+        // terminal program has the device open.
+        _slave: File,
+        path: PathBuf,
+    }
+
+    impl Pty {
+        pub fn open() -> io::Result<Self> {
+            let mut master = -1;
+            let mut slave = -1;
+
+            // SAFETY: `openpty` writes the two descriptors it opens, and the
+            // name, termios and window size arguments may be null.
+            let result = unsafe {
+                libc::openpty(
+                    &mut master,
+                    &mut slave,
+                    std::ptr::null_mut(),
+                    std::ptr::null_mut(),
+                    std::ptr::null_mut(),
+                )
+            };
+            if result != 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            // SAFETY: Both descriptors were just opened and are owned by
+            // nothing else.
+            let (master, slave) = unsafe { (File::from_raw_fd(master), File::from_raw_fd(slave)) };
+
+            let path = tty_name(&slave)?;
+
+            make_raw(&slave)?;
+
+            Ok(Self {
+                received: spawn_reader(master.try_clone()?),
+                master,
+                _slave: slave,
+                path,
+            })
+        }
+
+        /// The path of the terminal device to connect to.
+        pub fn path(&self) -> &Path {
+            &self.path
+        }
+    }
+
+    impl SerialBackend for Pty {
+        fn receive(&mut self) -> Option<Byte> {
+            self.received.try_recv().ok()
+        }
+
+        fn transmit(&mut self, data: Byte) {
+            self.master.write_all(&[data]).ok();
+        }
+    }
+
+    fn raw_fd(file: &File) -> libc::c_int {
+        use std::os::fd::AsRawFd;
+        file.as_raw_fd()
+    }
+
+    fn tty_name(file: &File) -> io::Result<PathBuf> {
+        let mut buffer = [0 as libc::c_char; 256];
+
+        // SAFETY: `ttyname_r` writes a nul-terminated string of at most
+        // `buffer.len()` bytes into the buffer, which is only read on success.
+        unsafe {
+            let result = libc::ttyname_r(raw_fd(file), buffer.as_mut_ptr(), buffer.len());
+            if result != 0 {
+                return Err(io::Error::from_raw_os_error(result));
+            }
+            let name = std::ffi::CStr::from_ptr(buffer.as_ptr());
+            Ok(PathBuf::from(name.to_string_lossy().into_owned()))
+        }
+    }
+
+    /// Disables line editing, echo and character translation on the
+    /// terminal, so that bytes pass between the UART and the terminal program
+    /// unchanged.
+    fn make_raw(file: &File) -> io::Result<()> {
+        // SAFETY: `termios` is a plain C struct that `tcgetattr` fills in
+        // before it is read.
+        unsafe {
+            let mut termios = std::mem::zeroed();
+            if libc::tcgetattr(raw_fd(file), &mut termios) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            libc::cfmakeraw(&mut termios);
+            if libc::tcsetattr(raw_fd(file), libc::TCSANOW, &termios) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::VecDeque;
+
+use arch::{Address, Byte};
+
+use crate::serial::{Disconnected, SerialBackend};
+
+/// The UART's registers occupy four I/O addresses from here.
+pub const UART_BASE: Address = 0x0010;
+
+/// Reads pop the receive FIFO, returning zero when it is empty; writes push
+/// the transmit FIFO, and are dropped when it is full.
+pub const UART_DATA: Address = UART_BASE;
+/// Read-only; see the `STATUS_` bits. Reading clears `STATUS_RX_OVERRUN`.
+pub const UART_STATUS: Address = UART_BASE + 1;
+/// See the `CONTROL_` bits.
+pub const UART_CONTROL: Address = UART_BASE + 2;
+/// One character is transferred in each direction every `(divisor + 1) * 16`
+/// cycles.
+pub const UART_DIVISOR: Address = UART_BASE + 3;
+
+/// The receive FIFO holds at least one byte.
+pub const STATUS_RX_READY: Byte = 0b0000_0001;
+/// The transmit FIFO has room for another byte.
+pub const STATUS_TX_READY: Byte = 0b0000_0010;
+/// The transmit FIFO is empty; every byte written has been sent.
+pub const STATUS_TX_EMPTY: Byte = 0b0000_0100;
+/// A byte arrived while the receive FIFO was full, and was lost.
+pub const STATUS_RX_OVERRUN: Byte = 0b0000_1000;
+
+/// Raise an IRQ while the receive FIFO holds at least one byte.
+pub const CONTROL_RX_IRQ_ENABLE: Byte = 0b0000_0001;
+
+pub const FIFO_DEPTH: usize = 16;
+
+const CYCLES_PER_DIVISOR_STEP: usize = 16;
+
+pub(crate) struct Uart {
+    backend: Box<dyn SerialBackend>,
+    rx_fifo: VecDeque<Byte>,
+    tx_fifo: VecDeque<Byte>,
+    rx_overrun: bool,
+    control: Byte,
+    divisor: Byte,
+    /// Cycles remaining in the current character period.
+    countdown: usize,
+}
+
+impl Uart {
+    pub fn new() -> Self {
+        Self {
+            backend: Box::new(Disconnected),
+            rx_fifo: VecDeque::with_capacity(FIFO_DEPTH),
+            tx_fifo: VecDeque::with_capacity(FIFO_DEPTH),
+            rx_overrun: false,
+            control: 0,
+            divisor: 0,
+            countdown: CYCLES_PER_DIVISOR_STEP,
+        }
+    }
+
+    pub fn connect(&mut self, backend: Box<dyn SerialBackend>) {
+        self.backend = backend;
+    }
+
+    /// Resets the registers and empties the FIFOs. The host stays connected.
+    pub fn reset(&mut self) {
+        self.rx_fifo.clear();
+        self.tx_fifo.clear();
+        self.rx_overrun = false;
+        self.control = 0;
+        self.divisor = 0;
+        self.countdown = self.character_period();
+    }
+
+    pub fn clock(&mut self) {
+        self.countdown -= 1;
+        if self.countdown > 0 {
+            return;
+        }
+        self.countdown = self.character_period();
+
+        if let Some(data) = self.tx_fifo.pop_front() {
+            self.backend.transmit(data);
+        }
+
+        if let Some(data) = self.backend.receive() {
+            if self.rx_fifo.len() < FIFO_DEPTH {
+                self.rx_fifo.push_back(data);
+            } else {
+                self.rx_overrun = true;
+            }
+        }
+    }
+
+    pub fn irq(&self) -> bool {
+        self.control & CONTROL_RX_IRQ_ENABLE != 0 && !self.rx_fifo.is_empty()
+    }
+
+    pub fn read(&mut self, address: Address) -> Byte {
+        match address {
+            UART_DATA => self.rx_fifo.pop_front().unwrap_or(0),
+            UART_STATUS => {
+                let status = self.status();
+                self.rx_overrun = false;
+                status
+            }
+            UART_CONTROL => self.control,
+            UART_DIVISOR => self.divisor,
+            _ => 0,
+        }
+    }
+
+    pub fn write(&mut self, address: Address, data: Byte) {
+        match address {
+            UART_DATA if self.tx_fifo.len() < FIFO_DEPTH => self.tx_fifo.push_back(data),
+            UART_CONTROL => self.control = data,
+            UART_DIVISOR => self.divisor = data,
+            _ => {}
+        }
+    }
+
+    fn status(&self) -> Byte {
+        let mut status = 0;
+
+        if !self.rx_fifo.is_empty() {
+            status |= STATUS_RX_READY;
+        }
+        if self.tx_fifo.len() < FIFO_DEPTH {
+            status |= STATUS_TX_READY;
+        }
+        if self.tx_fifo.is_empty() {
+            status |= STATUS_TX_EMPTY;
+        }
+        if self.rx_overrun {
+            status |= STATUS_RX_OVERRUN;
+        }
+
+        status
+    }
+
+    fn character_period(&self) -> usize {
+        (self.divisor as usize + 1) * CYCLES_PER_DIVISOR_STEP
+    }
+}
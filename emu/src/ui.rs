@@ -1,66 +1,36 @@
-mod bus;
-mod uart;
-mod vga;
-
-use arch::{self, Architectural16, Architectural8, ReachedBreakpoint};
+use arch::{Address, Architectural16, Architectural8, StopReason};
+use emu::{vga, Bw8};
 use spin_sleep_util::{Interval, RateReporter};
+use std::collections::BTreeMap;
+use std::io::Write;
 use std::time::Duration;
 
 const WIDTH: usize = 1280;
 const HEIGHT: usize = 960;
 
-pub struct Bw8 {
-    cpu: arch::CpuState,
-    bus: bus::Bw8Bus,
-    vga: vga::Vga,
-}
-
-impl Bw8 {
-    pub fn new(binary_path: String) -> Self {
-        Self {
-            cpu: arch::CpuState::new(),
-            bus: bus::Bw8Bus::new(binary_path),
-            vga: vga::Vga::new(),
-        }
-    }
-
-    pub fn run(&mut self, cycles: usize) -> (arch::trace::Trace, ReachedBreakpoint) {
-        let rv = self.cpu.run(&mut self.bus, cycles);
-        self.vga.clock(&self.bus);
-        rv
-    }
-
-    pub fn reset(&mut self) {
-        self.bus.set_reset(true);
-        self.cpu.run(&mut self.bus, 1);
-        self.bus.set_reset(false);
-        self.bus.reset();
-        self.vga.reset();
-    }
-
-    pub fn inject_irq(&mut self) {
-        self.bus.set_irq(true);
-    }
-
-    pub fn inject_nmi(&mut self) {
-        self.bus.set_nmi(true);
-    }
-
-    pub fn vga_frame(&self) -> &[u8] {
-        &self.vga.pixel_data()
-    }
-}
+/// How many instructions the disassembly shows before and after the one it
+/// is centred on.
+const DISASM_BEFORE: usize = 12;
+const DISASM_AFTER: usize = 24;
 
 pub struct EmulatorState {
     running: bool,
     fps: f64,
+    /// Cycles owed to the processor; the fraction of a cycle, or the overshoot
+    /// of the last instruction, carries over from frame to frame.
+    cycle_budget: f64,
     loop_interval: Interval,
     loop_reporter: RateReporter,
     vga_texture: egui::TextureHandle,
+    /// Names for addresses, from the assembler's symbol file.
+    symbols: BTreeMap<Address, String>,
+    /// The address the disassembly is centred on, after a jump target was
+    /// clicked; `None` follows the program counter.
+    disasm_anchor: Option<Address>,
 }
 
 impl EmulatorState {
-    pub fn new(ui_context: &egui::Context) -> Self {
+    pub fn new(ui_context: &egui::Context, symbols: BTreeMap<Address, String>) -> Self {
         let loop_interval =
             spin_sleep_util::interval(Duration::from_secs_f64(1.0 / vga::FRAMERATE));
         let loop_reporter = RateReporter::new(Duration::from_secs_f64(0.5));
@@ -72,25 +42,35 @@ impl EmulatorState {
         Self {
             running: false,
             fps: 0.0,
+            cycle_budget: 0.0,
             loop_interval,
             loop_reporter,
             vga_texture,
+            symbols,
+            disasm_anchor: None,
         }
     }
 
     pub fn update(&mut self, system: &mut Bw8) {
         self.loop_interval.tick();
 
-        // Need to write microcode so that either:
-        //  1. Know cycle counts and can add wait-cycles between frames
-        //  2. Implement cycle-accurate microcode emulation in `arch`.
-        // Assuming 4 MHz clock rate and 4 cycles per instruction, we can
-        // expect 16,667 instructions to be executed in 1/60th of a second.
         if self.running {
-            let (_trace, bp) = system.run(16_650);
-            self.running = bp == ReachedBreakpoint::DidNot;
+            self.cycle_budget += system.clock_rate() / vga::FRAMERATE;
+            let (_trace, bp, elapsed) = system.run(self.cycle_budget as usize);
+            self.cycle_budget -= elapsed as f64;
+            self.running = bp == StopReason::CycleLimit;
+            if bp == StopReason::Breakpoint {
+                eprintln!("Reached breakpoint!");
+            }
+        } else {
+            self.cycle_budget = 0.0;
         };
 
+        // Includes output from single steps taken while paused.
+        let mut stdout = std::io::stdout();
+        stdout.write_all(&system.take_output()).ok();
+        stdout.flush().ok();
+
         if let Some(fps) = self.loop_reporter.increment_and_report() {
             self.fps = fps;
         }
@@ -111,9 +91,7 @@ impl EmulatorState {
         SidePanel::new(Side::Right, "disasm")
             .show_separator_line(false)
             .resizable(false)
-            .show_inside(ui, |ui| {
-                
-            });
+            .show_inside(ui, |ui| self.draw_disassembly(system, ui));
 
         SidePanel::new(Side::Right, "ctrl")
             .show_separator_line(false)
@@ -127,6 +105,7 @@ impl EmulatorState {
                     } else {
                         ui.label("- fps");
                     }
+                    ui.label(format!("{:.3} MHz", system.clock_rate() / 1_000_000.0));
 
                     ui.with_layout(
                         Layout {
@@ -160,6 +139,10 @@ impl EmulatorState {
                             if ui.add_enabled(self.running, Button::new("NMI")).clicked() {
                                 system.inject_nmi();
                             }
+
+                            if ui.button("Load Font").clicked() {
+                                system.load_font();
+                            }
                         },
                     );
 
@@ -168,26 +151,26 @@ impl EmulatorState {
                             main_dir: Direction::LeftToRight,
                             ..*ui.layout()
                         },
-                        |_ui| {
-                            // if ui
-                            //     .add_enabled(
-                            //         self.running && (system.clock_rate() > 1_000.0),
-                            //         Button::new("-- Clock Speed"),
-                            //     )
-                            //     .clicked()
-                            // {
-                            //     system.set_clock_rate(system.clock_rate() * 0.5);
-                            // }
-
-                            // if ui
-                            //     .add_enabled(
-                            //         self.running && (system.clock_rate() < 16_000_000.0),
-                            //         Button::new("++ Clock Speed"),
-                            //     )
-                            //     .clicked()
-                            // {
-                            //     system.set_clock_rate(system.clock_rate() * 2.0);
-                            // }
+                        |ui| {
+                            if ui
+                                .add_enabled(
+                                    self.running && (system.clock_rate() > 1_000.0),
+                                    Button::new("-- Clock Speed"),
+                                )
+                                .clicked()
+                            {
+                                system.set_clock_rate(system.clock_rate() * 0.5);
+                            }
+
+                            if ui
+                                .add_enabled(
+                                    self.running && (system.clock_rate() < 16_000_000.0),
+                                    Button::new("++ Clock Speed"),
+                                )
+                                .clicked()
+                            {
+                                system.set_clock_rate(system.clock_rate() * 2.0);
+                            }
                         },
                     );
 
@@ -206,10 +189,10 @@ impl EmulatorState {
                             ui.with_layout(ui.layout().with_cross_align(Align::LEFT), |ui| {
                                 ui.label(RichText::new("16 Bit Regs").color(Color32::WHITE));
 
-                                ui.label(format!("PC: {:0>4X}", system.cpu[Architectural16::PC]));
-                                ui.label(format!("SP: {:0>4X}", system.cpu[Architectural16::SP]));
-                                ui.label(format!("X:  {:0>4X}", system.cpu[Architectural16::X]));
-                                ui.label(format!("Y:  {:0>4X}", system.cpu[Architectural16::Y]));
+                                ui.label(format!("PC: {:0>4X}", system.cpu()[Architectural16::PC]));
+                                ui.label(format!("SP: {:0>4X}", system.cpu()[Architectural16::SP]));
+                                ui.label(format!("X:  {:0>4X}", system.cpu()[Architectural16::X]));
+                                ui.label(format!("Y:  {:0>4X}", system.cpu()[Architectural16::Y]));
                             });
                         });
 
@@ -224,10 +207,10 @@ impl EmulatorState {
                             ui.with_layout(ui.layout().with_cross_align(Align::LEFT), |ui| {
                                 ui.label(RichText::new("8 Bit Regs").color(Color32::WHITE));
 
-                                ui.label(format!("A: {:0>2X}", system.cpu[Architectural8::A]));
-                                ui.label(format!("B: {:0>2X}", system.cpu[Architectural8::B]));
-                                ui.label(format!("C: {:0>2X}", system.cpu[Architectural8::C]));
-                                ui.label(format!("D: {:0>2X}", system.cpu[Architectural8::D]));
+                                ui.label(format!("A: {:0>2X}", system.cpu()[Architectural8::A]));
+                                ui.label(format!("B: {:0>2X}", system.cpu()[Architectural8::B]));
+                                ui.label(format!("C: {:0>2X}", system.cpu()[Architectural8::C]));
+                                ui.label(format!("D: {:0>2X}", system.cpu()[Architectural8::D]));
                             });
                         });
 
@@ -242,7 +225,7 @@ impl EmulatorState {
                             ui.with_layout(ui.layout().with_cross_align(Align::LEFT), |ui| {
                                 ui.label(RichText::new("Flags").color(Color32::WHITE));
 
-                                let status = system.cpu.status();
+                                let status = system.cpu().status();
 
                                 let carry: u8 = status.carry.into();
                                 let zero: u8 = status.zero.into();
@@ -267,7 +250,7 @@ impl EmulatorState {
                                 ui.label(format!("Privilege: {:?}", status.privilege_level,));
                                 ui.label(format!(
                                     "Bank Register: {:0>1X}",
-                                    system.cpu.br().as_inner()
+                                    system.cpu().br().as_inner()
                                 ));
                             });
                         });
@@ -289,7 +272,7 @@ impl EmulatorState {
                             let mut line = String::with_capacity(6 + 16 * 3);
                             write!(line, "{:0>4X} |", addr).unwrap();
                             for i in 0..16 {
-                                write!(line, " {:0>2X}", system.bus.inspect_memory(addr + i),)
+                                write!(line, " {:0>2X}", system.read_code_memory(addr + i),)
                                     .unwrap();
                             }
 
@@ -321,6 +304,59 @@ impl EmulatorState {
             });
     }
 
+    /// Lists the instructions around the program counter, or around the jump
+    /// target last clicked, with the current instruction highlighted.
+    fn draw_disassembly(&mut self, system: &Bw8, ui: &mut egui::Ui) {
+        use egui::*;
+
+        ui.style_mut().wrap_mode = Some(TextWrapMode::Extend);
+
+        let pc = system.cpu()[Architectural16::PC];
+        let anchor = self.disasm_anchor.unwrap_or(pc);
+
+        ui.with_layout(ui.layout().with_cross_align(Align::Center), |ui| {
+            ui.label(RichText::new("Disassembly").color(Color32::WHITE))
+        });
+        if ui
+            .add_enabled(self.disasm_anchor.is_some(), Button::new("Follow PC"))
+            .clicked()
+        {
+            self.disasm_anchor = None;
+        }
+        ui.separator();
+
+        let name = |address: Address| match self.symbols.get(&address) {
+            Some(name) => name.clone(),
+            None => format!("{:0>4X}", address),
+        };
+
+        let mut clicked = None;
+        let read = |address| system.read_code_memory(address);
+        for (address, instruction) in disasm::around(read, anchor, DISASM_BEFORE, DISASM_AFTER) {
+            if let Some(label) = self.symbols.get(&address) {
+                ui.label(RichText::new(format!("{}:", label)).color(Color32::LIGHT_BLUE));
+            }
+
+            ui.horizontal(|ui| {
+                let mut text = RichText::new(format!("{:0>4X}  {}", address, instruction));
+                if address == pc {
+                    text = text.color(Color32::BLACK).background_color(Color32::YELLOW);
+                }
+                ui.label(text);
+
+                if let Some(target) = disasm::target(address, &instruction) {
+                    if ui.link(format!("<{}>", name(target))).clicked() {
+                        clicked = Some(target);
+                    }
+                }
+            });
+        }
+
+        if clicked.is_some() {
+            self.disasm_anchor = clicked;
+        }
+    }
+
     #[inline]
     pub fn quit(&mut self, _system: &mut Bw8) {
         // system.terminal().quit().unwrap();
@@ -0,0 +1,589 @@
+use arch::{Address, Byte};
+
+use crate::font;
+
+pub const FRAMERATE: f64 = 60.0;
+
+pub const COLUMN_COUNT: usize = 640;
+pub const ROW_COUNT: usize = 480;
+
+#[repr(C)]
+#[repr(align(4))]
+#[derive(Clone, Copy, Debug)]
+pub struct Color {
+    channels: [arch::Byte; 4],
+}
+
+impl Color {
+    pub const BLACK: Color = Color::new(0, 0, 0);
+
+    pub const fn new(red: arch::Byte, green: arch::Byte, blue: arch::Byte) -> Self {
+        Self {
+            channels: [red, green, blue, u8::MAX],
+        }
+    }
+
+    /// Decodes an `RRRGGGBB` palette entry.
+    const fn from_byte(value: u8) -> Self {
+        Self::new(
+            ((value >> 5) & 0b111) * 32,
+            ((value >> 2) & 0b111) * 32,
+            (value & 0b11) * 64,
+        )
+    }
+}
+
+impl From<u8> for Color {
+    fn from(value: u8) -> Self {
+        Self::from_byte(value)
+    }
+}
+
+/// Every color a palette entry can select, indexed by the entry.
+static COLORS: [Color; 256] = {
+    let mut colors = [Color::BLACK; 256];
+    let mut value = 0;
+    while value < 256 {
+        colors[value] = Color::from_byte(value as u8);
+        value += 1;
+    }
+    colors
+};
+
+/// Spreads the bits of a byte into the low bits of the nibbles of a word, so
+/// that four color planes can be combined into eight palette indices at once.
+static SPREAD_BITS: [u32; 256] = {
+    let mut spread = [0; 256];
+    let mut value = 0;
+    while value < 256 {
+        let mut bit = 0;
+        while bit < 8 {
+            spread[value] |= ((value as u32 >> bit) & 1) << (4 * bit);
+            bit += 1;
+        }
+        value += 1;
+    }
+    spread
+};
+
+/// The VGA's memory is mapped into I/O space from here to `MEMORY_END`.
+pub const MEMORY_BASE: Address = 0x8000;
+pub const MEMORY_END: Address = 0xF1FF;
+const MEMORY_SIZE: usize = 0x7200;
+
+// Tiles are 8x8 pixels. The tilemap holds a (bitmap, palette) pair of bytes
+// per tile, in 64 rows of 128 tiles; the screen shows an 80x60 tile window
+// onto it, positioned by the scroll registers, which wraps around at the
+// tilemap's edges. Each bitmap is 32 bytes: the tile's eight rows for color
+// plane 0, then plane 1, and so on, with the leftmost pixel in the most
+// significant bit. A pixel's four plane bits select one of the 16 colors in
+// its palette.
+//
+// Sprites are drawn over the tiles from the bitmaps and palettes. Each has
+// `SPRITE_SIZE` bytes in the sprite table: X and Y as little-endian words,
+// in screen pixels, then the bitmap, the palette and the `SPRITE_` flags.
+// Color 0 of a sprite's palette is transparent. X wraps at `SPRITE_X_WRAP`
+// and Y at `SPRITE_Y_WRAP`, so a position just below either is a small
+// negative one, leaving the sprite partly off the left or top edge.
+const TILE_SIZE: usize = 8;
+const MAP_COLUMN_COUNT: usize = 128;
+const MAP_ROW_COUNT: usize = 64;
+const MAP_WIDTH: usize = MAP_COLUMN_COUNT * TILE_SIZE;
+const MAP_HEIGHT: usize = MAP_ROW_COUNT * TILE_SIZE;
+const TILEMAP_OFFSET: usize = 0x0000;
+const TILEMAP_ROW_SIZE: usize = 2 * MAP_COLUMN_COUNT;
+const BITMAP_OFFSET: usize = 0x4000;
+const BITMAP_SIZE: usize = 4 * TILE_SIZE;
+const PALETTE_OFFSET: usize = 0x6000;
+const PALETTE_SIZE: usize = 16;
+const PALETTE_END: usize = PALETTE_OFFSET + 256 * PALETTE_SIZE;
+const SPRITE_TABLE_OFFSET: usize = 0x7000;
+const SPRITE_SIZE: usize = 8;
+const SPRITE_X_WRAP: usize = 1024;
+const SPRITE_Y_WRAP: usize = 512;
+
+pub const SPRITE_COUNT: usize = 64;
+/// Sprites beyond this many on one line are not drawn; the lowest-numbered
+/// sprites are kept.
+pub const SPRITES_PER_LINE: usize = 16;
+
+/// The sprite is drawn.
+pub const SPRITE_ENABLE: Byte = 0b0000_0001;
+/// The sprite's bitmap is mirrored left to right.
+pub const SPRITE_FLIP_X: Byte = 0b0000_0010;
+/// The sprite's bitmap is mirrored top to bottom.
+pub const SPRITE_FLIP_Y: Byte = 0b0000_0100;
+
+/// The VGA's registers occupy eight I/O addresses from here.
+pub const VGA_BASE: Address = 0x0040;
+
+/// Read-only; see the `STATUS_` bits.
+pub const VGA_STATUS: Address = VGA_BASE;
+/// See the `CONTROL_` bits.
+pub const VGA_CONTROL: Address = VGA_BASE + 1;
+/// Read-only. The line the beam is on, counting from the first visible line
+/// and continuing through the vertical blanking interval.
+pub const VGA_LINE_LOW: Address = VGA_BASE + 2;
+pub const VGA_LINE_HIGH: Address = VGA_BASE + 3;
+/// The tilemap pixel shown at the screen's left edge, from 0 to 1023.
+pub const VGA_SCROLL_X_LOW: Address = VGA_BASE + 4;
+pub const VGA_SCROLL_X_HIGH: Address = VGA_BASE + 5;
+/// The tilemap pixel shown at the screen's top edge, from 0 to 511.
+pub const VGA_SCROLL_Y_LOW: Address = VGA_BASE + 6;
+pub const VGA_SCROLL_Y_HIGH: Address = VGA_BASE + 7;
+
+/// The beam is between the last visible line and the first.
+pub const STATUS_VBLANK: Byte = 0b0000_0001;
+/// The beam is between the end of a line and the start of the next.
+pub const STATUS_HBLANK: Byte = 0b0000_0010;
+/// A line drawn in this frame had more than `SPRITES_PER_LINE` sprites.
+pub const STATUS_SPRITE_OVERFLOW: Byte = 0b0000_0100;
+
+/// Raise an IRQ each time the beam enters the vertical blanking interval.
+pub const CONTROL_VBLANK_IRQ_ENABLE: Byte = 0b0000_0001;
+
+/// VGA cycles taken to scan one line, including horizontal blanking.
+pub const CYCLES_PER_LINE: usize = 32;
+/// VGA cycles in each line during which pixels are drawn; the rest are
+/// horizontal blanking.
+pub const VISIBLE_CYCLES_PER_LINE: usize = 26;
+/// Lines per frame, including the vertical blanking interval.
+pub const LINES_PER_FRAME: usize = 525;
+pub const CYCLES_PER_FRAME: usize = CYCLES_PER_LINE * LINES_PER_FRAME;
+/// The VGA's clock, in cycles per second, which scans `FRAMERATE` frames a
+/// second whatever the processor's clock rate. It is also the processor's
+/// default clock rate, at which the two run in step.
+pub const CLOCK_RATE: u64 = CYCLES_PER_FRAME as u64 * FRAMERATE as u64;
+
+pub(crate) struct Vga {
+    memory: Box<[Byte; MEMORY_SIZE]>,
+    pixels: Box<[Color; COLUMN_COUNT * ROW_COUNT]>,
+    /// Lines whose memory has changed since they were last drawn.
+    dirty_lines: [bool; ROW_COUNT],
+    tile_rows: [TileRow; MAP_ROW_COUNT],
+    control: Byte,
+    scroll_x: usize,
+    scroll_y: usize,
+    /// The lines that had too many sprites when last drawn.
+    overflowing_lines: [bool; ROW_COUNT],
+    sprite_overflow: bool,
+    /// Cycles since the beam started the first visible line.
+    beam: usize,
+    vblank_irq: bool,
+}
+
+impl Vga {
+    pub fn new() -> Self {
+        let mut vga = Self {
+            memory: Box::new([0; MEMORY_SIZE]),
+            pixels: Box::new([Color::BLACK; COLUMN_COUNT * ROW_COUNT]),
+            dirty_lines: [true; ROW_COUNT],
+            tile_rows: [TileRow::default(); MAP_ROW_COUNT],
+            control: 0,
+            scroll_x: 0,
+            scroll_y: 0,
+            overflowing_lines: [false; ROW_COUNT],
+            sprite_overflow: false,
+            beam: 0,
+            vblank_irq: false,
+        };
+
+        for tile_y in 0..MAP_ROW_COUNT {
+            vga.index_tile_row(tile_y);
+        }
+
+        vga
+    }
+
+    pub fn pixel_data(&self) -> &[u8] {
+        const COLOR_SIZE: usize = std::mem::size_of::<Color>();
+
+        // TODO: Don't use unsafe block, lol
+        unsafe {
+            let pixel_ptr = self.pixels.as_ptr();
+            let data_ptr = pixel_ptr as *const u8;
+            let len = self.pixels.len() * COLOR_SIZE;
+            std::slice::from_raw_parts(data_ptr, len)
+        }
+    }
+
+    /// Blanks the screen and restarts the beam at the first line. The VGA's
+    /// memory is left as it was.
+    pub fn reset(&mut self) {
+        *self.pixels = [Color::BLACK; COLUMN_COUNT * ROW_COUNT];
+        self.dirty_lines = [true; ROW_COUNT];
+        self.control = 0;
+        self.scroll_x = 0;
+        self.scroll_y = 0;
+        self.overflowing_lines = [false; ROW_COUNT];
+        self.sprite_overflow = false;
+        self.beam = 0;
+        self.vblank_irq = false;
+    }
+
+    /// Reads the VGA's memory, relative to `MEMORY_BASE`.
+    pub fn inspect_memory(&self, offset: Address) -> Byte {
+        self.memory[offset as usize]
+    }
+
+    pub fn write_memory(&mut self, offset: Address, data: Byte) {
+        let offset = offset as usize;
+        if self.memory[offset] == data {
+            return;
+        }
+
+        if offset >= SPRITE_TABLE_OFFSET {
+            let index = (offset - SPRITE_TABLE_OFFSET) / SPRITE_SIZE;
+            self.invalidate_sprite(index);
+            self.memory[offset] = data;
+            self.invalidate_sprite(index);
+        } else {
+            self.memory[offset] = data;
+            self.invalidate(offset);
+        }
+    }
+
+    /// Replaces bitmap `code` with the font's glyph for CP437 character
+    /// `code`, for every code. Glyphs are drawn in color 1 over color 0.
+    pub fn load_font(&mut self) {
+        for (code, glyph) in font::glyphs().iter().enumerate() {
+            // The glyph is plane 0, and the other planes are clear.
+            let mut bitmap = [0; BITMAP_SIZE];
+            bitmap[..TILE_SIZE].copy_from_slice(glyph);
+
+            let address = BITMAP_OFFSET + BITMAP_SIZE * code;
+            for (offset, data) in bitmap.into_iter().enumerate() {
+                self.write_memory((address + offset) as Address, data);
+            }
+        }
+    }
+
+    pub fn read(&self, address: Address) -> Byte {
+        let [line_low, line_high] = (self.line() as u16).to_le_bytes();
+        let [scroll_x_low, scroll_x_high] = (self.scroll_x as u16).to_le_bytes();
+        let [scroll_y_low, scroll_y_high] = (self.scroll_y as u16).to_le_bytes();
+
+        match address {
+            VGA_STATUS => self.status(),
+            VGA_CONTROL => self.control,
+            VGA_LINE_LOW => line_low,
+            VGA_LINE_HIGH => line_high,
+            VGA_SCROLL_X_LOW => scroll_x_low,
+            VGA_SCROLL_X_HIGH => scroll_x_high,
+            VGA_SCROLL_Y_LOW => scroll_y_low,
+            VGA_SCROLL_Y_HIGH => scroll_y_high,
+            _ => 0,
+        }
+    }
+
+    pub fn write(&mut self, address: Address, data: Byte) {
+        let (scroll_x, scroll_y) = (self.scroll_x, self.scroll_y);
+        let data = data as usize;
+
+        match address {
+            VGA_CONTROL => self.control = data as Byte,
+            VGA_SCROLL_X_LOW => self.scroll_x = (self.scroll_x & !0xFF) | data,
+            VGA_SCROLL_X_HIGH => self.scroll_x = ((data << 8) | (self.scroll_x & 0xFF)) % MAP_WIDTH,
+            VGA_SCROLL_Y_LOW => self.scroll_y = ((self.scroll_y & !0xFF) | data) % MAP_HEIGHT,
+            VGA_SCROLL_Y_HIGH => {
+                self.scroll_y = ((data << 8) | (self.scroll_y & 0xFF)) % MAP_HEIGHT
+            }
+            _ => {}
+        }
+
+        if (scroll_x, scroll_y) != (self.scroll_x, self.scroll_y) {
+            self.dirty_lines = [true; ROW_COUNT];
+        }
+    }
+
+    /// Whether the beam entered the vertical blanking interval on the last
+    /// cycle with the vblank IRQ enabled.
+    pub fn irq(&self) -> bool {
+        self.vblank_irq
+    }
+
+    /// Advances the beam by one processor cycle, drawing each line as the beam
+    /// reaches its end, so that changes made to the VGA's memory mid-frame
+    /// take effect from the line being scanned.
+    pub fn clock(&mut self) {
+        let line = self.line();
+        if line < ROW_COUNT && self.beam % CYCLES_PER_LINE == VISIBLE_CYCLES_PER_LINE - 1 {
+            self.render_line(line);
+            self.sprite_overflow |= self.overflowing_lines[line];
+        }
+
+        self.beam = (self.beam + 1) % CYCLES_PER_FRAME;
+        if self.beam == 0 {
+            self.sprite_overflow = false;
+        }
+
+        self.vblank_irq = self.beam == ROW_COUNT * CYCLES_PER_LINE
+            && self.control & CONTROL_VBLANK_IRQ_ENABLE != 0;
+    }
+
+    fn line(&self) -> usize {
+        self.beam / CYCLES_PER_LINE
+    }
+
+    fn status(&self) -> Byte {
+        let mut status = 0;
+
+        if self.line() >= ROW_COUNT {
+            status |= STATUS_VBLANK;
+        }
+        if self.beam % CYCLES_PER_LINE >= VISIBLE_CYCLES_PER_LINE {
+            status |= STATUS_HBLANK;
+        }
+        if self.sprite_overflow {
+            status |= STATUS_SPRITE_OVERFLOW;
+        }
+
+        status
+    }
+
+    fn render_line(&mut self, y: usize) {
+        if !self.dirty_lines[y] {
+            return;
+        }
+        self.dirty_lines[y] = false;
+
+        // The background is drawn a tile wider than the screen, so that it
+        // can be shifted left by the fine horizontal scroll.
+        let mut background = [Color::BLACK; COLUMN_COUNT + TILE_SIZE];
+
+        let map_y = (y + self.scroll_y) % MAP_HEIGHT;
+        let tile_y = map_y / TILE_SIZE;
+        let first_tile_x = self.scroll_x / TILE_SIZE;
+
+        for (index, pixels) in background.chunks_exact_mut(TILE_SIZE).enumerate() {
+            let tile_x = (first_tile_x + index) % MAP_COLUMN_COUNT;
+            let map_address = TILEMAP_OFFSET + TILEMAP_ROW_SIZE * tile_y + 2 * tile_x;
+            let bitmap_id = self.memory[map_address];
+            let palette = self.palette(self.memory[map_address + 1]);
+
+            let mut indices = self.bitmap_row(bitmap_id, map_y % TILE_SIZE);
+            for pixel in pixels {
+                *pixel = COLORS[palette[(indices >> 28) as usize] as usize];
+                indices <<= 4;
+            }
+        }
+
+        let line = &mut self.pixels[y * COLUMN_COUNT..][..COLUMN_COUNT];
+        line.copy_from_slice(&background[self.scroll_x % TILE_SIZE..][..COLUMN_COUNT]);
+
+        // Lower-numbered sprites are drawn last, on top of the others.
+        let sprites = self.sprites_on_line(y);
+        for (sprite, row) in sprites.iter().rev().flatten() {
+            let row = match sprite.flip_y {
+                true => TILE_SIZE - 1 - row,
+                false => *row,
+            };
+
+            let mut indices = self.bitmap_row(sprite.bitmap_id, row);
+            if sprite.flip_x {
+                indices = reverse_nibbles(indices);
+            }
+
+            let mut palette = [0; PALETTE_SIZE];
+            palette.copy_from_slice(self.palette(sprite.palette_id));
+            for column in 0..TILE_SIZE {
+                let x = (sprite.x + column) % SPRITE_X_WRAP;
+                let index = (indices >> 28) as usize;
+                indices <<= 4;
+
+                if index != 0 && x < COLUMN_COUNT {
+                    self.pixels[y * COLUMN_COUNT + x] = COLORS[palette[index] as usize];
+                }
+            }
+        }
+    }
+
+    /// The palette indices of the eight pixels in one row of a bitmap, a
+    /// nibble each, with the leftmost pixel in the most significant nibble.
+    fn bitmap_row(&self, bitmap_id: Byte, row: usize) -> u32 {
+        // Each of the bitmap's rows is stored once per color plane, a whole
+        // plane apart; see `BITMAP_SIZE`.
+        let address = BITMAP_OFFSET + BITMAP_SIZE * bitmap_id as usize + row;
+        let plane =
+            |plane: usize| SPREAD_BITS[self.memory[address + TILE_SIZE * plane] as usize] << plane;
+
+        plane(0) | plane(1) | plane(2) | plane(3)
+    }
+
+    fn palette(&self, palette_id: Byte) -> &[Byte] {
+        &self.memory[PALETTE_OFFSET + PALETTE_SIZE * palette_id as usize..][..PALETTE_SIZE]
+    }
+
+    fn sprite(&self, index: usize) -> Sprite {
+        let attributes = &self.memory[SPRITE_TABLE_OFFSET + SPRITE_SIZE * index..][..SPRITE_SIZE];
+        let flags = attributes[6];
+
+        Sprite {
+            x: u16::from_le_bytes([attributes[0], attributes[1]]) as usize % SPRITE_X_WRAP,
+            y: u16::from_le_bytes([attributes[2], attributes[3]]) as usize % SPRITE_Y_WRAP,
+            bitmap_id: attributes[4],
+            palette_id: attributes[5],
+            enabled: flags & SPRITE_ENABLE != 0,
+            flip_x: flags & SPRITE_FLIP_X != 0,
+            flip_y: flags & SPRITE_FLIP_Y != 0,
+        }
+    }
+
+    /// The enabled sprites covering line `y`, in priority order, each with
+    /// the row of it on the line. Sprites past the per-line limit are
+    /// dropped, and flagged in the status register.
+    fn sprites_on_line(&mut self, y: usize) -> [Option<(Sprite, usize)>; SPRITES_PER_LINE] {
+        let mut sprites = [None; SPRITES_PER_LINE];
+        let mut count = 0;
+
+        for index in 0..SPRITE_COUNT {
+            let sprite = self.sprite(index);
+            let row = (y + SPRITE_Y_WRAP - sprite.y) % SPRITE_Y_WRAP;
+            if !sprite.enabled || row >= TILE_SIZE {
+                continue;
+            }
+
+            if count == SPRITES_PER_LINE {
+                self.overflowing_lines[y] = true;
+                return sprites;
+            }
+
+            sprites[count] = Some((sprite, row));
+            count += 1;
+        }
+
+        self.overflowing_lines[y] = false;
+        sprites
+    }
+
+    /// Marks the lines that display the VGA memory at `offset` for redrawing.
+    /// Writes to the sprite table are handled by `write_memory`, which needs
+    /// to see the sprite before and after the write.
+    fn invalidate(&mut self, offset: usize) {
+        match offset {
+            TILEMAP_OFFSET..BITMAP_OFFSET => {
+                let tile_y = (offset - TILEMAP_OFFSET) / TILEMAP_ROW_SIZE;
+                self.index_tile_row(tile_y);
+                for row in 0..TILE_SIZE {
+                    self.invalidate_map_line(tile_y * TILE_SIZE + row);
+                }
+            }
+            BITMAP_OFFSET..PALETTE_OFFSET => {
+                let bitmap_id = ((offset - BITMAP_OFFSET) / BITMAP_SIZE) as Byte;
+                let row = offset % TILE_SIZE;
+                for tile_y in 0..MAP_ROW_COUNT {
+                    if self.tile_rows[tile_y].bitmaps.contains(bitmap_id) {
+                        self.invalidate_map_line(tile_y * TILE_SIZE + row);
+                    }
+                }
+                for index in 0..SPRITE_COUNT {
+                    let sprite = self.sprite(index);
+                    if sprite.bitmap_id == bitmap_id {
+                        let row = match sprite.flip_y {
+                            true => TILE_SIZE - 1 - row,
+                            false => row,
+                        };
+                        self.invalidate_sprite_line(&sprite, row);
+                    }
+                }
+            }
+            PALETTE_OFFSET..PALETTE_END => {
+                let palette_id = ((offset - PALETTE_OFFSET) / PALETTE_SIZE) as Byte;
+                for tile_y in 0..MAP_ROW_COUNT {
+                    if self.tile_rows[tile_y].palettes.contains(palette_id) {
+                        for row in 0..TILE_SIZE {
+                            self.invalidate_map_line(tile_y * TILE_SIZE + row);
+                        }
+                    }
+                }
+                for index in 0..SPRITE_COUNT {
+                    let sprite = self.sprite(index);
+                    if sprite.palette_id == palette_id {
+                        for row in 0..TILE_SIZE {
+                            self.invalidate_sprite_line(&sprite, row);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Marks the line showing row `map_y` of the scrolled tilemap, if any.
+    fn invalidate_map_line(&mut self, map_y: usize) {
+        let y = (map_y + MAP_HEIGHT - self.scroll_y) % MAP_HEIGHT;
+        self.invalidate_lines(y..y + 1);
+    }
+
+    fn invalidate_sprite(&mut self, index: usize) {
+        let sprite = self.sprite(index);
+        if sprite.enabled {
+            for row in 0..TILE_SIZE {
+                self.invalidate_sprite_line(&sprite, row);
+            }
+        }
+    }
+
+    /// Marks the line showing row `row` of `sprite`, if any.
+    fn invalidate_sprite_line(&mut self, sprite: &Sprite, row: usize) {
+        let y = (sprite.y + row) % SPRITE_Y_WRAP;
+        self.invalidate_lines(y..y + 1);
+    }
+
+    fn invalidate_lines(&mut self, lines: std::ops::Range<usize>) {
+        let end = lines.end.min(ROW_COUNT);
+        if lines.start < end {
+            self.dirty_lines[lines.start..end].fill(true);
+        }
+    }
+
+    /// Records which bitmaps and palettes the tiles in row `tile_y` use.
+    fn index_tile_row(&mut self, tile_y: usize) {
+        let row = &self.memory[TILEMAP_OFFSET + TILEMAP_ROW_SIZE * tile_y..][..TILEMAP_ROW_SIZE];
+        let tile_row = &mut self.tile_rows[tile_y];
+
+        *tile_row = TileRow::default();
+        for entry in row.chunks_exact(2) {
+            tile_row.bitmaps.insert(entry[0]);
+            tile_row.palettes.insert(entry[1]);
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Sprite {
+    x: usize,
+    y: usize,
+    bitmap_id: Byte,
+    palette_id: Byte,
+    enabled: bool,
+    flip_x: bool,
+    flip_y: bool,
+}
+
+/// Mirrors eight packed palette indices, as for a horizontally flipped sprite.
+fn reverse_nibbles(indices: u32) -> u32 {
+    let indices = indices.swap_bytes();
+    ((indices & 0x0F0F_0F0F) << 4) | ((indices & 0xF0F0_F0F0) >> 4)
+}
+
+/// The bitmaps and palettes used by one row of tiles, so that a write to
+/// either can be traced back to the lines it affects.
+#[derive(Clone, Copy, Default)]
+struct TileRow {
+    bitmaps: IdSet,
+    palettes: IdSet,
+}
+
+#[derive(Clone, Copy, Default)]
+struct IdSet([u64; 4]);
+
+impl IdSet {
+    fn insert(&mut self, id: Byte) {
+        self.0[id as usize / 64] |= 1 << (id % 64);
+    }
+
+    fn contains(&self, id: Byte) -> bool {
+        self.0[id as usize / 64] & (1 << (id % 64)) != 0
+    }
+}
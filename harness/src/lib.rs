@@ -0,0 +1,195 @@
+//! Helpers for writing bw8 firmware tests as ordinary `#[test]` functions.
+//!
+//! A test assembles or loads a program, sets up registers and memory, runs
+//! until the program writes to the breakpoint port (`out [0x03], a`), and then
+//! asserts on the machine's state:
+//!
+//! ```no_run
+//! use arch::Architectural8::{A, B};
+//! use harness::Harness;
+//!
+//! let mut bw8 = Harness::assemble(
+//!     "
+//!     clr.c
+//!     addc a, b
+//!     out [0x03], a
+//!     ",
+//! );
+//! bw8[A] = 2;
+//! bw8[B] = 3;
+//! bw8.run();
+//! assert_eq!(bw8[A], 5);
+//! ```
+
+use std::ops::{Index, IndexMut};
+use std::path::Path;
+
+use arch::{Address, Architectural16, Architectural8, Byte, Status, StopReason};
+use emu::Bw8;
+
+/// The number of cycles `Harness::run` executes before deciding the program
+/// will never reach its breakpoint.
+pub const DEFAULT_CYCLE_LIMIT: usize = 1_000_000;
+
+pub struct Harness {
+    system: Bw8,
+    assembly: Option<asm::Assembly>,
+    cycle_limit: usize,
+    cycles: usize,
+}
+
+impl Harness {
+    /// Assembles a snippet and loads it at address 0, where execution starts
+    /// after reset. Relative `#include`s are resolved from the current
+    /// directory, which is the package root under `cargo test`.
+    #[track_caller]
+    pub fn assemble(source: &str) -> Self {
+        match asm::assemble_source(Path::new("<snippet>"), source) {
+            Ok(assembly) => Self::from_assembly(assembly),
+            Err(error) => panic!("failed to assemble snippet: {}", error),
+        }
+    }
+
+    #[track_caller]
+    pub fn assemble_file(path: impl AsRef<Path>) -> Self {
+        match asm::assemble_file(path.as_ref()) {
+            Ok(assembly) => Self::from_assembly(assembly),
+            Err(error) => panic!("failed to assemble {}: {}", path.as_ref().display(), error),
+        }
+    }
+
+    /// Loads an already assembled binary at address 0.
+    #[track_caller]
+    pub fn load(image: &[Byte]) -> Self {
+        match Bw8::from_image(image) {
+            Ok(system) => Self::new(system, None),
+            Err(error) => panic!("failed to load image: {}", error),
+        }
+    }
+
+    #[track_caller]
+    fn from_assembly(assembly: asm::Assembly) -> Self {
+        match Bw8::from_image(&assembly.bytes) {
+            Ok(system) => Self::new(system, Some(assembly)),
+            Err(error) => panic!("failed to load assembled program: {}", error),
+        }
+    }
+
+    fn new(mut system: Bw8, assembly: Option<asm::Assembly>) -> Self {
+        system.reset();
+
+        Self {
+            system,
+            assembly,
+            cycle_limit: DEFAULT_CYCLE_LIMIT,
+            cycles: 0,
+        }
+    }
+
+    pub fn with_cycle_limit(mut self, cycles: usize) -> Self {
+        self.cycle_limit = cycles;
+        self
+    }
+
+    /// Runs until the program reaches a breakpoint, panicking if it halts or
+    /// runs out of cycles first.
+    #[track_caller]
+    pub fn run(&mut self) -> &mut Self {
+        match self.run_until_stopped() {
+            StopReason::Breakpoint => self,
+            StopReason::Halt(code) => panic!(
+                "program halted with 0x{:02x} at PC {:04x} before reaching a breakpoint",
+                code,
+                self[Architectural16::PC]
+            ),
+            StopReason::CycleLimit => panic!(
+                "program did not reach a breakpoint within {} cycles; PC is {:04x}",
+                self.cycle_limit,
+                self[Architectural16::PC]
+            ),
+        }
+    }
+
+    /// Runs until the program reaches a breakpoint, halts, or runs out of
+    /// cycles, and reports which happened.
+    pub fn run_until_stopped(&mut self) -> StopReason {
+        let (_trace, stop, elapsed) = self.system.run(self.cycle_limit);
+        self.cycles += elapsed;
+        stop
+    }
+
+    /// The clock cycles the program has run for, over every call to `run`.
+    pub fn cycles(&self) -> usize {
+        self.cycles
+    }
+
+    pub fn status(&self) -> &Status {
+        self.system.cpu().status()
+    }
+
+    pub fn memory(&self, address: Address) -> Byte {
+        self.system.read_memory(address)
+    }
+
+    /// Reads a little-endian word, as `ld x, [address]` would.
+    pub fn memory_word(&self, address: Address) -> Address {
+        let low = self.memory(address);
+        let high = self.memory(address.wrapping_add(1));
+        Address::from_le_bytes([low, high])
+    }
+
+    pub fn set_memory(&mut self, address: Address, data: &[Byte]) {
+        for (offset, &byte) in data.iter().enumerate() {
+            self.system
+                .write_memory(address.wrapping_add(offset as Address), byte);
+        }
+    }
+
+    /// Looks up the address of a label or the value of a constant in the
+    /// assembled program.
+    #[track_caller]
+    pub fn symbol(&self, name: &str) -> Address {
+        let Some(assembly) = &self.assembly else {
+            panic!("no symbols are available for a loaded binary");
+        };
+
+        match assembly.symbol(name) {
+            Some(value) => value as Address,
+            None => panic!("no symbol named `{}`", name),
+        }
+    }
+
+    /// Gives access to the whole system, for anything the harness doesn't
+    /// wrap, such as injecting interrupts.
+    pub fn system(&mut self) -> &mut Bw8 {
+        &mut self.system
+    }
+}
+
+impl Index<Architectural8> for Harness {
+    type Output = Byte;
+
+    fn index(&self, index: Architectural8) -> &Self::Output {
+        &self.system.cpu()[index]
+    }
+}
+
+impl IndexMut<Architectural8> for Harness {
+    fn index_mut(&mut self, index: Architectural8) -> &mut Self::Output {
+        &mut self.system.cpu_mut()[index]
+    }
+}
+
+impl Index<Architectural16> for Harness {
+    type Output = Address;
+
+    fn index(&self, index: Architectural16) -> &Self::Output {
+        &self.system.cpu()[index]
+    }
+}
+
+impl IndexMut<Architectural16> for Harness {
+    fn index_mut(&mut self, index: Architectural16) -> &mut Self::Output {
+        &mut self.system.cpu_mut()[index]
+    }
+}
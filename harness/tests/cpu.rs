@@ -0,0 +1,168 @@
+use arch::Architectural16::{SP, X, Y};
+use arch::Architectural8::{A, B, C, D};
+use harness::Harness;
+
+#[test]
+fn sixteen_bit_addition_carries_between_bytes() {
+    let mut bw8 = Harness::assemble(
+        "
+        clr.c
+        addc b, d
+        addc a, c
+        out [0x03], a
+        ",
+    );
+    bw8[A] = 0x12;
+    bw8[B] = 0xFF;
+    bw8[C] = 0x00;
+    bw8[D] = 0x01;
+
+    bw8.run();
+
+    assert_eq!((bw8[A], bw8[B]), (0x13, 0x00));
+    assert!(!bw8.status().carry);
+}
+
+#[test]
+fn compare_sets_flags_without_writing_result() {
+    let mut bw8 = Harness::assemble(
+        "
+        cmp a, 0x90
+        out [0x03], a
+        ",
+    );
+    bw8[A] = 0x10;
+
+    bw8.run();
+
+    let status = bw8.status();
+    assert_eq!(bw8[A], 0x10);
+    assert!(!status.zero);
+    assert!(!status.carry, "0x10 < 0x90 unsigned borrows");
+    assert!(status.overflow, "16 - (-112) overflows a signed byte");
+    assert!(status.negative);
+}
+
+#[test]
+fn call_and_return_through_the_stack() {
+    let mut bw8 = Harness::assemble(
+        "
+        ld x, #0xFF00
+        mv sp, x
+        ld a, #7
+        call.abs double
+        out [0x03], a
+
+        double:
+            clr.c
+            addc a, a
+            ret
+        ",
+    );
+
+    bw8.run();
+
+    assert_eq!(bw8[A], 14);
+    assert_eq!(bw8[SP], 0xFF00);
+}
+
+#[test]
+fn loads_and_stores_through_pointers() {
+    let mut bw8 = Harness::assemble(
+        "
+        ld x, #table
+        ld a, [x, #2]
+        ld y, #0x9000
+        st [y, #1], a
+        ld x, [word]
+        out [0x03], a
+
+        table:
+            #d 0x11, 0x22, 0x33
+        word:
+            #d16 0x3412
+        ",
+    );
+
+    bw8.run();
+
+    assert_eq!(bw8[A], 0x33);
+    assert_eq!(bw8.memory(0x9001), 0x33);
+    assert_eq!(bw8[X], 0x1234);
+    assert_eq!(bw8[Y], 0x9000);
+    assert_eq!(bw8.memory(bw8.symbol("table")), 0x11);
+}
+
+#[test]
+fn memory_can_be_prepared_before_running() {
+    let mut bw8 = Harness::assemble(
+        "
+        ld a, [0x8000]
+        ld b, [0x8001]
+        out [0x03], a
+        ",
+    );
+    bw8.set_memory(0x8000, &[0xAB, 0xCD]);
+
+    bw8.run();
+
+    assert_eq!((bw8[A], bw8[B]), (0xAB, 0xCD));
+}
+
+#[test]
+fn run_counts_the_clock_cycles_of_each_instruction() {
+    let mut bw8 = Harness::assemble(
+        "
+        ld a, #1
+        push a
+        pop b
+        out [0x03], a
+        ",
+    );
+
+    bw8.run();
+
+    // Each takes a cycle per instruction byte; `ld` then loads the register,
+    // `push` and `pop` move SP and the byte, and `out` writes the port.
+    assert_eq!(bw8.cycles(), 3 + 4 + 4 + 3);
+}
+
+#[test]
+fn run_counts_the_clock_cycles_of_a_taken_branch() {
+    let mut bw8 = Harness::assemble(
+        "
+        ld a, #0
+        test a
+        br.eq done - skip
+        skip:
+        ld a, #1
+        done:
+        out [0x03], a
+        ",
+    );
+
+    bw8.run();
+
+    // The branch takes its three bytes, a cycle to form the target and a
+    // cycle to load it into PC; the skipped `ld` takes none.
+    assert_eq!(bw8[A], 0);
+    assert_eq!(bw8.cycles(), 3 + 3 + 5 + 3);
+}
+
+#[test]
+fn run_counts_the_clock_cycles_of_an_interrupt_entry() {
+    let mut bw8 = Harness::assemble(
+        "
+        #addr 0x0000
+            set.i
+        #addr 0x0008
+            out [0x03], a
+        ",
+    );
+    bw8.system().inject_irq();
+
+    bw8.run();
+
+    // Entering the handler pushes PC and status and loads the vector.
+    assert_eq!(bw8.cycles(), 2 + 4 + 3);
+}
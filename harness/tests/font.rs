@@ -0,0 +1,71 @@
+use emu::font::{self, GLYPH_SIZE};
+use emu::vga::COLUMN_COUNT;
+use harness::Harness;
+
+#[test]
+fn firmware_prints_text_by_writing_character_codes_to_the_tilemap() {
+    let mut bw8 = Harness::assemble(
+        r#"
+        #include "../asm/emu.asm"
+
+            ld a, #0b111_111_11
+            ld x, #IO_PALETTE_BASE
+            out [x, 1], a
+
+            ld x, #IO_TILEMAP_BASE
+            ld a, #0x48 ; H
+            out [x, 0], a
+            ld a, #0x69 ; i
+            out [x, 2], a
+
+            ; Wait for the next frame to start, then for it to be drawn.
+        wait_for_start:
+            in a, [IO_VGA_STATUS]
+            and a, VGA_STATUS_VBLANK
+            br.eq.abs wait_for_start
+        wait_for_frame:
+            in a, [IO_VGA_STATUS]
+            and a, VGA_STATUS_VBLANK
+            br.ne.abs wait_for_frame
+        wait_for_vblank:
+            in a, [IO_VGA_STATUS]
+            and a, VGA_STATUS_VBLANK
+            br.eq.abs wait_for_vblank
+            out [EMULATOR_BREAKPOINT], a
+        "#,
+    );
+    bw8.system().load_font();
+
+    bw8.run();
+
+    let glyphs = font::glyphs();
+    let frame = bw8.system().vga_frame();
+    for (column, glyph) in [glyphs[b'H' as usize], glyphs[b'I' as usize]]
+        .iter()
+        .enumerate()
+    {
+        for (y, row) in glyph.iter().enumerate() {
+            for x in 0..GLYPH_SIZE {
+                let set = row & (0x80 >> x) != 0;
+                let offset = 4 * (column * GLYPH_SIZE + x + y * COLUMN_COUNT);
+                let expected = if set { [224, 224, 192] } else { [0, 0, 0] };
+                assert_eq!(
+                    frame[offset..offset + 3],
+                    expected,
+                    "pixel ({x}, {y}) of {column}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn font_has_the_letters_and_leaves_other_characters_blank() {
+    let glyphs = font::glyphs();
+
+    assert_eq!(glyphs[b'A' as usize], glyphs[b'a' as usize]);
+    assert_ne!(glyphs[b'A' as usize], glyphs[b'B' as usize]);
+    assert!(glyphs[b'Z' as usize].iter().any(|&row| row != 0));
+    assert_eq!(glyphs[b' ' as usize], [0; GLYPH_SIZE]);
+    assert_eq!(glyphs[b'0' as usize], [0; GLYPH_SIZE]);
+}
@@ -0,0 +1,98 @@
+use arch::Architectural8::{B, C};
+use emu::intc::{NO_SOURCE, SOURCE_EXTERNAL, SOURCE_TIMER};
+use harness::Harness;
+
+/// Starts the timer and waits, with interrupts disabled, for it to request an
+/// IRQ, then enables interrupts with `mask` applied. The handler stops with
+/// the current source in `c` and the pending sources in `b`.
+fn timer_and_external(mask: u8) -> Harness {
+    let mut bw8 = Harness::assemble(&format!(
+        r#"
+        #include "../asm/emu.asm"
+
+        #addr 0x0000
+            jmp.abs boot
+        #addr 0x0008
+            jmp.abs isr
+
+        boot:
+            ld x, #0xFF00
+            mv sp, x
+            ld a, #1
+            out [IO_PIT_RELOAD_LOW], a
+            ld a, #PIT_CONTROL_ENABLE | PIT_CONTROL_IRQ_ENABLE
+            out [IO_PIT_CONTROL], a
+        wait:
+            in a, [IO_INTC_PENDING]
+            and a, 1 << IRQ_TIMER
+            br.eq.abs wait
+            ld a, #{}
+            out [IO_INTC_MASK], a
+            set.i
+        spin:
+            jmp.abs spin
+
+        isr:
+            in c, [IO_INTC_CURRENT]
+            in b, [IO_INTC_PENDING]
+            out [EMULATOR_BREAKPOINT], a
+        "#,
+        mask
+    ));
+    bw8.system().inject_irq();
+    bw8
+}
+
+#[test]
+fn highest_priority_source_is_current() {
+    let mut bw8 = timer_and_external(0xFF);
+
+    bw8.run();
+
+    assert_eq!(bw8[C], SOURCE_TIMER);
+    assert_eq!(bw8[B], 1 << SOURCE_TIMER | 1 << SOURCE_EXTERNAL);
+}
+
+#[test]
+fn masked_sources_stay_pending_without_interrupting() {
+    let mut bw8 = timer_and_external(1 << SOURCE_EXTERNAL);
+
+    bw8.run();
+
+    assert_eq!(bw8[C], SOURCE_EXTERNAL);
+    assert_eq!(bw8[B], 1 << SOURCE_TIMER | 1 << SOURCE_EXTERNAL);
+}
+
+#[test]
+fn acknowledging_the_current_source_clears_it() {
+    let mut bw8 = Harness::assemble(
+        r#"
+        #include "../asm/emu.asm"
+
+        #addr 0x0000
+            jmp.abs boot
+        #addr 0x0008
+            jmp.abs isr
+
+        boot:
+            ld x, #0xFF00
+            mv sp, x
+            set.i
+        spin:
+            jmp.abs spin
+
+        isr:
+            in c, [IO_INTC_CURRENT]
+            ld a, #1 << IRQ_EXTERNAL
+            out [IO_INTC_PENDING], a
+            in b, [IO_INTC_CURRENT]
+            out [EMULATOR_BREAKPOINT], a
+        "#,
+    );
+    bw8.system().inject_irq();
+
+    bw8.run();
+
+    assert_eq!(bw8[C], SOURCE_EXTERNAL);
+    assert_eq!(bw8[B], NO_SOURCE);
+}
@@ -0,0 +1,115 @@
+use arch::Architectural8::{A, C};
+use emu::intc::SOURCE_KEYBOARD;
+use emu::keyboard::{Key, BREAK_PREFIX, EXTENDED_PREFIX};
+use harness::Harness;
+
+#[test]
+fn typed_text_arrives_as_make_and_break_scancodes() {
+    let mut bw8 = Harness::assemble(
+        r#"
+        #include "../asm/emu.asm"
+
+            ld x, #0x9000
+            ld c, #0
+        poll:
+            in a, [IO_KEYBOARD_STATUS]
+            and a, KEYBOARD_STATUS_READY
+            br.eq.abs poll
+            in a, [IO_KEYBOARD_DATA]
+            st [x, c], a
+            inc c
+            cmp c, 9
+            br.ne.abs poll
+            out [EMULATOR_BREAKPOINT], a
+        "#,
+    );
+    bw8.system().type_text("Hi");
+
+    bw8.run();
+
+    let (shift, h, i) = (Key::SHIFT_LEFT.code(), Key::H.code(), Key::I.code());
+    let expected = [
+        shift,
+        h,
+        BREAK_PREFIX,
+        h,
+        BREAK_PREFIX,
+        shift,
+        i,
+        BREAK_PREFIX,
+        i,
+    ];
+    for (offset, byte) in expected.iter().enumerate() {
+        assert_eq!(bw8.memory(0x9000 + offset as u16), *byte, "byte {offset}");
+    }
+}
+
+#[test]
+fn bytes_wait_in_the_keyboard_while_the_fifo_is_full() {
+    let mut bw8 = Harness::assemble(
+        r#"
+        #include "../asm/emu.asm"
+
+            ; Leave the FIFO alone long enough for every byte to be sent,
+            ; then read them all.
+            ld c, #64
+        outer:
+            ld d, #0
+        inner:
+            dec d
+            br.ne.abs inner
+            dec c
+            br.ne.abs outer
+        poll:
+            in a, [IO_KEYBOARD_STATUS]
+            and a, KEYBOARD_STATUS_READY
+            br.eq.abs poll
+            in a, [IO_KEYBOARD_DATA]
+            inc c
+            cmp c, 30
+            br.ne.abs poll
+            out [EMULATOR_BREAKPOINT], a
+        "#,
+    );
+    // Ten keys, each pressed and released, are 30 bytes.
+    bw8.system().type_text("abcdefghij");
+
+    bw8.run();
+
+    assert_eq!(bw8[A], Key::J.code());
+}
+
+#[test]
+fn extended_keys_raise_an_irq_with_the_prefix_first() {
+    let mut bw8 = Harness::assemble(
+        r#"
+        #include "../asm/emu.asm"
+
+        #addr 0x0000
+            jmp.abs boot
+        #addr 0x0008
+            jmp.abs isr
+
+        boot:
+            ld x, #0xFF00
+            mv sp, x
+            ld a, #KEYBOARD_CONTROL_IRQ_ENABLE
+            out [IO_KEYBOARD_CONTROL], a
+            set.i
+        spin:
+            jmp.abs spin
+
+        isr:
+            in c, [IO_INTC_CURRENT]
+            in a, [IO_KEYBOARD_DATA]
+            out [EMULATOR_BREAKPOINT], a
+        "#,
+    );
+    bw8.system().press_key(Key::ARROW_UP);
+
+    bw8.run();
+
+    assert_eq!(bw8[C], SOURCE_KEYBOARD);
+    assert_eq!(bw8[A], EXTENDED_PREFIX);
+    assert!(Key::ARROW_UP.is_extended());
+}
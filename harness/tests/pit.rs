@@ -0,0 +1,79 @@
+use arch::Architectural8::{A, B, C};
+use emu::pit::{CONTROL_ENABLE, STATUS_EXPIRED};
+use harness::Harness;
+
+#[test]
+fn periodic_timer_raises_irqs_until_acknowledged() {
+    let mut bw8 = Harness::assemble(
+        r#"
+        #include "../asm/emu.asm"
+
+        #addr 0x0000
+            jmp.abs boot
+        #addr 0x0008
+            jmp.abs isr
+
+        boot:
+            ld x, #0xFF00
+            mv sp, x
+            ld c, #0
+            ld a, #0
+            out [IO_PIT_RELOAD_HIGH], a
+            ld a, #200
+            out [IO_PIT_RELOAD_LOW], a
+            ld a, #PIT_CONTROL_ENABLE | PIT_CONTROL_PERIODIC | PIT_CONTROL_IRQ_ENABLE
+            out [IO_PIT_CONTROL], a
+            set.i
+        spin:
+            cmp c, 3
+            br.ne.abs spin
+            in a, [IO_PIT_CONTROL]
+            out [EMULATOR_BREAKPOINT], a
+
+        isr:
+            out [IO_PIT_STATUS], a
+            ld a, #1 << IRQ_TIMER
+            out [IO_INTC_PENDING], a
+            inc c
+            reti
+        "#,
+    );
+
+    bw8.run();
+
+    assert_eq!(bw8[C], 3);
+    assert_ne!(bw8[A] & CONTROL_ENABLE, 0, "a periodic timer keeps running");
+}
+
+#[test]
+fn one_shot_timer_stops_after_expiring() {
+    let mut bw8 = Harness::assemble(
+        r#"
+        #include "../asm/emu.asm"
+
+            ld a, #3
+            out [IO_PIT_PRESCALER], a
+            ld a, #10
+            out [IO_PIT_RELOAD_LOW], a
+            ld a, #0
+            out [IO_PIT_RELOAD_HIGH], a
+            ld a, #PIT_CONTROL_ENABLE
+            out [IO_PIT_CONTROL], a
+            ld c, #0
+        wait:
+            inc c
+            in b, [IO_PIT_STATUS]
+            test b
+            br.eq.abs wait
+            in a, [IO_PIT_CONTROL]
+            out [EMULATOR_BREAKPOINT], a
+        "#,
+    );
+
+    bw8.run();
+
+    assert_eq!(bw8[B], STATUS_EXPIRED);
+    assert_eq!(bw8[A] & CONTROL_ENABLE, 0);
+    // 40 cycles elapse at four cycles per tick, and each iteration takes 14.
+    assert!((3..=4).contains(&bw8[C]), "waited {} iterations", bw8[C]);
+}
@@ -0,0 +1,269 @@
+use arch::Architectural16::{PC, SP, X};
+use arch::Architectural8::{A, B};
+use arch::{Nibble, PhysicalAddress, PrivilegeLevel, StopReason};
+use harness::Harness;
+
+/// Enters `user` in user mode, in bank 0. The fault and SWI handlers pop the
+/// interrupt frame, leaving the saved status in `b` and the saved program
+/// counter in `x`, and stop at a breakpoint with `a` set to 0xFA for a fault
+/// or 0x5C for an SWI.
+fn user_program(user: &str) -> Harness {
+    user_program_in(0, 0b0100_0000, user)
+}
+
+/// Enters `user` with `status`, which should be a user mode one, in `bank`.
+/// The kernel's stack pointer is 0xFF00.
+fn user_program_in(bank: u8, status: u8, user: &str) -> Harness {
+    Harness::assemble(&format!(
+        "
+        #addr 0x0000
+            jmp.abs boot
+        #addr 0x000C
+            jmp.abs swi
+        #addr 0x0010
+            jmp.abs fault
+
+        boot:
+            ld x, #0xFF00
+            mv sp, x
+            ld a, #{}
+            mv br, a
+            ld y, #user
+            ld b, #{}
+            push y
+            push b
+            reti
+
+        fault:
+            pop b
+            pop x
+            ld a, #0xFA
+            out [0x03], a
+
+        swi:
+            pop b
+            pop x
+            ld a, #0x5C
+            out [0x03], a
+
+        user:
+            {}
+        ",
+        bank, status, user
+    ))
+}
+
+#[test]
+fn privileged_instructions_fault_in_user_mode() {
+    for instruction in [
+        "set.i",
+        "clr.i",
+        "set.b",
+        "clr.b",
+        "mv br, a",
+        "mv x, usp",
+        "mv usp, x",
+        "reti",
+    ] {
+        let mut bw8 = user_program(&format!("nop\nfaulting: {}", instruction));
+
+        bw8.run();
+
+        assert_eq!(bw8[A], 0xFA, "`{}` did not fault", instruction);
+        assert_eq!(bw8[X], bw8.symbol("faulting"));
+        assert_eq!(bw8[B] & 0b0100_0000, 0b0100_0000, "saved status is user");
+        assert_eq!(bw8.status().privilege_level, PrivilegeLevel::Kernel);
+        assert!(!bw8.status().irq_enable);
+    }
+}
+
+#[test]
+fn kernel_io_ports_fault_in_user_mode() {
+    for instruction in ["out [0x01], a", "in a, [0x02]", "out [x, 0], a"] {
+        let mut bw8 = user_program(&format!("ld x, #0x7FFF\nfaulting: {}", instruction));
+
+        bw8.run();
+
+        assert_eq!(bw8[A], 0xFA, "`{}` did not fault", instruction);
+        assert_eq!(bw8[X], bw8.symbol("faulting"));
+    }
+}
+
+#[test]
+fn user_mode_can_access_user_io_and_call_the_kernel() {
+    let mut bw8 = user_program(
+        "
+        ld x, #0x8000
+        ld a, #0x42
+        out [x, 0], a
+        swi
+        ",
+    );
+
+    bw8.run();
+
+    assert_eq!(bw8[A], 0x5C);
+    assert_eq!(bw8.system().read_framebuffer(0x0000), 0x42);
+}
+
+#[test]
+fn interrupt_frames_stay_on_the_kernels_stack() {
+    // User code can point its stack pointer anywhere, even with bank enable
+    // set in its status; neither sends the frame into the kernel's data or
+    // the user's bank.
+    for status in [0b0100_0000, 0b0110_0000] {
+        let mut bw8 = user_program_in(
+            1,
+            status,
+            "
+            ld x, #0x9002
+            mv sp, x
+            swi
+            returned:
+            ",
+        );
+        bw8.set_memory(0x9000, &[0x11, 0x22, 0x33]);
+
+        bw8.run();
+
+        assert_eq!(bw8[A], 0x5C);
+        assert_eq!(bw8[B], status);
+        assert_eq!(bw8[X], bw8.symbol("returned"));
+        assert_eq!(bw8[SP], 0xFF00);
+        assert_eq!(bw8.system().cpu().banked_sp(), 0x9002);
+
+        for (bank, expected) in [(0, [0x11, 0x22, 0x33]), (1, [0x00; 3])] {
+            let memory: Vec<u8> = (0x9000..0x9003)
+                .map(|base| {
+                    let address = PhysicalAddress::new(Nibble::new(bank).unwrap(), base);
+                    bw8.system().read_physical_memory(address)
+                })
+                .collect();
+            assert_eq!(memory, expected, "bank {}", bank);
+        }
+    }
+}
+
+#[test]
+fn reti_returns_to_the_users_own_stack() {
+    let mut bw8 = Harness::assemble(
+        "
+        #addr 0x0000
+            jmp.abs boot
+        #addr 0x000C
+            jmp.abs swi
+
+        boot:
+            ld x, #0xFF00
+            mv sp, x
+            ld y, #user
+            ld b, #0b0100_0000
+            push y
+            push b
+            reti
+
+        swi:
+            reti
+
+        user:
+            ld x, #0xA000
+            mv sp, x
+            swi
+            push a
+            mv x, sp
+        spin:
+            jmp.abs spin
+        ",
+    )
+    .with_cycle_limit(200);
+    bw8[A] = 0x77;
+
+    assert_eq!(bw8.run_until_stopped(), StopReason::CycleLimit);
+    assert_eq!(bw8[PC], bw8.symbol("spin"));
+
+    assert_eq!(bw8[X], 0x9FFF);
+    assert_eq!(bw8.memory(0xA000), 0x77);
+    assert_eq!(bw8.system().cpu().banked_sp(), 0xFF00);
+}
+
+#[test]
+fn the_kernel_switches_between_user_stacks() {
+    // Each SWI swaps the user's stack pointer with the one saved at 0x9000,
+    // as a scheduler switching between two processes' stacks would.
+    let mut bw8 = Harness::assemble(
+        "
+        #addr 0x0000
+            jmp.abs boot
+        #addr 0x000C
+            jmp.abs swi
+
+        boot:
+            ld x, #0xFF00
+            mv sp, x
+            ld x, #0xB000
+            st [0x9000], x
+            ld y, #user
+            ld b, #0b0100_0000
+            push y
+            push b
+            reti
+
+        swi:
+            mv x, usp
+            ld y, [0x9000]
+            st [0x9000], x
+            mv x, y
+            mv usp, x
+            reti
+
+        user:
+            ld x, #0xA000
+            mv sp, x
+            ld a, #0x11
+            push a
+            swi
+            ld a, #0x22
+            push a
+            swi
+            pop b
+            mv x, sp
+        spin:
+            jmp.abs spin
+        ",
+    )
+    .with_cycle_limit(400);
+
+    assert_eq!(bw8.run_until_stopped(), StopReason::CycleLimit);
+    assert_eq!(bw8[PC], bw8.symbol("spin"));
+
+    assert_eq!(bw8[B], 0x11);
+    assert_eq!(bw8[X], 0xA000);
+    assert_eq!(bw8.memory(0xA000), 0x11);
+    assert_eq!(bw8.memory(0xB000), 0x22);
+    assert_eq!(bw8.memory(0x9000), 0xFF);
+    assert_eq!(bw8.memory(0x9001), 0xAF);
+    assert_eq!(bw8.system().cpu().banked_sp(), 0xFF00);
+}
+
+#[test]
+fn code_memory_is_read_from_the_bank_being_executed() {
+    let mut bw8 = user_program_in(
+        2,
+        0b0100_0000,
+        "
+        spin:
+            jmp.abs spin
+        ",
+    )
+    .with_cycle_limit(100);
+    for bank in [0, 2] {
+        let address = PhysicalAddress::new(Nibble::new(bank).unwrap(), 0x9000);
+        bw8.system().write_physical_memory(address, 0xB0 | bank);
+    }
+
+    assert_eq!(bw8.system().read_code_memory(0x9000), 0xB0);
+
+    assert_eq!(bw8.run_until_stopped(), StopReason::CycleLimit);
+    assert_eq!(bw8.status().privilege_level, PrivilegeLevel::User);
+    assert_eq!(bw8.system().read_code_memory(0x9000), 0xB2);
+}
@@ -0,0 +1,60 @@
+use arch::Architectural16::X;
+use arch::Architectural8::{A, C};
+use arch::{PrivilegeLevel, StopReason};
+use harness::Harness;
+
+#[test]
+fn irq_hello_world_runs_user_code_from_bank_5() {
+    let mut bw8 = Harness::assemble_file("../asm/irq_hello_world.asm").with_cycle_limit(1_000);
+
+    assert_eq!(bw8.run_until_stopped(), StopReason::CycleLimit);
+    assert_eq!(bw8.system().cpu().br().as_inner(), 5);
+    assert_eq!(bw8.status().privilege_level, PrivilegeLevel::User);
+
+    bw8[A] = 0xA5;
+    bw8[X] = 0x1234;
+    bw8.system().inject_irq();
+    bw8.run();
+
+    assert_eq!(bw8[C], 1);
+    assert_eq!(bw8.status().privilege_level, PrivilegeLevel::Kernel);
+
+    // The interrupt frame was saved on the kernel's stack, so `reti` returns
+    // to the user program in bank 5, with the registers the handler used
+    // restored.
+    assert_eq!(bw8.run_until_stopped(), StopReason::CycleLimit);
+    assert_eq!(bw8.status().privilege_level, PrivilegeLevel::User);
+    assert_eq!(bw8.system().cpu().br().as_inner(), 5);
+    assert_eq!(bw8[A], 0xA5);
+    assert_eq!(bw8[X], 0x1234);
+
+    bw8.system().inject_irq();
+    bw8.run();
+
+    assert_eq!(bw8[C], 2);
+}
+
+#[test]
+fn hello_world_writes_its_greeting_to_the_putchar_port() {
+    let mut bw8 = Harness::assemble_file("../asm/hello_world.asm").with_cycle_limit(1_000);
+
+    assert_eq!(bw8.run_until_stopped(), StopReason::CycleLimit);
+    assert_eq!(bw8.system().take_output(), b"Hello, World!\n");
+    assert_eq!(bw8.system().take_output(), b"");
+}
+
+#[test]
+fn putchar_output_is_passed_through_as_raw_bytes() {
+    let mut bw8 = Harness::assemble(
+        "
+        ld a, #0xe9
+        out [0x01], a
+        ld a, #0x00
+        out [0x01], a
+        out [0x03], a
+        ",
+    );
+
+    bw8.run();
+    assert_eq!(bw8.system().take_output(), [0xe9, 0x00]);
+}
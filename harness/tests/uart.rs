@@ -0,0 +1,106 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use arch::Architectural8::{A, C};
+use arch::Byte;
+use emu::serial::SerialBackend;
+use emu::uart::STATUS_RX_READY;
+use harness::Harness;
+
+/// The host end of the line, shared with the test.
+#[derive(Clone, Default)]
+struct Host {
+    to_uart: Rc<RefCell<VecDeque<Byte>>>,
+    from_uart: Rc<RefCell<Vec<Byte>>>,
+}
+
+impl SerialBackend for Host {
+    fn receive(&mut self) -> Option<Byte> {
+        self.to_uart.borrow_mut().pop_front()
+    }
+
+    fn transmit(&mut self, data: Byte) {
+        self.from_uart.borrow_mut().push(data);
+    }
+}
+
+fn connect(bw8: &mut Harness) -> Host {
+    let host = Host::default();
+    bw8.system().connect_serial(Box::new(host.clone()));
+    host
+}
+
+#[test]
+fn transmits_when_the_fifo_has_room() {
+    let mut bw8 = Harness::assemble(
+        r#"
+        #include "../asm/emu.asm"
+
+            ld x, #message
+        next:
+            ld a, [x, #0]
+            cmp a, 0
+            br.eq.abs drain
+        wait:
+            in b, [IO_UART_STATUS]
+            and b, UART_STATUS_TX_READY
+            br.eq.abs wait
+            out [IO_UART_DATA], a
+            inc x
+            jmp.abs next
+        drain:
+            in b, [IO_UART_STATUS]
+            and b, UART_STATUS_TX_EMPTY
+            br.eq.abs drain
+            out [EMULATOR_BREAKPOINT], a
+
+        message:
+            #d "The quick brown fox jumps over the lazy dog\0"
+        "#,
+    );
+    let host = connect(&mut bw8);
+
+    bw8.run();
+
+    assert_eq!(
+        host.from_uart.borrow().as_slice(),
+        b"The quick brown fox jumps over the lazy dog"
+    );
+}
+
+#[test]
+fn received_bytes_raise_an_irq() {
+    let mut bw8 = Harness::assemble(
+        r#"
+        #include "../asm/emu.asm"
+
+        #addr 0x0000
+            jmp.abs boot
+        #addr 0x0008
+            jmp.abs isr
+
+        boot:
+            ld x, #0xFF00
+            mv sp, x
+            ld a, #UART_CONTROL_RX_IRQ_ENABLE
+            out [IO_UART_CONTROL], a
+            set.i
+        spin:
+            jmp.abs spin
+
+        isr:
+            in c, [IO_UART_DATA]
+            in a, [IO_UART_STATUS]
+            out [EMULATOR_BREAKPOINT], a
+        "#,
+    );
+    let host = connect(&mut bw8);
+    host.to_uart.borrow_mut().push_back(b'!');
+
+    bw8.run();
+
+    assert_eq!(bw8[C], b'!');
+    assert_eq!(bw8[A] & STATUS_RX_READY, 0, "the FIFO was drained");
+    assert!(!bw8.status().irq_enable, "the IRQ was serviced");
+}
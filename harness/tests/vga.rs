@@ -0,0 +1,368 @@
+use arch::Architectural8::{A, B, C, D};
+use emu::vga::{COLUMN_COUNT, ROW_COUNT, SPRITES_PER_LINE, STATUS_SPRITE_OVERFLOW, STATUS_VBLANK};
+use harness::Harness;
+
+#[test]
+fn vblank_irq_fires_once_per_frame_at_the_end_of_the_visible_lines() {
+    let mut bw8 = Harness::assemble(
+        r#"
+        #include "../asm/emu.asm"
+
+        #addr 0x0000
+            jmp.abs boot
+        #addr 0x0008
+            jmp.abs isr
+
+        boot:
+            ld x, #0xFF00
+            mv sp, x
+            ld c, #0
+            ld a, #VGA_CONTROL_VBLANK_IRQ_ENABLE
+            out [IO_VGA_CONTROL], a
+            set.i
+        spin:
+            cmp c, 2
+            br.ne.abs spin
+            out [EMULATOR_BREAKPOINT], a
+
+        isr:
+            in a, [IO_VGA_LINE_LOW]
+            in b, [IO_VGA_LINE_HIGH]
+            in d, [IO_VGA_STATUS]
+            push a
+            ld a, #1 << IRQ_VBLANK
+            out [IO_INTC_PENDING], a
+            pop a
+            inc c
+            reti
+        "#,
+    )
+    .with_cycle_limit(2 * emu::vga::CYCLES_PER_FRAME + 1_000);
+
+    bw8.run();
+
+    assert_eq!(bw8[C], 2);
+    assert_eq!(u16::from_le_bytes([bw8[A], bw8[B]]), ROW_COUNT as u16);
+    assert_ne!(bw8[D] & STATUS_VBLANK, 0);
+}
+
+#[test]
+fn palette_changes_take_effect_from_the_line_being_scanned() {
+    let mut bw8 = Harness::assemble(
+        r#"
+        #include "../asm/emu.asm"
+
+            ld x, #IO_PALETTE_BASE
+        wait_for_vblank:
+            in a, [IO_VGA_STATUS]
+            and a, VGA_STATUS_VBLANK
+            br.eq.abs wait_for_vblank
+            ld a, #0b111_000_00
+            out [x, 0], a
+        wait_for_frame:
+            in a, [IO_VGA_STATUS]
+            and a, VGA_STATUS_VBLANK
+            br.ne.abs wait_for_frame
+        wait_for_middle:
+            in a, [IO_VGA_LINE_LOW]
+            cmp a, 240
+            br.ne.abs wait_for_middle
+            ld a, #0b000_000_11
+            out [x, 0], a
+        wait_for_end:
+            in a, [IO_VGA_STATUS]
+            and a, VGA_STATUS_VBLANK
+            br.eq.abs wait_for_end
+            out [EMULATOR_BREAKPOINT], a
+        "#,
+    );
+
+    bw8.run();
+
+    let frame = bw8.system().vga_frame();
+    let pixel = |x: usize, y: usize| {
+        let offset = 4 * (x + y * COLUMN_COUNT);
+        &frame[offset..offset + 3]
+    };
+
+    assert_eq!(pixel(0, 0), [224, 0, 0]);
+    assert_eq!(pixel(COLUMN_COUNT - 1, 239), [224, 0, 0]);
+    assert_eq!(pixel(0, 241), [0, 0, 192]);
+    assert_eq!(pixel(COLUMN_COUNT - 1, ROW_COUNT - 1), [0, 0, 192]);
+}
+
+#[test]
+fn the_vga_keeps_its_own_clock_when_the_processor_clock_changes() {
+    let source = r#"
+        #include "../asm/emu.asm"
+
+        wait_for_vblank:
+            in a, [IO_VGA_STATUS]
+            and a, VGA_STATUS_VBLANK
+            br.eq.abs wait_for_vblank
+            out [EMULATOR_BREAKPOINT], a
+        "#;
+    let vblank = ROW_COUNT * emu::vga::CYCLES_PER_LINE;
+
+    for multiplier in [1, 2] {
+        let mut bw8 = Harness::assemble(source);
+        let rate = bw8.system().clock_rate();
+        bw8.system().set_clock_rate(rate * multiplier as f64);
+
+        bw8.run();
+
+        // One iteration of the loop may pass before vblank is noticed.
+        let cycles = bw8.cycles();
+        let expected = vblank * multiplier;
+        assert!(
+            (expected..expected + 20).contains(&cycles),
+            "reached vblank after {cycles} cycles at {multiplier}x"
+        );
+    }
+}
+
+/// Runs `setup`, then waits for the next whole frame to be drawn before
+/// stopping with the VGA status in `d`.
+fn draw_frame(setup: &str) -> Harness {
+    let mut bw8 = Harness::assemble(&format!(
+        r#"
+        #include "../asm/emu.asm"
+
+            {}
+        wait_for_start:
+            in a, [IO_VGA_STATUS]
+            and a, VGA_STATUS_VBLANK
+            br.eq.abs wait_for_start
+        wait_for_frame:
+            in a, [IO_VGA_STATUS]
+            and a, VGA_STATUS_VBLANK
+            br.ne.abs wait_for_frame
+        wait_for_vblank:
+            in d, [IO_VGA_STATUS]
+            mv a, d
+            and a, VGA_STATUS_VBLANK
+            br.eq.abs wait_for_vblank
+            out [EMULATOR_BREAKPOINT], a
+        "#,
+        setup
+    ))
+    .with_cycle_limit(3 * emu::vga::CYCLES_PER_FRAME);
+
+    bw8.run();
+    bw8
+}
+
+fn pixel(bw8: &mut Harness, x: usize, y: usize) -> [u8; 3] {
+    let offset = 4 * (x + y * COLUMN_COUNT);
+    bw8.system().vga_frame()[offset..offset + 3]
+        .try_into()
+        .unwrap()
+}
+
+const BLACK: [u8; 3] = [0, 0, 0];
+const RED: [u8; 3] = [224, 0, 0];
+const GREEN: [u8; 3] = [0, 224, 0];
+
+#[test]
+fn scroll_registers_wrap_around_the_tilemap() {
+    let mut bw8 = draw_frame(
+        r#"
+            ; Palette 1 is red, and the tile at column 1 of the last map
+            ; row uses it.
+            ld a, #0b111_000_00
+            ld x, #IO_PALETTE_BASE + 16
+            out [x, 0], a
+            ld a, #1
+            ld x, #IO_TILEMAP_BASE + 63 * 256
+            out [x, 3], a
+
+            ; Scroll to (5, 508).
+            ld a, #5
+            out [IO_VGA_SCROLL_X_LOW], a
+            ld a, #508 & 0xFF
+            out [IO_VGA_SCROLL_Y_LOW], a
+            ld a, #508 >> 8
+            out [IO_VGA_SCROLL_Y_HIGH], a
+        "#,
+    );
+
+    assert_eq!(pixel(&mut bw8, 3, 0), RED);
+    assert_eq!(pixel(&mut bw8, 10, 3), RED);
+    assert_eq!(pixel(&mut bw8, 2, 0), BLACK);
+    assert_eq!(pixel(&mut bw8, 11, 0), BLACK);
+    assert_eq!(pixel(&mut bw8, 3, 4), BLACK);
+}
+
+#[test]
+fn sprites_are_drawn_over_tiles_with_transparency_flipping_and_priority() {
+    let mut bw8 = draw_frame(
+        r#"
+            ; Bitmap 1 has a single pixel of color 1 at its top left.
+            ld a, #0b1000_0000
+            ld x, #IO_BITMAP_BASE + 32
+            out [x, 0], a
+
+            ; Palette 2 is green and palette 3 red, both over an opaque
+            ; color 0 that sprites must not draw.
+            ld a, #0xFF
+            ld x, #IO_PALETTE_BASE + 2 * 16
+            out [x, 0], a
+            out [x, 16], a
+            ld a, #0b000_111_00
+            out [x, 1], a
+            ld a, #0b111_000_00
+            out [x, 17], a
+
+            ; Sprite 0 is flipped, putting its pixel at (107, 57). Sprite 1,
+            ; beneath it, has its pixel in the same place.
+            ld x, #IO_SPRITE_BASE
+            ld a, #100
+            out [x, SPRITE_X_LOW], a
+            ld a, #50
+            out [x, SPRITE_Y_LOW], a
+            ld a, #1
+            out [x, SPRITE_BITMAP], a
+            ld a, #2
+            out [x, SPRITE_PALETTE], a
+            ld a, #SPRITE_ENABLE | SPRITE_FLIP_X | SPRITE_FLIP_Y
+            out [x, SPRITE_FLAGS], a
+
+            ld a, #107
+            out [x, SPRITE_SIZE + SPRITE_X_LOW], a
+            ld a, #57
+            out [x, SPRITE_SIZE + SPRITE_Y_LOW], a
+            ld a, #1
+            out [x, SPRITE_SIZE + SPRITE_BITMAP], a
+            ld a, #3
+            out [x, SPRITE_SIZE + SPRITE_PALETTE], a
+            ld a, #SPRITE_ENABLE
+            out [x, SPRITE_SIZE + SPRITE_FLAGS], a
+        "#,
+    );
+
+    assert_eq!(pixel(&mut bw8, 107, 57), GREEN);
+    assert_eq!(pixel(&mut bw8, 100, 50), BLACK);
+    assert_eq!(pixel(&mut bw8, 106, 57), BLACK);
+    assert_eq!(pixel(&mut bw8, 108, 57), BLACK);
+}
+
+#[test]
+fn sprites_past_the_per_line_limit_are_dropped() {
+    let mut bw8 = draw_frame(&format!(
+        r#"
+            ld a, #0b1000_0000
+            ld x, #IO_BITMAP_BASE + 32
+            out [x, 0], a
+            ld a, #0b111_000_00
+            ld x, #IO_PALETTE_BASE + 16
+            out [x, 1], a
+
+            ; Put one more sprite than fits on line 200, eight pixels apart.
+            ld x, #IO_SPRITE_BASE
+            ld b, #0
+        next_sprite:
+            out [x, SPRITE_X_LOW], b
+            ld a, #200
+            out [x, SPRITE_Y_LOW], a
+            ld a, #1
+            out [x, SPRITE_BITMAP], a
+            out [x, SPRITE_PALETTE], a
+            out [x, SPRITE_FLAGS], a
+            lea [x, SPRITE_SIZE]
+            clr.c
+            addc b, 8
+            cmp b, 8 * {}
+            br.ne.abs next_sprite
+        "#,
+        SPRITES_PER_LINE + 1
+    ));
+
+    assert_eq!(pixel(&mut bw8, 8 * (SPRITES_PER_LINE - 1), 200), RED);
+    assert_eq!(pixel(&mut bw8, 8 * SPRITES_PER_LINE, 200), BLACK);
+    assert_ne!(bw8[D] & STATUS_SPRITE_OVERFLOW, 0);
+}
+
+#[test]
+fn sprites_wrap_around_the_left_and_top_edges() {
+    let mut bw8 = draw_frame(
+        r#"
+            ; Bitmap 1 is solid color 1, which palette 1 makes red.
+            ld a, #0xFF
+            ld x, #IO_BITMAP_BASE + 32
+            out [x, 0], a
+            out [x, 1], a
+            out [x, 2], a
+            out [x, 3], a
+            out [x, 4], a
+            out [x, 5], a
+            out [x, 6], a
+            out [x, 7], a
+            ld a, #0b111_000_00
+            ld x, #IO_PALETTE_BASE + 16
+            out [x, 1], a
+
+            ; Sprite 0 is at (1020, 510), or (-4, -2).
+            ld x, #IO_SPRITE_BASE
+            ld a, #1020 & 0xFF
+            out [x, SPRITE_X_LOW], a
+            ld a, #1020 >> 8
+            out [x, SPRITE_X_HIGH], a
+            ld a, #510 & 0xFF
+            out [x, SPRITE_Y_LOW], a
+            ld a, #510 >> 8
+            out [x, SPRITE_Y_HIGH], a
+            ld a, #1
+            out [x, SPRITE_BITMAP], a
+            out [x, SPRITE_PALETTE], a
+            out [x, SPRITE_FLAGS], a
+        "#,
+    );
+
+    assert_eq!(pixel(&mut bw8, 0, 0), RED);
+    assert_eq!(pixel(&mut bw8, 3, 5), RED);
+    assert_eq!(pixel(&mut bw8, 4, 0), BLACK);
+    assert_eq!(pixel(&mut bw8, 0, 6), BLACK);
+    assert_eq!(pixel(&mut bw8, COLUMN_COUNT - 1, 0), BLACK);
+    assert_eq!(pixel(&mut bw8, 0, ROW_COUNT - 1), BLACK);
+}
+
+#[test]
+fn sprites_straddling_the_top_edge_count_towards_the_per_line_limit() {
+    let mut bw8 = draw_frame(&format!(
+        r#"
+            ; Bitmap 1 has a single pixel of color 1 in the left of row 4.
+            ld a, #0b1000_0000
+            ld x, #IO_BITMAP_BASE + 32
+            out [x, 4], a
+            ld a, #0b111_000_00
+            ld x, #IO_PALETTE_BASE + 16
+            out [x, 1], a
+
+            ; Put one more sprite than fits at Y 508, or -4, so that row 4
+            ; of each is on line 0.
+            ld x, #IO_SPRITE_BASE
+            ld b, #0
+        next_sprite:
+            out [x, SPRITE_X_LOW], b
+            ld a, #508 & 0xFF
+            out [x, SPRITE_Y_LOW], a
+            ld a, #508 >> 8
+            out [x, SPRITE_Y_HIGH], a
+            ld a, #1
+            out [x, SPRITE_BITMAP], a
+            out [x, SPRITE_PALETTE], a
+            out [x, SPRITE_FLAGS], a
+            lea [x, SPRITE_SIZE]
+            clr.c
+            addc b, 8
+            cmp b, 8 * {}
+            br.ne.abs next_sprite
+        "#,
+        SPRITES_PER_LINE + 1
+    ));
+
+    assert_eq!(pixel(&mut bw8, 8 * (SPRITES_PER_LINE - 1), 0), RED);
+    assert_eq!(pixel(&mut bw8, 8 * SPRITES_PER_LINE, 0), BLACK);
+    assert_eq!(pixel(&mut bw8, 0, 1), BLACK);
+    assert_ne!(bw8[D] & STATUS_SPRITE_OVERFLOW, 0);
+}
@@ -1,5 +1,15 @@
 use crate::{Address, Byte};
 
+/// The first I/O address user programs may access. The I/O space below it,
+/// which includes every address reachable by the `[port]` forms of `in` and
+/// `out`, is reserved for the kernel.
+pub const USER_IO_BASE: Address = 0x8000;
+
+/// Whether accessing the I/O `address` requires kernel privilege.
+pub const fn is_privileged_io(address: Address) -> bool {
+    address < USER_IO_BASE
+}
+
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
 pub enum Pointer {
     X,
@@ -53,13 +63,29 @@ pub enum RegisterPair {
     Cd,
 }
 
+/// Two-operand ALU operations, `dst = dst op src`.
+///
+/// Every operation sets Z when the 8-bit result is zero and N to bit 7 of the
+/// result. Carry uses the not-borrow convention for subtraction: C set means
+/// no borrow occurred, so a multi-byte subtraction starts with `set.c`.
 #[derive(PartialEq, Clone, Copy, Debug, Hash, Eq)]
 pub enum Alu2Op {
+    /// `dst + src + C`. C is the carry out of bit 7; V is set when both
+    /// operands have the same sign and the result's sign differs.
     Addc,
+    /// `dst - src - !C`. C is set when no borrow occurred, that is when
+    /// `dst >= src + !C` unsigned; V is set when the operands' signs differ
+    /// and the result's sign differs from `dst`.
     Subb,
+    /// Bitwise and. V is cleared and C is unchanged.
     And,
+    /// Bitwise or. V is cleared and C is unchanged.
     Or,
+    /// Bitwise exclusive or. V is cleared and C is unchanged.
     Xor,
+    /// `dst - src`, discarding the result. Flags are set as for `Subb` with
+    /// no borrow in, so C means `dst >= src` unsigned and `N != V` means
+    /// `dst < src` signed.
     Cmp,
 }
 
@@ -69,15 +95,31 @@ pub enum Alu2OpMode {
     Constant(Byte),
 }
 
+/// One-operand ALU operations on `dst`. Z and N are set from the result as
+/// for [`Alu2Op`].
 #[derive(PartialEq, Clone, Copy, Debug, Hash, Eq)]
 pub enum Alu1Op {
+    /// Shift left by one, shifting in zero. C is the bit shifted out of bit 7;
+    /// V is set when the shift changed the sign bit.
     Shl,
+    /// Logical shift right by one, shifting in zero. C is the bit shifted out
+    /// of bit 0; V is cleared.
     Shr,
+    /// Arithmetic shift right by one, preserving the sign bit. C is the bit
+    /// shifted out of bit 0; V is cleared.
     Asr,
+    /// Bitwise complement. V is cleared and C is unchanged.
     Not,
+    /// Two's complement negation, `0 - dst`. C is set when `dst` is zero (no
+    /// borrow); V is set when `dst` is 0x80, which has no positive counterpart.
     Neg,
+    /// `dst + 1`. V is set when `dst` was 0x7F. C is unchanged, so counters
+    /// can be stepped in the middle of multi-byte arithmetic.
     Inc,
+    /// `dst - 1`. V is set when `dst` was 0x80. C is unchanged.
     Dec,
+    /// Sets Z and N from `dst` without modifying it. V is cleared and C is
+    /// unchanged.
     Test,
 }
 
@@ -122,6 +164,8 @@ pub enum Instruction {
     Out(IOMode, Register8),
     ReadStackPointer,
     WriteStackPointer,
+    ReadUserStackPointer,
+    WriteUserStackPointer,
     Move16(Register16, Register16),
     Move16FromPair(Register16, RegisterPair),
     Move16ToPair(RegisterPair, Register16),
@@ -143,3 +187,353 @@ pub enum Instruction {
     Reti,
     Jmp(Condition, JumpMode),
 }
+
+impl Instruction {
+    /// Whether the instruction may only be executed with kernel privilege.
+    /// These are the instructions that change the interrupt or bank state,
+    /// that reach the user's stack pointer, or, in the case of `reti`, could
+    /// restore a kernel status byte. Whether an
+    /// `in` or `out` is privileged depends on its address; see
+    /// [`is_privileged_io`].
+    pub fn is_privileged(&self) -> bool {
+        matches!(
+            self,
+            Instruction::SetInterruptEnable
+                | Instruction::ClearInterruptEnable
+                | Instruction::SetBankEnable
+                | Instruction::ClearBankEnable
+                | Instruction::WriteBankRegister
+                | Instruction::ReadUserStackPointer
+                | Instruction::WriteUserStackPointer
+                | Instruction::Reti
+        )
+    }
+
+    /// The number of bytes the instruction is encoded in: its opcode, the
+    /// extension prefix if the opcode is on the extended page, and any
+    /// operand bytes.
+    pub fn size(&self) -> usize {
+        use Instruction as Inst;
+
+        let memory8 = |mode: Memory8Mode| match mode {
+            Memory8Mode::Absolute(_) => 2,
+            Memory8Mode::ConstantOffset(..) => 1,
+            Memory8Mode::RegisterOffset(..) => 0,
+        };
+        let memory16 = |mode: Memory16Mode| match mode {
+            Memory16Mode::Absolute(_) => 2,
+            Memory16Mode::ConstantOffset(..) => 1,
+        };
+        let io = |mode: IOMode| match mode {
+            IOMode::Port(_) | IOMode::ConstantOffset(..) => 1,
+            IOMode::RegisterOffset(..) => 0,
+        };
+        let jump = |mode: JumpMode| match mode {
+            JumpMode::Absolute(_) => 2,
+            JumpMode::Relative(_) | JumpMode::Indirect(..) => 1,
+        };
+
+        match *self {
+            Inst::Nop
+            | Inst::SetCarry
+            | Inst::ClearCarry
+            | Inst::SetInterruptEnable
+            | Inst::ClearInterruptEnable
+            | Inst::SetBankEnable
+            | Inst::ClearBankEnable
+            | Inst::ReadBankRegister
+            | Inst::WriteBankRegister
+            | Inst::Move8(..)
+            | Inst::ReadStackPointer
+            | Inst::WriteStackPointer
+            | Inst::ReadUserStackPointer
+            | Inst::WriteUserStackPointer
+            | Inst::Move16(..)
+            | Inst::Move16FromPair(..) => 1,
+            Inst::Load8Immediate(..) => 2,
+            Inst::Load8(_, mode) | Inst::Store8(mode, _) => 1 + memory8(mode),
+            Inst::In(_, mode) | Inst::Out(mode, _) => 1 + io(mode),
+
+            Inst::Move16ToPair(..)
+            | Inst::Inc16(_)
+            | Inst::Dec16(_)
+            | Inst::Alu1(..)
+            | Inst::Push8(_)
+            | Inst::Push16(_)
+            | Inst::Pop8(_)
+            | Inst::Pop16(_)
+            | Inst::Ret
+            | Inst::Swi
+            | Inst::Reti => 2,
+            Inst::Load16Immediate(..) => 4,
+            Inst::Load16(_, mode) | Inst::Store16(mode, _) => 2 + memory16(mode),
+            Inst::Lea(_, LeaMode::Register(_)) | Inst::Alu2(_, _, Alu2OpMode::Register(_)) => 2,
+            Inst::Lea(_, LeaMode::Constant(_)) | Inst::Alu2(_, _, Alu2OpMode::Constant(_)) => 3,
+            Inst::Call(mode) | Inst::Jmp(_, mode) => 2 + jump(mode),
+        }
+    }
+
+    /// The clock cycles the instruction takes. Each byte moved over the 8-bit
+    /// bus takes a cycle, starting with the instruction's own bytes, as does
+    /// each internal step such as an ALU operation, forming an address from
+    /// an offset, or updating half of a 16-bit register.
+    pub fn cycles(&self) -> usize {
+        use Instruction as Inst;
+
+        let offset = |mode: Memory8Mode| match mode {
+            Memory8Mode::Absolute(_) => 0,
+            Memory8Mode::ConstantOffset(..) | Memory8Mode::RegisterOffset(..) => 1,
+        };
+        let offset16 = |mode: Memory16Mode| match mode {
+            Memory16Mode::Absolute(_) => 0,
+            Memory16Mode::ConstantOffset(..) => 1,
+        };
+        let target = |mode: JumpMode| match mode {
+            JumpMode::Absolute(_) => 0,
+            JumpMode::Relative(_) | JumpMode::Indirect(..) => 1,
+        };
+
+        let execution = match *self {
+            Inst::Nop
+            | Inst::SetCarry
+            | Inst::ClearCarry
+            | Inst::SetInterruptEnable
+            | Inst::ClearInterruptEnable
+            | Inst::SetBankEnable
+            | Inst::ClearBankEnable
+            | Inst::ReadBankRegister
+            | Inst::WriteBankRegister
+            | Inst::Move8(..)
+            | Inst::Load8Immediate(..)
+            | Inst::Load16Immediate(..)
+            | Inst::Alu2(..)
+            | Inst::Alu1(..) => 1,
+            Inst::Load8(_, mode) | Inst::Store8(mode, _) => 1 + offset(mode),
+            Inst::In(_, mode) | Inst::Out(mode, _) => 1 + io_offset(mode),
+            Inst::ReadStackPointer
+            | Inst::WriteStackPointer
+            | Inst::ReadUserStackPointer
+            | Inst::WriteUserStackPointer
+            | Inst::Move16(..)
+            | Inst::Move16FromPair(..)
+            | Inst::Move16ToPair(..)
+            | Inst::Lea(..)
+            | Inst::Inc16(_)
+            | Inst::Dec16(_) => 2,
+            Inst::Load16(_, mode) | Inst::Store16(mode, _) => 2 + offset16(mode),
+            Inst::Push8(_) | Inst::Pop8(_) => 2,
+            Inst::Push16(_) | Inst::Pop16(_) => 3,
+            Inst::Jmp(_, mode) => 1 + target(mode),
+            Inst::Call(mode) => 3 + target(mode),
+            Inst::Ret => 3,
+            Inst::Swi | Inst::Reti => INTERRUPT_FRAME_CYCLES,
+        };
+
+        self.size() + execution
+    }
+
+    /// The clock cycles taken when the instruction faults instead of
+    /// executing. It is fetched, and an `in` or `out` forms its address to
+    /// check it, before the fault is taken.
+    pub fn fault_cycles(&self) -> usize {
+        let check = match *self {
+            Instruction::In(_, mode) | Instruction::Out(mode, _) => io_offset(mode),
+            _ => 0,
+        };
+
+        self.size() + check + INTERRUPT_FRAME_CYCLES
+    }
+}
+
+/// The cycle taken to add the offset of an `in` or `out` to its base.
+fn io_offset(mode: IOMode) -> usize {
+    match mode {
+        IOMode::Port(_) => 0,
+        IOMode::ConstantOffset(..) | IOMode::RegisterOffset(..) => 1,
+    }
+}
+
+/// The clock cycles taken to enter an interrupt handler, pushing the program
+/// counter and status and loading the vector, or to return from one.
+pub const INTERRUPT_FRAME_CYCLES: usize = 4;
+
+impl std::fmt::Display for Pointer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Pointer::X => "x",
+            Pointer::Y => "y",
+            Pointer::SP => "sp",
+        })
+    }
+}
+
+impl std::fmt::Display for Register8 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Register8::A => "a",
+            Register8::B => "b",
+            Register8::C => "c",
+            Register8::D => "d",
+        })
+    }
+}
+
+impl std::fmt::Display for Register16 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Register16::X => "x",
+            Register16::Y => "y",
+        })
+    }
+}
+
+impl std::fmt::Display for RegisterPair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            RegisterPair::Ab => "ab",
+            RegisterPair::Cd => "cd",
+        })
+    }
+}
+
+impl std::fmt::Display for Memory8Mode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Memory8Mode::Absolute(address) => write!(f, "[{:#06x}]", address),
+            Memory8Mode::ConstantOffset(base, offset) => {
+                write!(f, "[{}, #{}]", base, offset as i8)
+            }
+            Memory8Mode::RegisterOffset(base, offset) => write!(f, "[{}, {}]", base, offset),
+        }
+    }
+}
+
+impl std::fmt::Display for Memory16Mode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Memory16Mode::Absolute(address) => write!(f, "[{:#06x}]", address),
+            Memory16Mode::ConstantOffset(base, offset) => write!(f, "[{}, {}]", base, offset as i8),
+        }
+    }
+}
+
+impl std::fmt::Display for IOMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            IOMode::Port(port) => write!(f, "[{:#04x}]", port),
+            IOMode::ConstantOffset(base, offset) => write!(f, "[{}, {}]", base, offset as i8),
+            IOMode::RegisterOffset(base, offset) => write!(f, "[{}, {}]", base, offset),
+        }
+    }
+}
+
+impl std::fmt::Display for Alu2Op {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Alu2Op::Addc => "addc",
+            Alu2Op::Subb => "subb",
+            Alu2Op::And => "and",
+            Alu2Op::Or => "or",
+            Alu2Op::Xor => "xor",
+            Alu2Op::Cmp => "cmp",
+        })
+    }
+}
+
+impl std::fmt::Display for Alu1Op {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Alu1Op::Shl => "shl",
+            Alu1Op::Shr => "shr",
+            Alu1Op::Asr => "asr",
+            Alu1Op::Not => "not",
+            Alu1Op::Neg => "neg",
+            Alu1Op::Inc => "inc",
+            Alu1Op::Dec => "dec",
+            Alu1Op::Test => "test",
+        })
+    }
+}
+
+/// Formats instructions in the syntax of `asm/bw8.asm`, so that assembling
+/// the text gives back the same encoding. Offsets are written as signed
+/// bytes, which is how the assembler takes them.
+impl std::fmt::Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use Instruction as Inst;
+
+        match *self {
+            Inst::Nop => write!(f, "nop"),
+            Inst::SetCarry => write!(f, "set.c"),
+            Inst::ClearCarry => write!(f, "clr.c"),
+            Inst::SetInterruptEnable => write!(f, "set.i"),
+            Inst::ClearInterruptEnable => write!(f, "clr.i"),
+            Inst::SetBankEnable => write!(f, "set.b"),
+            Inst::ClearBankEnable => write!(f, "clr.b"),
+            Inst::ReadBankRegister => write!(f, "mv a, br"),
+            Inst::WriteBankRegister => write!(f, "mv br, a"),
+            Inst::Move8(dst, src) => write!(f, "mv {}, {}", dst, src),
+            Inst::Load8Immediate(dst, value) => write!(f, "ld {}, #{:#04x}", dst, value),
+            Inst::Load8(dst, mode) => write!(f, "ld {}, {}", dst, mode),
+            Inst::Store8(mode, src) => write!(f, "st {}, {}", mode, src),
+            Inst::In(dst, mode) => write!(f, "in {}, {}", dst, mode),
+            Inst::Out(mode, src) => write!(f, "out {}, {}", mode, src),
+            Inst::ReadStackPointer => write!(f, "mv x, sp"),
+            Inst::WriteStackPointer => write!(f, "mv sp, x"),
+            Inst::ReadUserStackPointer => write!(f, "mv x, usp"),
+            Inst::WriteUserStackPointer => write!(f, "mv usp, x"),
+            Inst::Move16(dst, src) => write!(f, "mv {}, {}", dst, src),
+            Inst::Move16FromPair(dst, src) => write!(f, "mv {}, {}", dst, src),
+            Inst::Move16ToPair(dst, src) => write!(f, "mv {}, {}", dst, src),
+            Inst::Load16Immediate(dst, value) => write!(f, "ld {}, #{:#06x}", dst, value),
+            Inst::Load16(dst, mode) => write!(f, "ld {}, {}", dst, mode),
+            Inst::Store16(mode, src) => write!(f, "st {}, {}", mode, src),
+            Inst::Lea(base, LeaMode::Register(offset)) => write!(f, "lea [{}, {}]", base, offset),
+            Inst::Lea(base, LeaMode::Constant(offset)) => {
+                write!(f, "lea [{}, {}]", base, offset as i8)
+            }
+            Inst::Inc16(dst) => write!(f, "inc {}", dst),
+            Inst::Dec16(dst) => write!(f, "dec {}", dst),
+            Inst::Alu2(op, dst, Alu2OpMode::Register(src)) => write!(f, "{} {}, {}", op, dst, src),
+            Inst::Alu2(op, dst, Alu2OpMode::Constant(value)) => {
+                write!(f, "{} {}, {:#04x}", op, dst, value)
+            }
+            Inst::Alu1(op, dst) => write!(f, "{} {}", op, dst),
+            Inst::Push8(src) => write!(f, "push {}", src),
+            Inst::Push16(src) => write!(f, "push {}", src),
+            Inst::Pop8(dst) => write!(f, "pop {}", dst),
+            Inst::Pop16(dst) => write!(f, "pop {}", dst),
+            Inst::Call(mode) => jump(f, "call", mode),
+            Inst::Ret => write!(f, "ret"),
+            Inst::Swi => write!(f, "swi"),
+            Inst::Reti => write!(f, "reti"),
+            Inst::Jmp(condition, mode) => {
+                let mnemonic = match condition {
+                    Condition::Always => "jmp",
+                    Condition::Equal => "br.eq",
+                    Condition::NotEqual => "br.ne",
+                    Condition::LessThan => "br.lt",
+                    Condition::GreaterThan => "br.gt",
+                    Condition::LessEqual => "br.le",
+                    Condition::GreaterEqual => "br.ge",
+                    Condition::LessThanSigned => "br.lts",
+                    Condition::GreaterThanSigned => "br.gts",
+                    Condition::LessEqualSigned => "br.les",
+                    Condition::GreaterEqualSigned => "br.ges",
+                };
+                jump(f, mnemonic, mode)
+            }
+        }
+    }
+}
+
+/// Formats a control transfer. The absolute form takes the `.abs` suffix,
+/// since the assembler reads a bare target that fits a signed byte as a
+/// relative offset.
+fn jump(f: &mut std::fmt::Formatter<'_>, mnemonic: &str, mode: JumpMode) -> std::fmt::Result {
+    match mode {
+        JumpMode::Relative(offset) => write!(f, "{} {}", mnemonic, offset as i8),
+        JumpMode::Absolute(address) => write!(f, "{}.abs {:#06x}", mnemonic, address),
+        JumpMode::Indirect(base, offset) => write!(f, "{} ({}, {})", mnemonic, base, offset as i8),
+    }
+}
@@ -1,18 +1,23 @@
 mod instruction;
 mod opcode;
 
-use instruction::*;
+pub use instruction::*;
 use opcode::*;
+pub use opcode::{Opcode, OPCODES};
 
 pub type Address = u16;
 pub type Byte = u8;
 pub type Word = u16;
 
+/// The opcode that precedes each extended opcode.
+pub const EXTENSION_PREFIX: Byte = 0x01;
+
 pub enum ExtensionMode {
     Normal,
     Extended,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum InstructionBytes {
     One(Byte),
     Two(Byte, Byte),
@@ -20,10 +25,21 @@ pub enum InstructionBytes {
     Four(Byte, Byte, Byte, Byte),
 }
 
+impl InstructionBytes {
+    pub fn to_vec(&self) -> Vec<Byte> {
+        match *self {
+            Self::One(a) => vec![a],
+            Self::Two(a, b) => vec![a, b],
+            Self::Three(a, b, c) => vec![a, b, c],
+            Self::Four(a, b, c, d) => vec![a, b, c, d],
+        }
+    }
+}
+
 pub fn fetch_word(stream: &mut impl Iterator<Item = Byte>) -> Option<Word> {
     let low = stream.next()?;
     let high = stream.next()?;
-    Some(((high as u16) << 8) | ((low as u16) << 0))    
+    Some(((high as u16) << 8) | (low as u16))
 }
 
 pub fn decode(
@@ -317,13 +333,13 @@ pub fn decode(
             MV_X_SP => Inst::ReadStackPointer,
             MV_SP_X => Inst::WriteStackPointer,
 
-            MV_X_X => Inst::Move16(Register16::X, Register16::X),
+            MV_X_USP => Inst::ReadUserStackPointer,
             MV_X_Y => Inst::Move16(Register16::X, Register16::Y),
             MV_X_AB => Inst::Move16FromPair(Register16::X, RegisterPair::Ab),
             MV_X_CD => Inst::Move16FromPair(Register16::X, RegisterPair::Cd),
 
             MV_Y_X => Inst::Move16(Register16::Y, Register16::X),
-            MV_Y_Y => Inst::Move16(Register16::Y, Register16::Y),
+            MV_USP_X => Inst::WriteUserStackPointer,
             MV_Y_AB => Inst::Move16FromPair(Register16::Y, RegisterPair::Ab),
             MV_Y_CD => Inst::Move16FromPair(Register16::Y, RegisterPair::Cd),
         },
@@ -776,14 +792,15 @@ pub fn decode(
 
 pub fn encode(inst: Instruction) -> InstructionBytes {
     use Instruction as Inst;
+    use Memory16Mode as Mem16;
     use Memory8Mode as Mem8;
+    use Pointer::SP;
+    use Pointer::X;
+    use Pointer::Y;
     use Register8::A;
     use Register8::B;
     use Register8::C;
     use Register8::D;
-    use Pointer::X;
-    use Pointer::Y;
-    use Pointer::SP;
 
     match inst {
         Inst::Nop => opcode::NOP.encode(),
@@ -846,7 +863,7 @@ pub fn encode(inst: Instruction) -> InstructionBytes {
                     X => opcode::LD_D_REL_X_BY_IMM.encode_with_byte(offset),
                     Y => opcode::LD_D_REL_Y_BY_IMM.encode_with_byte(offset),
                     SP => opcode::LD_D_REL_SP_BY_IMM.encode_with_byte(offset),
-                }
+                },
             },
             Mem8::RegisterOffset(ptr, reg) => match dst {
                 A => match ptr {
@@ -929,13 +946,786 @@ pub fn encode(inst: Instruction) -> InstructionBytes {
                         D => opcode::LD_D_REL_SP_BY_D.encode(),
                     },
                 },
+            },
+        },
+        Inst::Store8(mode, src) => match mode {
+            Mem8::Absolute(word) => match src {
+                A => opcode::ST_ABS_A.encode_with_word(word),
+                B => opcode::ST_ABS_B.encode_with_word(word),
+                C => opcode::ST_ABS_C.encode_with_word(word),
+                D => opcode::ST_ABS_D.encode_with_word(word),
+            },
+            Mem8::ConstantOffset(ptr, offset) => match src {
+                A => match ptr {
+                    X => opcode::ST_REL_X_BY_IMM_A.encode_with_byte(offset),
+                    Y => opcode::ST_REL_Y_BY_IMM_A.encode_with_byte(offset),
+                    SP => opcode::ST_REL_SP_BY_IMM_A.encode_with_byte(offset),
+                },
+                B => match ptr {
+                    X => opcode::ST_REL_X_BY_IMM_B.encode_with_byte(offset),
+                    Y => opcode::ST_REL_Y_BY_IMM_B.encode_with_byte(offset),
+                    SP => opcode::ST_REL_SP_BY_IMM_B.encode_with_byte(offset),
+                },
+                C => match ptr {
+                    X => opcode::ST_REL_X_BY_IMM_C.encode_with_byte(offset),
+                    Y => opcode::ST_REL_Y_BY_IMM_C.encode_with_byte(offset),
+                    SP => opcode::ST_REL_SP_BY_IMM_C.encode_with_byte(offset),
+                },
+                D => match ptr {
+                    X => opcode::ST_REL_X_BY_IMM_D.encode_with_byte(offset),
+                    Y => opcode::ST_REL_Y_BY_IMM_D.encode_with_byte(offset),
+                    SP => opcode::ST_REL_SP_BY_IMM_D.encode_with_byte(offset),
+                },
+            },
+            Mem8::RegisterOffset(ptr, reg) => match src {
+                A => match ptr {
+                    X => match reg {
+                        A => opcode::ST_REL_X_BY_A_A.encode(),
+                        B => opcode::ST_REL_X_BY_B_A.encode(),
+                        C => opcode::ST_REL_X_BY_C_A.encode(),
+                        D => opcode::ST_REL_X_BY_D_A.encode(),
+                    },
+                    Y => match reg {
+                        A => opcode::ST_REL_Y_BY_A_A.encode(),
+                        B => opcode::ST_REL_Y_BY_B_A.encode(),
+                        C => opcode::ST_REL_Y_BY_C_A.encode(),
+                        D => opcode::ST_REL_Y_BY_D_A.encode(),
+                    },
+                    SP => match reg {
+                        A => opcode::ST_REL_SP_BY_A_A.encode(),
+                        B => opcode::ST_REL_SP_BY_B_A.encode(),
+                        C => opcode::ST_REL_SP_BY_C_A.encode(),
+                        D => opcode::ST_REL_SP_BY_D_A.encode(),
+                    },
+                },
+                B => match ptr {
+                    X => match reg {
+                        A => opcode::ST_REL_X_BY_A_B.encode(),
+                        B => opcode::ST_REL_X_BY_B_B.encode(),
+                        C => opcode::ST_REL_X_BY_C_B.encode(),
+                        D => opcode::ST_REL_X_BY_D_B.encode(),
+                    },
+                    Y => match reg {
+                        A => opcode::ST_REL_Y_BY_A_B.encode(),
+                        B => opcode::ST_REL_Y_BY_B_B.encode(),
+                        C => opcode::ST_REL_Y_BY_C_B.encode(),
+                        D => opcode::ST_REL_Y_BY_D_B.encode(),
+                    },
+                    SP => match reg {
+                        A => opcode::ST_REL_SP_BY_A_B.encode(),
+                        B => opcode::ST_REL_SP_BY_B_B.encode(),
+                        C => opcode::ST_REL_SP_BY_C_B.encode(),
+                        D => opcode::ST_REL_SP_BY_D_B.encode(),
+                    },
+                },
+                C => match ptr {
+                    X => match reg {
+                        A => opcode::ST_REL_X_BY_A_C.encode(),
+                        B => opcode::ST_REL_X_BY_B_C.encode(),
+                        C => opcode::ST_REL_X_BY_C_C.encode(),
+                        D => opcode::ST_REL_X_BY_D_C.encode(),
+                    },
+                    Y => match reg {
+                        A => opcode::ST_REL_Y_BY_A_C.encode(),
+                        B => opcode::ST_REL_Y_BY_B_C.encode(),
+                        C => opcode::ST_REL_Y_BY_C_C.encode(),
+                        D => opcode::ST_REL_Y_BY_D_C.encode(),
+                    },
+                    SP => match reg {
+                        A => opcode::ST_REL_SP_BY_A_C.encode(),
+                        B => opcode::ST_REL_SP_BY_B_C.encode(),
+                        C => opcode::ST_REL_SP_BY_C_C.encode(),
+                        D => opcode::ST_REL_SP_BY_D_C.encode(),
+                    },
+                },
+                D => match ptr {
+                    X => match reg {
+                        A => opcode::ST_REL_X_BY_A_D.encode(),
+                        B => opcode::ST_REL_X_BY_B_D.encode(),
+                        C => opcode::ST_REL_X_BY_C_D.encode(),
+                        D => opcode::ST_REL_X_BY_D_D.encode(),
+                    },
+                    Y => match reg {
+                        A => opcode::ST_REL_Y_BY_A_D.encode(),
+                        B => opcode::ST_REL_Y_BY_B_D.encode(),
+                        C => opcode::ST_REL_Y_BY_C_D.encode(),
+                        D => opcode::ST_REL_Y_BY_D_D.encode(),
+                    },
+                    SP => match reg {
+                        A => opcode::ST_REL_SP_BY_A_D.encode(),
+                        B => opcode::ST_REL_SP_BY_B_D.encode(),
+                        C => opcode::ST_REL_SP_BY_C_D.encode(),
+                        D => opcode::ST_REL_SP_BY_D_D.encode(),
+                    },
+                },
+            },
+        },
+        Inst::In(dst, mode) => match mode {
+            IOMode::Port(port) => match dst {
+                A => opcode::IN_A_PORT.encode_with_byte(port),
+                B => opcode::IN_B_PORT.encode_with_byte(port),
+                C => opcode::IN_C_PORT.encode_with_byte(port),
+                D => opcode::IN_D_PORT.encode_with_byte(port),
+            },
+            IOMode::ConstantOffset(base, offset) => match dst {
+                A => match base {
+                    Register16::X => opcode::IN_A_REL_X_BY_IMM.encode_with_byte(offset),
+                    Register16::Y => opcode::IN_A_REL_Y_BY_IMM.encode_with_byte(offset),
+                },
+                B => match base {
+                    Register16::X => opcode::IN_B_REL_X_BY_IMM.encode_with_byte(offset),
+                    Register16::Y => opcode::IN_B_REL_Y_BY_IMM.encode_with_byte(offset),
+                },
+                C => match base {
+                    Register16::X => opcode::IN_C_REL_X_BY_IMM.encode_with_byte(offset),
+                    Register16::Y => opcode::IN_C_REL_Y_BY_IMM.encode_with_byte(offset),
+                },
+                D => match base {
+                    Register16::X => opcode::IN_D_REL_X_BY_IMM.encode_with_byte(offset),
+                    Register16::Y => opcode::IN_D_REL_Y_BY_IMM.encode_with_byte(offset),
+                },
+            },
+            IOMode::RegisterOffset(base, reg) => match dst {
+                A => match base {
+                    Register16::X => match reg {
+                        A => opcode::IN_A_REL_X_BY_A.encode(),
+                        B => opcode::IN_A_REL_X_BY_B.encode(),
+                        C => opcode::IN_A_REL_X_BY_C.encode(),
+                        D => opcode::IN_A_REL_X_BY_D.encode(),
+                    },
+                    Register16::Y => match reg {
+                        A => opcode::IN_A_REL_Y_BY_A.encode(),
+                        B => opcode::IN_A_REL_Y_BY_B.encode(),
+                        C => opcode::IN_A_REL_Y_BY_C.encode(),
+                        D => opcode::IN_A_REL_Y_BY_D.encode(),
+                    },
+                },
+                B => match base {
+                    Register16::X => match reg {
+                        A => opcode::IN_B_REL_X_BY_A.encode(),
+                        B => opcode::IN_B_REL_X_BY_B.encode(),
+                        C => opcode::IN_B_REL_X_BY_C.encode(),
+                        D => opcode::IN_B_REL_X_BY_D.encode(),
+                    },
+                    Register16::Y => match reg {
+                        A => opcode::IN_B_REL_Y_BY_A.encode(),
+                        B => opcode::IN_B_REL_Y_BY_B.encode(),
+                        C => opcode::IN_B_REL_Y_BY_C.encode(),
+                        D => opcode::IN_B_REL_Y_BY_D.encode(),
+                    },
+                },
+                C => match base {
+                    Register16::X => match reg {
+                        A => opcode::IN_C_REL_X_BY_A.encode(),
+                        B => opcode::IN_C_REL_X_BY_B.encode(),
+                        C => opcode::IN_C_REL_X_BY_C.encode(),
+                        D => opcode::IN_C_REL_X_BY_D.encode(),
+                    },
+                    Register16::Y => match reg {
+                        A => opcode::IN_C_REL_Y_BY_A.encode(),
+                        B => opcode::IN_C_REL_Y_BY_B.encode(),
+                        C => opcode::IN_C_REL_Y_BY_C.encode(),
+                        D => opcode::IN_C_REL_Y_BY_D.encode(),
+                    },
+                },
+                D => match base {
+                    Register16::X => match reg {
+                        A => opcode::IN_D_REL_X_BY_A.encode(),
+                        B => opcode::IN_D_REL_X_BY_B.encode(),
+                        C => opcode::IN_D_REL_X_BY_C.encode(),
+                        D => opcode::IN_D_REL_X_BY_D.encode(),
+                    },
+                    Register16::Y => match reg {
+                        A => opcode::IN_D_REL_Y_BY_A.encode(),
+                        B => opcode::IN_D_REL_Y_BY_B.encode(),
+                        C => opcode::IN_D_REL_Y_BY_C.encode(),
+                        D => opcode::IN_D_REL_Y_BY_D.encode(),
+                    },
+                },
+            },
+        },
+        Inst::Out(mode, src) => match mode {
+            IOMode::Port(port) => match src {
+                A => opcode::OUT_PORT_A.encode_with_byte(port),
+                B => opcode::OUT_PORT_B.encode_with_byte(port),
+                C => opcode::OUT_PORT_C.encode_with_byte(port),
+                D => opcode::OUT_PORT_D.encode_with_byte(port),
+            },
+            IOMode::ConstantOffset(base, offset) => match src {
+                A => match base {
+                    Register16::X => opcode::OUT_REL_X_BY_IMM_A.encode_with_byte(offset),
+                    Register16::Y => opcode::OUT_REL_Y_BY_IMM_A.encode_with_byte(offset),
+                },
+                B => match base {
+                    Register16::X => opcode::OUT_REL_X_BY_IMM_B.encode_with_byte(offset),
+                    Register16::Y => opcode::OUT_REL_Y_BY_IMM_B.encode_with_byte(offset),
+                },
+                C => match base {
+                    Register16::X => opcode::OUT_REL_X_BY_IMM_C.encode_with_byte(offset),
+                    Register16::Y => opcode::OUT_REL_Y_BY_IMM_C.encode_with_byte(offset),
+                },
+                D => match base {
+                    Register16::X => opcode::OUT_REL_X_BY_IMM_D.encode_with_byte(offset),
+                    Register16::Y => opcode::OUT_REL_Y_BY_IMM_D.encode_with_byte(offset),
+                },
+            },
+            IOMode::RegisterOffset(base, reg) => match src {
+                A => match base {
+                    Register16::X => match reg {
+                        A => opcode::OUT_REL_X_BY_A_A.encode(),
+                        B => opcode::OUT_REL_X_BY_B_A.encode(),
+                        C => opcode::OUT_REL_X_BY_C_A.encode(),
+                        D => opcode::OUT_REL_X_BY_D_A.encode(),
+                    },
+                    Register16::Y => match reg {
+                        A => opcode::OUT_REL_Y_BY_A_A.encode(),
+                        B => opcode::OUT_REL_Y_BY_B_A.encode(),
+                        C => opcode::OUT_REL_Y_BY_C_A.encode(),
+                        D => opcode::OUT_REL_Y_BY_D_A.encode(),
+                    },
+                },
+                B => match base {
+                    Register16::X => match reg {
+                        A => opcode::OUT_REL_X_BY_A_B.encode(),
+                        B => opcode::OUT_REL_X_BY_B_B.encode(),
+                        C => opcode::OUT_REL_X_BY_C_B.encode(),
+                        D => opcode::OUT_REL_X_BY_D_B.encode(),
+                    },
+                    Register16::Y => match reg {
+                        A => opcode::OUT_REL_Y_BY_A_B.encode(),
+                        B => opcode::OUT_REL_Y_BY_B_B.encode(),
+                        C => opcode::OUT_REL_Y_BY_C_B.encode(),
+                        D => opcode::OUT_REL_Y_BY_D_B.encode(),
+                    },
+                },
+                C => match base {
+                    Register16::X => match reg {
+                        A => opcode::OUT_REL_X_BY_A_C.encode(),
+                        B => opcode::OUT_REL_X_BY_B_C.encode(),
+                        C => opcode::OUT_REL_X_BY_C_C.encode(),
+                        D => opcode::OUT_REL_X_BY_D_C.encode(),
+                    },
+                    Register16::Y => match reg {
+                        A => opcode::OUT_REL_Y_BY_A_C.encode(),
+                        B => opcode::OUT_REL_Y_BY_B_C.encode(),
+                        C => opcode::OUT_REL_Y_BY_C_C.encode(),
+                        D => opcode::OUT_REL_Y_BY_D_C.encode(),
+                    },
+                },
+                D => match base {
+                    Register16::X => match reg {
+                        A => opcode::OUT_REL_X_BY_A_D.encode(),
+                        B => opcode::OUT_REL_X_BY_B_D.encode(),
+                        C => opcode::OUT_REL_X_BY_C_D.encode(),
+                        D => opcode::OUT_REL_X_BY_D_D.encode(),
+                    },
+                    Register16::Y => match reg {
+                        A => opcode::OUT_REL_Y_BY_A_D.encode(),
+                        B => opcode::OUT_REL_Y_BY_B_D.encode(),
+                        C => opcode::OUT_REL_Y_BY_C_D.encode(),
+                        D => opcode::OUT_REL_Y_BY_D_D.encode(),
+                    },
+                },
+            },
+        },
+        Inst::ReadStackPointer => opcode::MV_X_SP.encode(),
+        Inst::WriteStackPointer => opcode::MV_SP_X.encode(),
+        Inst::ReadUserStackPointer => opcode::MV_X_USP.encode(),
+        Inst::WriteUserStackPointer => opcode::MV_USP_X.encode(),
+        Inst::Move16(dst, src) => match (dst, src) {
+            // Moving a pointer to itself does nothing, and its opcodes went to
+            // `mv x, usp` and `mv usp, x`.
+            (Register16::X, Register16::X) | (Register16::Y, Register16::Y) => opcode::NOP.encode(),
+            (Register16::X, Register16::Y) => opcode::MV_X_Y.encode(),
+            (Register16::Y, Register16::X) => opcode::MV_Y_X.encode(),
+        },
+        Inst::Move16FromPair(dst, src) => match (dst, src) {
+            (Register16::X, RegisterPair::Ab) => opcode::MV_X_AB.encode(),
+            (Register16::X, RegisterPair::Cd) => opcode::MV_X_CD.encode(),
+            (Register16::Y, RegisterPair::Ab) => opcode::MV_Y_AB.encode(),
+            (Register16::Y, RegisterPair::Cd) => opcode::MV_Y_CD.encode(),
+        },
+        Inst::Move16ToPair(dst, src) => match (dst, src) {
+            (RegisterPair::Ab, Register16::X) => opcode::MV_AB_X.encode(),
+            (RegisterPair::Ab, Register16::Y) => opcode::MV_AB_Y.encode(),
+            (RegisterPair::Cd, Register16::X) => opcode::MV_CD_X.encode(),
+            (RegisterPair::Cd, Register16::Y) => opcode::MV_CD_Y.encode(),
+        },
+        Inst::Load16Immediate(dst, word) => match dst {
+            Register16::X => opcode::LD_X_IMM.encode_with_word(word),
+            Register16::Y => opcode::LD_Y_IMM.encode_with_word(word),
+        },
+        Inst::Load16(dst, mode) => match mode {
+            Mem16::Absolute(word) => match dst {
+                Register16::X => opcode::LD_X_ABS.encode_with_word(word),
+                Register16::Y => opcode::LD_Y_ABS.encode_with_word(word),
+            },
+            Mem16::ConstantOffset(ptr, offset) => match dst {
+                Register16::X => match ptr {
+                    X => opcode::LD_X_REL_X_BY_IMM.encode_with_byte(offset),
+                    Y => opcode::LD_X_REL_Y_BY_IMM.encode_with_byte(offset),
+                    SP => opcode::LD_X_REL_SP_BY_IMM.encode_with_byte(offset),
+                },
+                Register16::Y => match ptr {
+                    X => opcode::LD_Y_REL_X_BY_IMM.encode_with_byte(offset),
+                    Y => opcode::LD_Y_REL_Y_BY_IMM.encode_with_byte(offset),
+                    SP => opcode::LD_Y_REL_SP_BY_IMM.encode_with_byte(offset),
+                },
+            },
+        },
+        Inst::Store16(mode, src) => match mode {
+            Mem16::Absolute(word) => match src {
+                Register16::X => opcode::ST_ABS_X.encode_with_word(word),
+                Register16::Y => opcode::ST_ABS_Y.encode_with_word(word),
+            },
+            Mem16::ConstantOffset(ptr, offset) => match src {
+                Register16::X => match ptr {
+                    X => opcode::ST_REL_X_BY_IMM_X.encode_with_byte(offset),
+                    Y => opcode::ST_REL_Y_BY_IMM_X.encode_with_byte(offset),
+                    SP => opcode::ST_REL_SP_BY_IMM_X.encode_with_byte(offset),
+                },
+                Register16::Y => match ptr {
+                    X => opcode::ST_REL_X_BY_IMM_Y.encode_with_byte(offset),
+                    Y => opcode::ST_REL_Y_BY_IMM_Y.encode_with_byte(offset),
+                    SP => opcode::ST_REL_SP_BY_IMM_Y.encode_with_byte(offset),
+                },
+            },
+        },
+        Inst::Lea(ptr, mode) => match mode {
+            LeaMode::Register(reg) => match ptr {
+                X => match reg {
+                    A => opcode::LEA_X_BY_A.encode(),
+                    B => opcode::LEA_X_BY_B.encode(),
+                    C => opcode::LEA_X_BY_C.encode(),
+                    D => opcode::LEA_X_BY_D.encode(),
+                },
+                Y => match reg {
+                    A => opcode::LEA_Y_BY_A.encode(),
+                    B => opcode::LEA_Y_BY_B.encode(),
+                    C => opcode::LEA_Y_BY_C.encode(),
+                    D => opcode::LEA_Y_BY_D.encode(),
+                },
+                SP => match reg {
+                    A => opcode::LEA_SP_BY_A.encode(),
+                    B => opcode::LEA_SP_BY_B.encode(),
+                    C => opcode::LEA_SP_BY_C.encode(),
+                    D => opcode::LEA_SP_BY_D.encode(),
+                },
+            },
+            LeaMode::Constant(offset) => match ptr {
+                X => opcode::LEA_X_BY_IMM.encode_with_byte(offset),
+                Y => opcode::LEA_Y_BY_IMM.encode_with_byte(offset),
+                SP => opcode::LEA_SP_BY_IMM.encode_with_byte(offset),
+            },
+        },
+        Inst::Inc16(dst) => match dst {
+            Register16::X => opcode::INC_X.encode(),
+            Register16::Y => opcode::INC_Y.encode(),
+        },
+        Inst::Dec16(dst) => match dst {
+            Register16::X => opcode::DEC_X.encode(),
+            Register16::Y => opcode::DEC_Y.encode(),
+        },
+        Inst::Alu2(op, dst, mode) => match op {
+            Alu2Op::Addc => match mode {
+                Alu2OpMode::Register(src) => match dst {
+                    A => match src {
+                        A => opcode::ADDC_A_A.encode(),
+                        B => opcode::ADDC_A_B.encode(),
+                        C => opcode::ADDC_A_C.encode(),
+                        D => opcode::ADDC_A_D.encode(),
+                    },
+                    B => match src {
+                        A => opcode::ADDC_B_A.encode(),
+                        B => opcode::ADDC_B_B.encode(),
+                        C => opcode::ADDC_B_C.encode(),
+                        D => opcode::ADDC_B_D.encode(),
+                    },
+                    C => match src {
+                        A => opcode::ADDC_C_A.encode(),
+                        B => opcode::ADDC_C_B.encode(),
+                        C => opcode::ADDC_C_C.encode(),
+                        D => opcode::ADDC_C_D.encode(),
+                    },
+                    D => match src {
+                        A => opcode::ADDC_D_A.encode(),
+                        B => opcode::ADDC_D_B.encode(),
+                        C => opcode::ADDC_D_C.encode(),
+                        D => opcode::ADDC_D_D.encode(),
+                    },
+                },
+                Alu2OpMode::Constant(byte) => match dst {
+                    A => opcode::ADDC_A_IMM.encode_with_byte(byte),
+                    B => opcode::ADDC_B_IMM.encode_with_byte(byte),
+                    C => opcode::ADDC_C_IMM.encode_with_byte(byte),
+                    D => opcode::ADDC_D_IMM.encode_with_byte(byte),
+                },
+            },
+            Alu2Op::Subb => match mode {
+                Alu2OpMode::Register(src) => match dst {
+                    A => match src {
+                        A => opcode::SUBB_A_A.encode(),
+                        B => opcode::SUBB_A_B.encode(),
+                        C => opcode::SUBB_A_C.encode(),
+                        D => opcode::SUBB_A_D.encode(),
+                    },
+                    B => match src {
+                        A => opcode::SUBB_B_A.encode(),
+                        B => opcode::SUBB_B_B.encode(),
+                        C => opcode::SUBB_B_C.encode(),
+                        D => opcode::SUBB_B_D.encode(),
+                    },
+                    C => match src {
+                        A => opcode::SUBB_C_A.encode(),
+                        B => opcode::SUBB_C_B.encode(),
+                        C => opcode::SUBB_C_C.encode(),
+                        D => opcode::SUBB_C_D.encode(),
+                    },
+                    D => match src {
+                        A => opcode::SUBB_D_A.encode(),
+                        B => opcode::SUBB_D_B.encode(),
+                        C => opcode::SUBB_D_C.encode(),
+                        D => opcode::SUBB_D_D.encode(),
+                    },
+                },
+                Alu2OpMode::Constant(byte) => match dst {
+                    A => opcode::SUBB_A_IMM.encode_with_byte(byte),
+                    B => opcode::SUBB_B_IMM.encode_with_byte(byte),
+                    C => opcode::SUBB_C_IMM.encode_with_byte(byte),
+                    D => opcode::SUBB_D_IMM.encode_with_byte(byte),
+                },
+            },
+            Alu2Op::And => match mode {
+                Alu2OpMode::Register(src) => match dst {
+                    A => match src {
+                        A => opcode::AND_A_A.encode(),
+                        B => opcode::AND_A_B.encode(),
+                        C => opcode::AND_A_C.encode(),
+                        D => opcode::AND_A_D.encode(),
+                    },
+                    B => match src {
+                        A => opcode::AND_B_A.encode(),
+                        B => opcode::AND_B_B.encode(),
+                        C => opcode::AND_B_C.encode(),
+                        D => opcode::AND_B_D.encode(),
+                    },
+                    C => match src {
+                        A => opcode::AND_C_A.encode(),
+                        B => opcode::AND_C_B.encode(),
+                        C => opcode::AND_C_C.encode(),
+                        D => opcode::AND_C_D.encode(),
+                    },
+                    D => match src {
+                        A => opcode::AND_D_A.encode(),
+                        B => opcode::AND_D_B.encode(),
+                        C => opcode::AND_D_C.encode(),
+                        D => opcode::AND_D_D.encode(),
+                    },
+                },
+                Alu2OpMode::Constant(byte) => match dst {
+                    A => opcode::AND_A_IMM.encode_with_byte(byte),
+                    B => opcode::AND_B_IMM.encode_with_byte(byte),
+                    C => opcode::AND_C_IMM.encode_with_byte(byte),
+                    D => opcode::AND_D_IMM.encode_with_byte(byte),
+                },
+            },
+            Alu2Op::Or => match mode {
+                Alu2OpMode::Register(src) => match dst {
+                    A => match src {
+                        A => opcode::OR_A_A.encode(),
+                        B => opcode::OR_A_B.encode(),
+                        C => opcode::OR_A_C.encode(),
+                        D => opcode::OR_A_D.encode(),
+                    },
+                    B => match src {
+                        A => opcode::OR_B_A.encode(),
+                        B => opcode::OR_B_B.encode(),
+                        C => opcode::OR_B_C.encode(),
+                        D => opcode::OR_B_D.encode(),
+                    },
+                    C => match src {
+                        A => opcode::OR_C_A.encode(),
+                        B => opcode::OR_C_B.encode(),
+                        C => opcode::OR_C_C.encode(),
+                        D => opcode::OR_C_D.encode(),
+                    },
+                    D => match src {
+                        A => opcode::OR_D_A.encode(),
+                        B => opcode::OR_D_B.encode(),
+                        C => opcode::OR_D_C.encode(),
+                        D => opcode::OR_D_D.encode(),
+                    },
+                },
+                Alu2OpMode::Constant(byte) => match dst {
+                    A => opcode::OR_A_IMM.encode_with_byte(byte),
+                    B => opcode::OR_B_IMM.encode_with_byte(byte),
+                    C => opcode::OR_C_IMM.encode_with_byte(byte),
+                    D => opcode::OR_D_IMM.encode_with_byte(byte),
+                },
+            },
+            Alu2Op::Xor => match mode {
+                Alu2OpMode::Register(src) => match dst {
+                    A => match src {
+                        A => opcode::XOR_A_A.encode(),
+                        B => opcode::XOR_A_B.encode(),
+                        C => opcode::XOR_A_C.encode(),
+                        D => opcode::XOR_A_D.encode(),
+                    },
+                    B => match src {
+                        A => opcode::XOR_B_A.encode(),
+                        B => opcode::XOR_B_B.encode(),
+                        C => opcode::XOR_B_C.encode(),
+                        D => opcode::XOR_B_D.encode(),
+                    },
+                    C => match src {
+                        A => opcode::XOR_C_A.encode(),
+                        B => opcode::XOR_C_B.encode(),
+                        C => opcode::XOR_C_C.encode(),
+                        D => opcode::XOR_C_D.encode(),
+                    },
+                    D => match src {
+                        A => opcode::XOR_D_A.encode(),
+                        B => opcode::XOR_D_B.encode(),
+                        C => opcode::XOR_D_C.encode(),
+                        D => opcode::XOR_D_D.encode(),
+                    },
+                },
+                Alu2OpMode::Constant(byte) => match dst {
+                    A => opcode::XOR_A_IMM.encode_with_byte(byte),
+                    B => opcode::XOR_B_IMM.encode_with_byte(byte),
+                    C => opcode::XOR_C_IMM.encode_with_byte(byte),
+                    D => opcode::XOR_D_IMM.encode_with_byte(byte),
+                },
+            },
+            Alu2Op::Cmp => match mode {
+                Alu2OpMode::Register(src) => match dst {
+                    A => match src {
+                        A => opcode::CMP_A_A.encode(),
+                        B => opcode::CMP_A_B.encode(),
+                        C => opcode::CMP_A_C.encode(),
+                        D => opcode::CMP_A_D.encode(),
+                    },
+                    B => match src {
+                        A => opcode::CMP_B_A.encode(),
+                        B => opcode::CMP_B_B.encode(),
+                        C => opcode::CMP_B_C.encode(),
+                        D => opcode::CMP_B_D.encode(),
+                    },
+                    C => match src {
+                        A => opcode::CMP_C_A.encode(),
+                        B => opcode::CMP_C_B.encode(),
+                        C => opcode::CMP_C_C.encode(),
+                        D => opcode::CMP_C_D.encode(),
+                    },
+                    D => match src {
+                        A => opcode::CMP_D_A.encode(),
+                        B => opcode::CMP_D_B.encode(),
+                        C => opcode::CMP_D_C.encode(),
+                        D => opcode::CMP_D_D.encode(),
+                    },
+                },
+                Alu2OpMode::Constant(byte) => match dst {
+                    A => opcode::CMP_A_IMM.encode_with_byte(byte),
+                    B => opcode::CMP_B_IMM.encode_with_byte(byte),
+                    C => opcode::CMP_C_IMM.encode_with_byte(byte),
+                    D => opcode::CMP_D_IMM.encode_with_byte(byte),
+                },
+            },
+        },
+        Inst::Alu1(op, dst) => match op {
+            Alu1Op::Shl => match dst {
+                A => opcode::SHL_A.encode(),
+                B => opcode::SHL_B.encode(),
+                C => opcode::SHL_C.encode(),
+                D => opcode::SHL_D.encode(),
+            },
+            Alu1Op::Shr => match dst {
+                A => opcode::SHR_A.encode(),
+                B => opcode::SHR_B.encode(),
+                C => opcode::SHR_C.encode(),
+                D => opcode::SHR_D.encode(),
+            },
+            Alu1Op::Asr => match dst {
+                A => opcode::ASR_A.encode(),
+                B => opcode::ASR_B.encode(),
+                C => opcode::ASR_C.encode(),
+                D => opcode::ASR_D.encode(),
+            },
+            Alu1Op::Not => match dst {
+                A => opcode::NOT_A.encode(),
+                B => opcode::NOT_B.encode(),
+                C => opcode::NOT_C.encode(),
+                D => opcode::NOT_D.encode(),
+            },
+            Alu1Op::Neg => match dst {
+                A => opcode::NEG_A.encode(),
+                B => opcode::NEG_B.encode(),
+                C => opcode::NEG_C.encode(),
+                D => opcode::NEG_D.encode(),
+            },
+            Alu1Op::Inc => match dst {
+                A => opcode::INC_A.encode(),
+                B => opcode::INC_B.encode(),
+                C => opcode::INC_C.encode(),
+                D => opcode::INC_D.encode(),
+            },
+            Alu1Op::Dec => match dst {
+                A => opcode::DEC_A.encode(),
+                B => opcode::DEC_B.encode(),
+                C => opcode::DEC_C.encode(),
+                D => opcode::DEC_D.encode(),
+            },
+            Alu1Op::Test => match dst {
+                A => opcode::TEST_A.encode(),
+                B => opcode::TEST_B.encode(),
+                C => opcode::TEST_C.encode(),
+                D => opcode::TEST_D.encode(),
+            },
+        },
+        Inst::Push8(src) => match src {
+            A => opcode::PUSH_A.encode(),
+            B => opcode::PUSH_B.encode(),
+            C => opcode::PUSH_C.encode(),
+            D => opcode::PUSH_D.encode(),
+        },
+        Inst::Push16(src) => match src {
+            Register16::X => opcode::PUSH_X.encode(),
+            Register16::Y => opcode::PUSH_Y.encode(),
+        },
+        Inst::Pop8(dst) => match dst {
+            A => opcode::POP_A.encode(),
+            B => opcode::POP_B.encode(),
+            C => opcode::POP_C.encode(),
+            D => opcode::POP_D.encode(),
+        },
+        Inst::Pop16(dst) => match dst {
+            Register16::X => opcode::POP_X.encode(),
+            Register16::Y => opcode::POP_Y.encode(),
+        },
+        Inst::Call(mode) => match mode {
+            JumpMode::Relative(offset) => opcode::CALL_PC_REL.encode_with_byte(offset),
+            JumpMode::Absolute(address) => opcode::CALL_ABS.encode_with_word(address),
+            JumpMode::Indirect(Register16::X, offset) => {
+                opcode::CALL_X_REL_IMM.encode_with_byte(offset)
             }
-        }
-        _ => todo!(),
-        // Inst::Store8(mode, src) => match mode {
-        //     Mem8::Absolute() => {},
-        //     Mem8::ConstantOffset(, ) => {},
-        //     Mem8::RegisterOffset(, ) => {}
-        // }
+            JumpMode::Indirect(Register16::Y, offset) => {
+                opcode::CALL_Y_REL_IMM.encode_with_byte(offset)
+            }
+        },
+        Inst::Ret => opcode::RET.encode(),
+        Inst::Swi => opcode::SWI.encode(),
+        Inst::Reti => opcode::RETI.encode(),
+        Inst::Jmp(condition, mode) => match condition {
+            Condition::Always => match mode {
+                JumpMode::Relative(offset) => opcode::JMP_PC_REL.encode_with_byte(offset),
+                JumpMode::Absolute(address) => opcode::JMP_ABS.encode_with_word(address),
+                JumpMode::Indirect(Register16::X, offset) => {
+                    opcode::JMP_X_REL_IMM.encode_with_byte(offset)
+                }
+                JumpMode::Indirect(Register16::Y, offset) => {
+                    opcode::JMP_Y_REL_IMM.encode_with_byte(offset)
+                }
+            },
+            Condition::Equal => match mode {
+                JumpMode::Relative(offset) => opcode::BR_EQ_PC_REL.encode_with_byte(offset),
+                JumpMode::Absolute(address) => opcode::BR_EQ_ABS.encode_with_word(address),
+                JumpMode::Indirect(Register16::X, offset) => {
+                    opcode::BR_EQ_X_REL_IMM.encode_with_byte(offset)
+                }
+                JumpMode::Indirect(Register16::Y, offset) => {
+                    opcode::BR_EQ_Y_REL_IMM.encode_with_byte(offset)
+                }
+            },
+            Condition::NotEqual => match mode {
+                JumpMode::Relative(offset) => opcode::BR_NE_PC_REL.encode_with_byte(offset),
+                JumpMode::Absolute(address) => opcode::BR_NE_ABS.encode_with_word(address),
+                JumpMode::Indirect(Register16::X, offset) => {
+                    opcode::BR_NE_X_REL_IMM.encode_with_byte(offset)
+                }
+                JumpMode::Indirect(Register16::Y, offset) => {
+                    opcode::BR_NE_Y_REL_IMM.encode_with_byte(offset)
+                }
+            },
+            Condition::LessThan => match mode {
+                JumpMode::Relative(offset) => opcode::BR_LT_PC_REL.encode_with_byte(offset),
+                JumpMode::Absolute(address) => opcode::BR_LT_ABS.encode_with_word(address),
+                JumpMode::Indirect(Register16::X, offset) => {
+                    opcode::BR_LT_X_REL_IMM.encode_with_byte(offset)
+                }
+                JumpMode::Indirect(Register16::Y, offset) => {
+                    opcode::BR_LT_Y_REL_IMM.encode_with_byte(offset)
+                }
+            },
+            Condition::GreaterThan => match mode {
+                JumpMode::Relative(offset) => opcode::BR_GT_PC_REL.encode_with_byte(offset),
+                JumpMode::Absolute(address) => opcode::BR_GT_ABS.encode_with_word(address),
+                JumpMode::Indirect(Register16::X, offset) => {
+                    opcode::BR_GT_X_REL_IMM.encode_with_byte(offset)
+                }
+                JumpMode::Indirect(Register16::Y, offset) => {
+                    opcode::BR_GT_Y_REL_IMM.encode_with_byte(offset)
+                }
+            },
+            Condition::LessEqual => match mode {
+                JumpMode::Relative(offset) => opcode::BR_LE_PC_REL.encode_with_byte(offset),
+                JumpMode::Absolute(address) => opcode::BR_LE_ABS.encode_with_word(address),
+                JumpMode::Indirect(Register16::X, offset) => {
+                    opcode::BR_LE_X_REL_IMM.encode_with_byte(offset)
+                }
+                JumpMode::Indirect(Register16::Y, offset) => {
+                    opcode::BR_LE_Y_REL_IMM.encode_with_byte(offset)
+                }
+            },
+            Condition::GreaterEqual => match mode {
+                JumpMode::Relative(offset) => opcode::BR_GE_PC_REL.encode_with_byte(offset),
+                JumpMode::Absolute(address) => opcode::BR_GE_ABS.encode_with_word(address),
+                JumpMode::Indirect(Register16::X, offset) => {
+                    opcode::BR_GE_X_REL_IMM.encode_with_byte(offset)
+                }
+                JumpMode::Indirect(Register16::Y, offset) => {
+                    opcode::BR_GE_Y_REL_IMM.encode_with_byte(offset)
+                }
+            },
+            Condition::LessThanSigned => match mode {
+                JumpMode::Relative(offset) => opcode::BR_LTS_PC_REL.encode_with_byte(offset),
+                JumpMode::Absolute(address) => opcode::BR_LTS_ABS.encode_with_word(address),
+                JumpMode::Indirect(Register16::X, offset) => {
+                    opcode::BR_LTS_X_REL_IMM.encode_with_byte(offset)
+                }
+                JumpMode::Indirect(Register16::Y, offset) => {
+                    opcode::BR_LTS_Y_REL_IMM.encode_with_byte(offset)
+                }
+            },
+            Condition::GreaterThanSigned => match mode {
+                JumpMode::Relative(offset) => opcode::BR_GTS_PC_REL.encode_with_byte(offset),
+                JumpMode::Absolute(address) => opcode::BR_GTS_ABS.encode_with_word(address),
+                JumpMode::Indirect(Register16::X, offset) => {
+                    opcode::BR_GTS_X_REL_IMM.encode_with_byte(offset)
+                }
+                JumpMode::Indirect(Register16::Y, offset) => {
+                    opcode::BR_GTS_Y_REL_IMM.encode_with_byte(offset)
+                }
+            },
+            Condition::LessEqualSigned => match mode {
+                JumpMode::Relative(offset) => opcode::BR_LES_PC_REL.encode_with_byte(offset),
+                JumpMode::Absolute(address) => opcode::BR_LES_ABS.encode_with_word(address),
+                JumpMode::Indirect(Register16::X, offset) => {
+                    opcode::BR_LES_X_REL_IMM.encode_with_byte(offset)
+                }
+                JumpMode::Indirect(Register16::Y, offset) => {
+                    opcode::BR_LES_Y_REL_IMM.encode_with_byte(offset)
+                }
+            },
+            Condition::GreaterEqualSigned => match mode {
+                JumpMode::Relative(offset) => opcode::BR_GES_PC_REL.encode_with_byte(offset),
+                JumpMode::Absolute(address) => opcode::BR_GES_ABS.encode_with_word(address),
+                JumpMode::Indirect(Register16::X, offset) => {
+                    opcode::BR_GES_X_REL_IMM.encode_with_byte(offset)
+                }
+                JumpMode::Indirect(Register16::Y, offset) => {
+                    opcode::BR_GES_Y_REL_IMM.encode_with_byte(offset)
+                }
+            },
+        },
     }
 }
@@ -1,6 +1,6 @@
-use crate::{Byte, Word, InstructionBytes};
+use crate::{Byte, Word, InstructionBytes, EXTENSION_PREFIX};
 
-#[derive(PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Opcode {
     Normal(Byte),
     Extended(Byte),
@@ -51,608 +51,621 @@ impl Opcode {
     }
 }
 
-pub const NOP: Opcode = Opcode::Normal(0x00);
-
-pub const EXT: Opcode = Opcode::Normal(0x01);
-
-pub const SET_C: Opcode = Opcode::Normal(0x02);
-pub const CLR_C: Opcode = Opcode::Normal(0x03);
-
-pub const SET_I: Opcode = Opcode::Normal(0x04);
-pub const CLR_I: Opcode = Opcode::Normal(0x05);
-
-pub const SET_B: Opcode = Opcode::Normal(0x06);
-pub const CLR_B: Opcode = Opcode::Normal(0x07);
-
-pub const MV_A_BR: Opcode = Opcode::Normal(0x08);
-pub const MV_BR_A: Opcode = Opcode::Normal(0x09);
-
-pub const MV_A_A: Opcode = Opcode::Normal(0x0a);
-pub const MV_A_B: Opcode = Opcode::Normal(0x0b);
-pub const MV_A_C: Opcode = Opcode::Normal(0x0c);
-pub const MV_A_D: Opcode = Opcode::Normal(0x0d);
-pub const MV_B_A: Opcode = Opcode::Normal(0x0e);
-pub const MV_B_B: Opcode = Opcode::Normal(0x0f);
-pub const MV_B_C: Opcode = Opcode::Normal(0x10);
-pub const MV_B_D: Opcode = Opcode::Normal(0x11);
-pub const MV_C_A: Opcode = Opcode::Normal(0x12);
-pub const MV_C_B: Opcode = Opcode::Normal(0x13);
-pub const MV_C_C: Opcode = Opcode::Normal(0x14);
-pub const MV_C_D: Opcode = Opcode::Normal(0x15);
-pub const MV_D_A: Opcode = Opcode::Normal(0x16);
-pub const MV_D_B: Opcode = Opcode::Normal(0x17);
-pub const MV_D_C: Opcode = Opcode::Normal(0x18);
-pub const MV_D_D: Opcode = Opcode::Normal(0x19);
-
-pub const LD_A_IMM: Opcode = Opcode::Normal(0x1a);
-pub const LD_B_IMM: Opcode = Opcode::Normal(0x1b);
-pub const LD_C_IMM: Opcode = Opcode::Normal(0x1c);
-pub const LD_D_IMM: Opcode = Opcode::Normal(0x1d);
-
-pub const LD_A_ABS: Opcode = Opcode::Normal(0x1e);
-pub const LD_A_REL_X_BY_IMM: Opcode = Opcode::Normal(0x1f);
-pub const LD_A_REL_Y_BY_IMM: Opcode = Opcode::Normal(0x20);
-pub const LD_A_REL_SP_BY_IMM: Opcode = Opcode::Normal(0x21);
-pub const LD_A_REL_X_BY_A: Opcode = Opcode::Normal(0x22);
-pub const LD_A_REL_X_BY_B: Opcode = Opcode::Normal(0x23);
-pub const LD_A_REL_X_BY_C: Opcode = Opcode::Normal(0x24);
-pub const LD_A_REL_X_BY_D: Opcode = Opcode::Normal(0x25);
-pub const LD_A_REL_Y_BY_A: Opcode = Opcode::Normal(0x26);
-pub const LD_A_REL_Y_BY_B: Opcode = Opcode::Normal(0x27);
-pub const LD_A_REL_Y_BY_C: Opcode = Opcode::Normal(0x28);
-pub const LD_A_REL_Y_BY_D: Opcode = Opcode::Normal(0x29);
-pub const LD_A_REL_SP_BY_A: Opcode = Opcode::Normal(0x2a);
-pub const LD_A_REL_SP_BY_B: Opcode = Opcode::Normal(0x2b);
-pub const LD_A_REL_SP_BY_C: Opcode = Opcode::Normal(0x2c);
-pub const LD_A_REL_SP_BY_D: Opcode = Opcode::Normal(0x2d);
-
-pub const LD_B_ABS: Opcode = Opcode::Normal(0x2e);
-pub const LD_B_REL_X_BY_IMM: Opcode = Opcode::Normal(0x2f);
-pub const LD_B_REL_Y_BY_IMM: Opcode = Opcode::Normal(0x30);
-pub const LD_B_REL_SP_BY_IMM: Opcode = Opcode::Normal(0x31);
-pub const LD_B_REL_X_BY_A: Opcode = Opcode::Normal(0x32);
-pub const LD_B_REL_X_BY_B: Opcode = Opcode::Normal(0x33);
-pub const LD_B_REL_X_BY_C: Opcode = Opcode::Normal(0x34);
-pub const LD_B_REL_X_BY_D: Opcode = Opcode::Normal(0x35);
-pub const LD_B_REL_Y_BY_A: Opcode = Opcode::Normal(0x36);
-pub const LD_B_REL_Y_BY_B: Opcode = Opcode::Normal(0x37);
-pub const LD_B_REL_Y_BY_C: Opcode = Opcode::Normal(0x38);
-pub const LD_B_REL_Y_BY_D: Opcode = Opcode::Normal(0x39);
-pub const LD_B_REL_SP_BY_A: Opcode = Opcode::Normal(0x3a);
-pub const LD_B_REL_SP_BY_B: Opcode = Opcode::Normal(0x3b);
-pub const LD_B_REL_SP_BY_C: Opcode = Opcode::Normal(0x3c);
-pub const LD_B_REL_SP_BY_D: Opcode = Opcode::Normal(0x3d);
-
-pub const LD_C_ABS: Opcode = Opcode::Normal(0x3e);
-pub const LD_C_REL_X_BY_IMM: Opcode = Opcode::Normal(0x3f);
-pub const LD_C_REL_Y_BY_IMM: Opcode = Opcode::Normal(0x40);
-pub const LD_C_REL_SP_BY_IMM: Opcode = Opcode::Normal(0x41);
-pub const LD_C_REL_X_BY_A: Opcode = Opcode::Normal(0x42);
-pub const LD_C_REL_X_BY_B: Opcode = Opcode::Normal(0x43);
-pub const LD_C_REL_X_BY_C: Opcode = Opcode::Normal(0x44);
-pub const LD_C_REL_X_BY_D: Opcode = Opcode::Normal(0x45);
-pub const LD_C_REL_Y_BY_A: Opcode = Opcode::Normal(0x46);
-pub const LD_C_REL_Y_BY_B: Opcode = Opcode::Normal(0x47);
-pub const LD_C_REL_Y_BY_C: Opcode = Opcode::Normal(0x48);
-pub const LD_C_REL_Y_BY_D: Opcode = Opcode::Normal(0x49);
-pub const LD_C_REL_SP_BY_A: Opcode = Opcode::Normal(0x4a);
-pub const LD_C_REL_SP_BY_B: Opcode = Opcode::Normal(0x4b);
-pub const LD_C_REL_SP_BY_C: Opcode = Opcode::Normal(0x4c);
-pub const LD_C_REL_SP_BY_D: Opcode = Opcode::Normal(0x4d);
-
-pub const LD_D_ABS: Opcode = Opcode::Normal(0x4e);
-pub const LD_D_REL_X_BY_IMM: Opcode = Opcode::Normal(0x4f);
-pub const LD_D_REL_Y_BY_IMM: Opcode = Opcode::Normal(0x50);
-pub const LD_D_REL_SP_BY_IMM: Opcode = Opcode::Normal(0x51);
-pub const LD_D_REL_X_BY_A: Opcode = Opcode::Normal(0x52);
-pub const LD_D_REL_X_BY_B: Opcode = Opcode::Normal(0x53);
-pub const LD_D_REL_X_BY_C: Opcode = Opcode::Normal(0x54);
-pub const LD_D_REL_X_BY_D: Opcode = Opcode::Normal(0x55);
-pub const LD_D_REL_Y_BY_A: Opcode = Opcode::Normal(0x56);
-pub const LD_D_REL_Y_BY_B: Opcode = Opcode::Normal(0x57);
-pub const LD_D_REL_Y_BY_C: Opcode = Opcode::Normal(0x58);
-pub const LD_D_REL_Y_BY_D: Opcode = Opcode::Normal(0x59);
-pub const LD_D_REL_SP_BY_A: Opcode = Opcode::Normal(0x5a);
-pub const LD_D_REL_SP_BY_B: Opcode = Opcode::Normal(0x5b);
-pub const LD_D_REL_SP_BY_C: Opcode = Opcode::Normal(0x5c);
-pub const LD_D_REL_SP_BY_D: Opcode = Opcode::Normal(0x5d);
-
-pub const ST_ABS_A: Opcode = Opcode::Normal(0x5e);
-pub const ST_REL_X_BY_IMM_A: Opcode = Opcode::Normal(0x5f);
-pub const ST_REL_Y_BY_IMM_A: Opcode = Opcode::Normal(0x60);
-pub const ST_REL_SP_BY_IMM_A: Opcode = Opcode::Normal(0x61);
-pub const ST_REL_X_BY_A_A: Opcode = Opcode::Normal(0x62);
-pub const ST_REL_X_BY_B_A: Opcode = Opcode::Normal(0x63);
-pub const ST_REL_X_BY_C_A: Opcode = Opcode::Normal(0x64);
-pub const ST_REL_X_BY_D_A: Opcode = Opcode::Normal(0x65);
-pub const ST_REL_Y_BY_A_A: Opcode = Opcode::Normal(0x66);
-pub const ST_REL_Y_BY_B_A: Opcode = Opcode::Normal(0x67);
-pub const ST_REL_Y_BY_C_A: Opcode = Opcode::Normal(0x68);
-pub const ST_REL_Y_BY_D_A: Opcode = Opcode::Normal(0x69);
-pub const ST_REL_SP_BY_A_A: Opcode = Opcode::Normal(0x6a);
-pub const ST_REL_SP_BY_B_A: Opcode = Opcode::Normal(0x6b);
-pub const ST_REL_SP_BY_C_A: Opcode = Opcode::Normal(0x6c);
-pub const ST_REL_SP_BY_D_A: Opcode = Opcode::Normal(0x6d);
-
-pub const ST_ABS_B: Opcode = Opcode::Normal(0x6e);
-pub const ST_REL_X_BY_IMM_B: Opcode = Opcode::Normal(0x6f);
-pub const ST_REL_Y_BY_IMM_B: Opcode = Opcode::Normal(0x70);
-pub const ST_REL_SP_BY_IMM_B: Opcode = Opcode::Normal(0x71);
-pub const ST_REL_X_BY_A_B: Opcode = Opcode::Normal(0x72);
-pub const ST_REL_X_BY_B_B: Opcode = Opcode::Normal(0x73);
-pub const ST_REL_X_BY_C_B: Opcode = Opcode::Normal(0x74);
-pub const ST_REL_X_BY_D_B: Opcode = Opcode::Normal(0x75);
-pub const ST_REL_Y_BY_A_B: Opcode = Opcode::Normal(0x76);
-pub const ST_REL_Y_BY_B_B: Opcode = Opcode::Normal(0x77);
-pub const ST_REL_Y_BY_C_B: Opcode = Opcode::Normal(0x78);
-pub const ST_REL_Y_BY_D_B: Opcode = Opcode::Normal(0x79);
-pub const ST_REL_SP_BY_A_B: Opcode = Opcode::Normal(0x7a);
-pub const ST_REL_SP_BY_B_B: Opcode = Opcode::Normal(0x7b);
-pub const ST_REL_SP_BY_C_B: Opcode = Opcode::Normal(0x7c);
-pub const ST_REL_SP_BY_D_B: Opcode = Opcode::Normal(0x7d);
-
-pub const ST_ABS_C: Opcode = Opcode::Normal(0x7e);
-pub const ST_REL_X_BY_IMM_C: Opcode = Opcode::Normal(0x7f);
-pub const ST_REL_Y_BY_IMM_C: Opcode = Opcode::Normal(0x80);
-pub const ST_REL_SP_BY_IMM_C: Opcode = Opcode::Normal(0x81);
-pub const ST_REL_X_BY_A_C: Opcode = Opcode::Normal(0x82);
-pub const ST_REL_X_BY_B_C: Opcode = Opcode::Normal(0x83);
-pub const ST_REL_X_BY_C_C: Opcode = Opcode::Normal(0x84);
-pub const ST_REL_X_BY_D_C: Opcode = Opcode::Normal(0x85);
-pub const ST_REL_Y_BY_A_C: Opcode = Opcode::Normal(0x86);
-pub const ST_REL_Y_BY_B_C: Opcode = Opcode::Normal(0x87);
-pub const ST_REL_Y_BY_C_C: Opcode = Opcode::Normal(0x88);
-pub const ST_REL_Y_BY_D_C: Opcode = Opcode::Normal(0x89);
-pub const ST_REL_SP_BY_A_C: Opcode = Opcode::Normal(0x8a);
-pub const ST_REL_SP_BY_B_C: Opcode = Opcode::Normal(0x8b);
-pub const ST_REL_SP_BY_C_C: Opcode = Opcode::Normal(0x8c);
-pub const ST_REL_SP_BY_D_C: Opcode = Opcode::Normal(0x8d);
-
-pub const ST_ABS_D: Opcode = Opcode::Normal(0x8e);
-pub const ST_REL_X_BY_IMM_D: Opcode = Opcode::Normal(0x8f);
-pub const ST_REL_Y_BY_IMM_D: Opcode = Opcode::Normal(0x90);
-pub const ST_REL_SP_BY_IMM_D: Opcode = Opcode::Normal(0x91);
-pub const ST_REL_X_BY_A_D: Opcode = Opcode::Normal(0x92);
-pub const ST_REL_X_BY_B_D: Opcode = Opcode::Normal(0x93);
-pub const ST_REL_X_BY_C_D: Opcode = Opcode::Normal(0x94);
-pub const ST_REL_X_BY_D_D: Opcode = Opcode::Normal(0x95);
-pub const ST_REL_Y_BY_A_D: Opcode = Opcode::Normal(0x96);
-pub const ST_REL_Y_BY_B_D: Opcode = Opcode::Normal(0x97);
-pub const ST_REL_Y_BY_C_D: Opcode = Opcode::Normal(0x98);
-pub const ST_REL_Y_BY_D_D: Opcode = Opcode::Normal(0x99);
-pub const ST_REL_SP_BY_A_D: Opcode = Opcode::Normal(0x9a);
-pub const ST_REL_SP_BY_B_D: Opcode = Opcode::Normal(0x9b);
-pub const ST_REL_SP_BY_C_D: Opcode = Opcode::Normal(0x9c);
-pub const ST_REL_SP_BY_D_D: Opcode = Opcode::Normal(0x9d);
-
-pub const IN_A_PORT: Opcode = Opcode::Normal(0x9e);
-pub const IN_A_REL_X_BY_IMM: Opcode = Opcode::Normal(0x9f);
-pub const IN_A_REL_Y_BY_IMM: Opcode = Opcode::Normal(0xa0);
-pub const IN_A_REL_X_BY_A: Opcode = Opcode::Normal(0xa1);
-pub const IN_A_REL_X_BY_B: Opcode = Opcode::Normal(0xa2);
-pub const IN_A_REL_X_BY_C: Opcode = Opcode::Normal(0xa3);
-pub const IN_A_REL_X_BY_D: Opcode = Opcode::Normal(0xa4);
-pub const IN_A_REL_Y_BY_A: Opcode = Opcode::Normal(0xa5);
-pub const IN_A_REL_Y_BY_B: Opcode = Opcode::Normal(0xa6);
-pub const IN_A_REL_Y_BY_C: Opcode = Opcode::Normal(0xa7);
-pub const IN_A_REL_Y_BY_D: Opcode = Opcode::Normal(0xa8);
-
-pub const IN_B_PORT: Opcode = Opcode::Normal(0xa9);
-pub const IN_B_REL_X_BY_IMM: Opcode = Opcode::Normal(0xaa);
-pub const IN_B_REL_Y_BY_IMM: Opcode = Opcode::Normal(0xab);
-pub const IN_B_REL_X_BY_A: Opcode = Opcode::Normal(0xac);
-pub const IN_B_REL_X_BY_B: Opcode = Opcode::Normal(0xad);
-pub const IN_B_REL_X_BY_C: Opcode = Opcode::Normal(0xae);
-pub const IN_B_REL_X_BY_D: Opcode = Opcode::Normal(0xaf);
-pub const IN_B_REL_Y_BY_A: Opcode = Opcode::Normal(0xb0);
-pub const IN_B_REL_Y_BY_B: Opcode = Opcode::Normal(0xb1);
-pub const IN_B_REL_Y_BY_C: Opcode = Opcode::Normal(0xb2);
-pub const IN_B_REL_Y_BY_D: Opcode = Opcode::Normal(0xb3);
-
-pub const IN_C_PORT: Opcode = Opcode::Normal(0xb4);
-pub const IN_C_REL_X_BY_IMM: Opcode = Opcode::Normal(0xb5);
-pub const IN_C_REL_Y_BY_IMM: Opcode = Opcode::Normal(0xb6);
-pub const IN_C_REL_X_BY_A: Opcode = Opcode::Normal(0xb7);
-pub const IN_C_REL_X_BY_B: Opcode = Opcode::Normal(0xb8);
-pub const IN_C_REL_X_BY_C: Opcode = Opcode::Normal(0xb9);
-pub const IN_C_REL_X_BY_D: Opcode = Opcode::Normal(0xba);
-pub const IN_C_REL_Y_BY_A: Opcode = Opcode::Normal(0xbb);
-pub const IN_C_REL_Y_BY_B: Opcode = Opcode::Normal(0xbc);
-pub const IN_C_REL_Y_BY_C: Opcode = Opcode::Normal(0xbd);
-pub const IN_C_REL_Y_BY_D: Opcode = Opcode::Normal(0xbe);
-
-pub const IN_D_PORT: Opcode = Opcode::Normal(0xbf);
-pub const IN_D_REL_X_BY_IMM: Opcode = Opcode::Normal(0xc0);
-pub const IN_D_REL_Y_BY_IMM: Opcode = Opcode::Normal(0xc1);
-pub const IN_D_REL_X_BY_A: Opcode = Opcode::Normal(0xc2);
-pub const IN_D_REL_X_BY_B: Opcode = Opcode::Normal(0xc3);
-pub const IN_D_REL_X_BY_C: Opcode = Opcode::Normal(0xc4);
-pub const IN_D_REL_X_BY_D: Opcode = Opcode::Normal(0xc5);
-pub const IN_D_REL_Y_BY_A: Opcode = Opcode::Normal(0xc6);
-pub const IN_D_REL_Y_BY_B: Opcode = Opcode::Normal(0xc7);
-pub const IN_D_REL_Y_BY_C: Opcode = Opcode::Normal(0xc8);
-pub const IN_D_REL_Y_BY_D: Opcode = Opcode::Normal(0xc9);
-
-pub const OUT_PORT_A: Opcode = Opcode::Normal(0xca);
-pub const OUT_REL_X_BY_IMM_A: Opcode = Opcode::Normal(0xcb);
-pub const OUT_REL_Y_BY_IMM_A: Opcode = Opcode::Normal(0xcc);
-pub const OUT_REL_X_BY_A_A: Opcode = Opcode::Normal(0xcd);
-pub const OUT_REL_X_BY_B_A: Opcode = Opcode::Normal(0xce);
-pub const OUT_REL_X_BY_C_A: Opcode = Opcode::Normal(0xcf);
-pub const OUT_REL_X_BY_D_A: Opcode = Opcode::Normal(0xd0);
-pub const OUT_REL_Y_BY_A_A: Opcode = Opcode::Normal(0xd1);
-pub const OUT_REL_Y_BY_B_A: Opcode = Opcode::Normal(0xd2);
-pub const OUT_REL_Y_BY_C_A: Opcode = Opcode::Normal(0xd3);
-pub const OUT_REL_Y_BY_D_A: Opcode = Opcode::Normal(0xd4);
-
-pub const OUT_PORT_B: Opcode = Opcode::Normal(0xd5);
-pub const OUT_REL_X_BY_IMM_B: Opcode = Opcode::Normal(0xd6);
-pub const OUT_REL_Y_BY_IMM_B: Opcode = Opcode::Normal(0xd7);
-pub const OUT_REL_X_BY_A_B: Opcode = Opcode::Normal(0xd8);
-pub const OUT_REL_X_BY_B_B: Opcode = Opcode::Normal(0xd9);
-pub const OUT_REL_X_BY_C_B: Opcode = Opcode::Normal(0xda);
-pub const OUT_REL_X_BY_D_B: Opcode = Opcode::Normal(0xdb);
-pub const OUT_REL_Y_BY_A_B: Opcode = Opcode::Normal(0xdc);
-pub const OUT_REL_Y_BY_B_B: Opcode = Opcode::Normal(0xdd);
-pub const OUT_REL_Y_BY_C_B: Opcode = Opcode::Normal(0xde);
-pub const OUT_REL_Y_BY_D_B: Opcode = Opcode::Normal(0xdf);
-
-pub const OUT_PORT_C: Opcode = Opcode::Normal(0xe0);
-pub const OUT_REL_X_BY_IMM_C: Opcode = Opcode::Normal(0xe1);
-pub const OUT_REL_Y_BY_IMM_C: Opcode = Opcode::Normal(0xe2);
-pub const OUT_REL_X_BY_A_C: Opcode = Opcode::Normal(0xe3);
-pub const OUT_REL_X_BY_B_C: Opcode = Opcode::Normal(0xe4);
-pub const OUT_REL_X_BY_C_C: Opcode = Opcode::Normal(0xe5);
-pub const OUT_REL_X_BY_D_C: Opcode = Opcode::Normal(0xe6);
-pub const OUT_REL_Y_BY_A_C: Opcode = Opcode::Normal(0xe7);
-pub const OUT_REL_Y_BY_B_C: Opcode = Opcode::Normal(0xe8);
-pub const OUT_REL_Y_BY_C_C: Opcode = Opcode::Normal(0xe9);
-pub const OUT_REL_Y_BY_D_C: Opcode = Opcode::Normal(0xea);
-
-pub const OUT_PORT_D: Opcode = Opcode::Normal(0xeb);
-pub const OUT_REL_X_BY_IMM_D: Opcode = Opcode::Normal(0xec);
-pub const OUT_REL_Y_BY_IMM_D: Opcode = Opcode::Normal(0xed);
-pub const OUT_REL_X_BY_A_D: Opcode = Opcode::Normal(0xee);
-pub const OUT_REL_X_BY_B_D: Opcode = Opcode::Normal(0xef);
-pub const OUT_REL_X_BY_C_D: Opcode = Opcode::Normal(0xf0);
-pub const OUT_REL_X_BY_D_D: Opcode = Opcode::Normal(0xf1);
-pub const OUT_REL_Y_BY_A_D: Opcode = Opcode::Normal(0xf2);
-pub const OUT_REL_Y_BY_B_D: Opcode = Opcode::Normal(0xf3);
-pub const OUT_REL_Y_BY_C_D: Opcode = Opcode::Normal(0xf4);
-pub const OUT_REL_Y_BY_D_D: Opcode = Opcode::Normal(0xf5);
-
-pub const MV_X_SP: Opcode = Opcode::Normal(0xf6);
-pub const MV_SP_X: Opcode = Opcode::Normal(0xf7);
-
-pub const MV_X_X: Opcode = Opcode::Normal(0xf8);
-pub const MV_X_Y: Opcode = Opcode::Normal(0xf9);
-pub const MV_X_AB: Opcode = Opcode::Normal(0xfa);
-pub const MV_X_CD: Opcode = Opcode::Normal(0xfb);
-
-pub const MV_Y_X: Opcode = Opcode::Normal(0xfc);
-pub const MV_Y_Y: Opcode = Opcode::Normal(0xfd);
-pub const MV_Y_AB: Opcode = Opcode::Normal(0xfe);
-pub const MV_Y_CD: Opcode = Opcode::Normal(0xff);
-
-pub const MV_AB_X: Opcode = Opcode::Extended(0x00);
-pub const MV_AB_Y: Opcode = Opcode::Extended(0x01);
-
-pub const MV_CD_X: Opcode = Opcode::Extended(0x02);
-pub const MV_CD_Y: Opcode = Opcode::Extended(0x03);
-
-pub const LD_X_IMM: Opcode = Opcode::Extended(0x04);
-pub const LD_Y_IMM: Opcode = Opcode::Extended(0x05);
-
-pub const LD_X_ABS: Opcode = Opcode::Extended(0x06);
-pub const LD_X_REL_X_BY_IMM: Opcode = Opcode::Extended(0x07);
-pub const LD_X_REL_Y_BY_IMM: Opcode = Opcode::Extended(0x08);
-pub const LD_X_REL_SP_BY_IMM: Opcode = Opcode::Extended(0x09);
-
-pub const LD_Y_ABS: Opcode = Opcode::Extended(0x0a);
-pub const LD_Y_REL_X_BY_IMM: Opcode = Opcode::Extended(0x0b);
-pub const LD_Y_REL_Y_BY_IMM: Opcode = Opcode::Extended(0x0c);
-pub const LD_Y_REL_SP_BY_IMM: Opcode = Opcode::Extended(0x0d);
-
-pub const ST_ABS_X: Opcode = Opcode::Extended(0x0e);
-pub const ST_REL_X_BY_IMM_X: Opcode = Opcode::Extended(0x0f);
-pub const ST_REL_Y_BY_IMM_X: Opcode = Opcode::Extended(0x10);
-pub const ST_REL_SP_BY_IMM_X: Opcode = Opcode::Extended(0x11);
-
-pub const ST_ABS_Y: Opcode = Opcode::Extended(0x12);
-pub const ST_REL_X_BY_IMM_Y: Opcode = Opcode::Extended(0x13);
-pub const ST_REL_Y_BY_IMM_Y: Opcode = Opcode::Extended(0x14);
-pub const ST_REL_SP_BY_IMM_Y: Opcode = Opcode::Extended(0x15);
-
-pub const LEA_X_BY_A: Opcode = Opcode::Extended(0x16);
-pub const LEA_X_BY_B: Opcode = Opcode::Extended(0x17);
-pub const LEA_X_BY_C: Opcode = Opcode::Extended(0x18);
-pub const LEA_X_BY_D: Opcode = Opcode::Extended(0x19);
-pub const LEA_X_BY_IMM: Opcode = Opcode::Extended(0x1a);
-
-pub const LEA_Y_BY_A: Opcode = Opcode::Extended(0x1b);
-pub const LEA_Y_BY_B: Opcode = Opcode::Extended(0x1c);
-pub const LEA_Y_BY_C: Opcode = Opcode::Extended(0x1d);
-pub const LEA_Y_BY_D: Opcode = Opcode::Extended(0x1e);
-pub const LEA_Y_BY_IMM: Opcode = Opcode::Extended(0x1f);
-
-pub const LEA_SP_BY_A: Opcode = Opcode::Extended(0x20);
-pub const LEA_SP_BY_B: Opcode = Opcode::Extended(0x21);
-pub const LEA_SP_BY_C: Opcode = Opcode::Extended(0x22);
-pub const LEA_SP_BY_D: Opcode = Opcode::Extended(0x23);
-pub const LEA_SP_BY_IMM: Opcode = Opcode::Extended(0x24);
-
-pub const INC_X: Opcode = Opcode::Extended(0x25);
-pub const INC_Y: Opcode = Opcode::Extended(0x26);
-
-pub const DEC_X: Opcode = Opcode::Extended(0x27);
-pub const DEC_Y: Opcode = Opcode::Extended(0x28);
-
-pub const ADDC_A_A: Opcode = Opcode::Extended(0x29);
-pub const ADDC_A_B: Opcode = Opcode::Extended(0x2a);
-pub const ADDC_A_C: Opcode = Opcode::Extended(0x2b);
-pub const ADDC_A_D: Opcode = Opcode::Extended(0x2c);
-
-pub const ADDC_B_A: Opcode = Opcode::Extended(0x2d);
-pub const ADDC_B_B: Opcode = Opcode::Extended(0x2e);
-pub const ADDC_B_C: Opcode = Opcode::Extended(0x2f);
-pub const ADDC_B_D: Opcode = Opcode::Extended(0x30);
-
-pub const ADDC_C_A: Opcode = Opcode::Extended(0x31);
-pub const ADDC_C_B: Opcode = Opcode::Extended(0x32);
-pub const ADDC_C_C: Opcode = Opcode::Extended(0x33);
-pub const ADDC_C_D: Opcode = Opcode::Extended(0x34);
-
-pub const ADDC_D_B: Opcode = Opcode::Extended(0x36);
-pub const ADDC_D_A: Opcode = Opcode::Extended(0x35);
-pub const ADDC_D_C: Opcode = Opcode::Extended(0x37);
-pub const ADDC_D_D: Opcode = Opcode::Extended(0x38);
-
-pub const ADDC_A_IMM: Opcode = Opcode::Extended(0x39);
-pub const ADDC_B_IMM: Opcode = Opcode::Extended(0x3a);
-pub const ADDC_C_IMM: Opcode = Opcode::Extended(0x3b);
-pub const ADDC_D_IMM: Opcode = Opcode::Extended(0x3c);
-
-pub const SUBB_A_A: Opcode = Opcode::Extended(0x3d);
-pub const SUBB_A_B: Opcode = Opcode::Extended(0x3e);
-pub const SUBB_A_C: Opcode = Opcode::Extended(0x3f);
-pub const SUBB_A_D: Opcode = Opcode::Extended(0x40);
-
-pub const SUBB_B_A: Opcode = Opcode::Extended(0x41);
-pub const SUBB_B_B: Opcode = Opcode::Extended(0x42);
-pub const SUBB_B_C: Opcode = Opcode::Extended(0x43);
-pub const SUBB_B_D: Opcode = Opcode::Extended(0x44);
-
-pub const SUBB_C_A: Opcode = Opcode::Extended(0x45);
-pub const SUBB_C_B: Opcode = Opcode::Extended(0x46);
-pub const SUBB_C_C: Opcode = Opcode::Extended(0x47);
-pub const SUBB_C_D: Opcode = Opcode::Extended(0x48);
-
-pub const SUBB_D_A: Opcode = Opcode::Extended(0x49);
-pub const SUBB_D_B: Opcode = Opcode::Extended(0x4a);
-pub const SUBB_D_C: Opcode = Opcode::Extended(0x4b);
-pub const SUBB_D_D: Opcode = Opcode::Extended(0x4c);
-
-pub const SUBB_A_IMM: Opcode = Opcode::Extended(0x4d);
-pub const SUBB_B_IMM: Opcode = Opcode::Extended(0x4e);
-pub const SUBB_C_IMM: Opcode = Opcode::Extended(0x4f);
-pub const SUBB_D_IMM: Opcode = Opcode::Extended(0x50);
-
-pub const AND_A_A: Opcode = Opcode::Extended(0x51);
-pub const AND_A_B: Opcode = Opcode::Extended(0x52);
-pub const AND_A_C: Opcode = Opcode::Extended(0x53);
-pub const AND_A_D: Opcode = Opcode::Extended(0x54);
-
-pub const AND_B_A: Opcode = Opcode::Extended(0x55);
-pub const AND_B_B: Opcode = Opcode::Extended(0x56);
-pub const AND_B_C: Opcode = Opcode::Extended(0x57);
-pub const AND_B_D: Opcode = Opcode::Extended(0x58);
-
-pub const AND_C_A: Opcode = Opcode::Extended(0x59);
-pub const AND_C_B: Opcode = Opcode::Extended(0x5a);
-pub const AND_C_C: Opcode = Opcode::Extended(0x5b);
-pub const AND_C_D: Opcode = Opcode::Extended(0x5c);
-
-pub const AND_D_A: Opcode = Opcode::Extended(0x5d);
-pub const AND_D_B: Opcode = Opcode::Extended(0x5e);
-pub const AND_D_C: Opcode = Opcode::Extended(0x5f);
-pub const AND_D_D: Opcode = Opcode::Extended(0x60);
-
-pub const AND_A_IMM: Opcode = Opcode::Extended(0x61);
-pub const AND_B_IMM: Opcode = Opcode::Extended(0x62);
-pub const AND_C_IMM: Opcode = Opcode::Extended(0x63);
-pub const AND_D_IMM: Opcode = Opcode::Extended(0x64);
-
-pub const OR_A_A: Opcode = Opcode::Extended(0x65);
-pub const OR_A_B: Opcode = Opcode::Extended(0x66);
-pub const OR_A_C: Opcode = Opcode::Extended(0x67);
-pub const OR_A_D: Opcode = Opcode::Extended(0x68);
-
-pub const OR_B_A: Opcode = Opcode::Extended(0x69);
-pub const OR_B_B: Opcode = Opcode::Extended(0x6a);
-pub const OR_B_C: Opcode = Opcode::Extended(0x6b);
-pub const OR_B_D: Opcode = Opcode::Extended(0x6c);
-
-pub const OR_C_A: Opcode = Opcode::Extended(0x6d);
-pub const OR_C_B: Opcode = Opcode::Extended(0x6e);
-pub const OR_C_C: Opcode = Opcode::Extended(0x6f);
-pub const OR_C_D: Opcode = Opcode::Extended(0x70);
-
-pub const OR_D_A: Opcode = Opcode::Extended(0x71);
-pub const OR_D_B: Opcode = Opcode::Extended(0x72);
-pub const OR_D_C: Opcode = Opcode::Extended(0x73);
-pub const OR_D_D: Opcode = Opcode::Extended(0x74);
-
-pub const OR_A_IMM: Opcode = Opcode::Extended(0x75);
-pub const OR_B_IMM: Opcode = Opcode::Extended(0x76);
-pub const OR_C_IMM: Opcode = Opcode::Extended(0x77);
-pub const OR_D_IMM: Opcode = Opcode::Extended(0x78);
-
-pub const XOR_A_A: Opcode = Opcode::Extended(0x79);
-pub const XOR_A_B: Opcode = Opcode::Extended(0x7a);
-pub const XOR_A_C: Opcode = Opcode::Extended(0x7b);
-pub const XOR_A_D: Opcode = Opcode::Extended(0x7c);
-
-pub const XOR_B_A: Opcode = Opcode::Extended(0x7d);
-pub const XOR_B_B: Opcode = Opcode::Extended(0x7e);
-pub const XOR_B_C: Opcode = Opcode::Extended(0x7f);
-pub const XOR_B_D: Opcode = Opcode::Extended(0x80);
-
-pub const XOR_C_A: Opcode = Opcode::Extended(0x81);
-pub const XOR_C_B: Opcode = Opcode::Extended(0x82);
-pub const XOR_C_C: Opcode = Opcode::Extended(0x83);
-pub const XOR_C_D: Opcode = Opcode::Extended(0x84);
-
-pub const XOR_D_A: Opcode = Opcode::Extended(0x85);
-pub const XOR_D_B: Opcode = Opcode::Extended(0x86);
-pub const XOR_D_C: Opcode = Opcode::Extended(0x87);
-pub const XOR_D_D: Opcode = Opcode::Extended(0x88);
-
-pub const XOR_A_IMM: Opcode = Opcode::Extended(0x89);
-pub const XOR_B_IMM: Opcode = Opcode::Extended(0x8a);
-pub const XOR_C_IMM: Opcode = Opcode::Extended(0x8b);
-pub const XOR_D_IMM: Opcode = Opcode::Extended(0x8c);
-
-pub const SHL_A: Opcode = Opcode::Extended(0x8d);
-pub const SHL_B: Opcode = Opcode::Extended(0x8e);
-pub const SHL_C: Opcode = Opcode::Extended(0x8f);
-pub const SHL_D: Opcode = Opcode::Extended(0x90);
-
-pub const SHR_A: Opcode = Opcode::Extended(0x91);
-pub const SHR_B: Opcode = Opcode::Extended(0x92);
-pub const SHR_C: Opcode = Opcode::Extended(0x93);
-pub const SHR_D: Opcode = Opcode::Extended(0x94);
-
-pub const ASR_A: Opcode = Opcode::Extended(0x95);
-pub const ASR_B: Opcode = Opcode::Extended(0x96);
-pub const ASR_C: Opcode = Opcode::Extended(0x97);
-pub const ASR_D: Opcode = Opcode::Extended(0x98);
-
-pub const NOT_A: Opcode = Opcode::Extended(0x99);
-pub const NOT_B: Opcode = Opcode::Extended(0x9a);
-pub const NOT_C: Opcode = Opcode::Extended(0x9b);
-pub const NOT_D: Opcode = Opcode::Extended(0x9c);
-
-pub const NEG_A: Opcode = Opcode::Extended(0x9d);
-pub const NEG_B: Opcode = Opcode::Extended(0x9e);
-pub const NEG_C: Opcode = Opcode::Extended(0x9f);
-pub const NEG_D: Opcode = Opcode::Extended(0xa0);
-
-pub const INC_A: Opcode = Opcode::Extended(0xa1);
-pub const INC_B: Opcode = Opcode::Extended(0xa2);
-pub const INC_C: Opcode = Opcode::Extended(0xa3);
-pub const INC_D: Opcode = Opcode::Extended(0xa4);
-
-pub const DEC_A: Opcode = Opcode::Extended(0xa5);
-pub const DEC_B: Opcode = Opcode::Extended(0xa6);
-pub const DEC_C: Opcode = Opcode::Extended(0xa7);
-pub const DEC_D: Opcode = Opcode::Extended(0xa8);
-
-pub const CMP_A_A: Opcode = Opcode::Extended(0xa9);
-pub const CMP_A_B: Opcode = Opcode::Extended(0xaa);
-pub const CMP_A_C: Opcode = Opcode::Extended(0xab);
-pub const CMP_A_D: Opcode = Opcode::Extended(0xac);
-
-pub const CMP_B_A: Opcode = Opcode::Extended(0xad);
-pub const CMP_B_B: Opcode = Opcode::Extended(0xae);
-pub const CMP_B_C: Opcode = Opcode::Extended(0xaf);
-pub const CMP_B_D: Opcode = Opcode::Extended(0xb0);
-
-pub const CMP_C_A: Opcode = Opcode::Extended(0xb1);
-pub const CMP_C_B: Opcode = Opcode::Extended(0xb2);
-pub const CMP_C_C: Opcode = Opcode::Extended(0xb3);
-pub const CMP_C_D: Opcode = Opcode::Extended(0xb4);
-
-pub const CMP_D_A: Opcode = Opcode::Extended(0xb5);
-pub const CMP_D_B: Opcode = Opcode::Extended(0xb6);
-pub const CMP_D_C: Opcode = Opcode::Extended(0xb7);
-pub const CMP_D_D: Opcode = Opcode::Extended(0xb8);
-
-pub const CMP_A_IMM: Opcode = Opcode::Extended(0xb9);
-pub const CMP_B_IMM: Opcode = Opcode::Extended(0xba);
-pub const CMP_C_IMM: Opcode = Opcode::Extended(0xbb);
-pub const CMP_D_IMM: Opcode = Opcode::Extended(0xbc);
-
-pub const TEST_A: Opcode = Opcode::Extended(0xbd);
-pub const TEST_B: Opcode = Opcode::Extended(0xbe);
-pub const TEST_C: Opcode = Opcode::Extended(0xbf);
-pub const TEST_D: Opcode = Opcode::Extended(0xc0);
-
-pub const PUSH_A: Opcode = Opcode::Extended(0xc1);
-pub const PUSH_B: Opcode = Opcode::Extended(0xc2);
-pub const PUSH_C: Opcode = Opcode::Extended(0xc3);
-pub const PUSH_D: Opcode = Opcode::Extended(0xc4);
-
-pub const PUSH_X: Opcode = Opcode::Extended(0xc5);
-pub const PUSH_Y: Opcode = Opcode::Extended(0xc6);
-
-pub const POP_A: Opcode = Opcode::Extended(0xc7);
-pub const POP_B: Opcode = Opcode::Extended(0xc8);
-pub const POP_C: Opcode = Opcode::Extended(0xc9);
-pub const POP_D: Opcode = Opcode::Extended(0xca);
-
-pub const POP_X: Opcode = Opcode::Extended(0xcb);
-pub const POP_Y: Opcode = Opcode::Extended(0xcc);
-
-pub const CALL_PC_REL: Opcode = Opcode::Extended(0xcd);
-pub const CALL_ABS: Opcode = Opcode::Extended(0xce);
-pub const CALL_X_REL_IMM: Opcode = Opcode::Extended(0xcf);
-pub const CALL_Y_REL_IMM: Opcode = Opcode::Extended(0xd0);
-pub const RET: Opcode = Opcode::Extended(0xd1);
-
-pub const SWI: Opcode = Opcode::Extended(0xd2);
-pub const RETI: Opcode = Opcode::Extended(0xd3);
-
-pub const JMP_PC_REL: Opcode = Opcode::Extended(0xd4);
-pub const JMP_ABS: Opcode = Opcode::Extended(0xd5);
-pub const JMP_X_REL_IMM: Opcode = Opcode::Extended(0xd6);
-pub const JMP_Y_REL_IMM: Opcode = Opcode::Extended(0xd7);
-
-pub const BR_EQ_PC_REL: Opcode = Opcode::Extended(0xd8);
-pub const BR_EQ_ABS: Opcode = Opcode::Extended(0xd9);
-pub const BR_EQ_X_REL_IMM: Opcode = Opcode::Extended(0xda);
-pub const BR_EQ_Y_REL_IMM: Opcode = Opcode::Extended(0xdb);
-
-pub const BR_NE_PC_REL: Opcode = Opcode::Extended(0xdc);
-pub const BR_NE_ABS: Opcode = Opcode::Extended(0xdd);
-pub const BR_NE_X_REL_IMM: Opcode = Opcode::Extended(0xde);
-pub const BR_NE_Y_REL_IMM: Opcode = Opcode::Extended(0xdf);
-
-pub const BR_LT_PC_REL: Opcode = Opcode::Extended(0xe0);
-pub const BR_LT_ABS: Opcode = Opcode::Extended(0xe1);
-pub const BR_LT_X_REL_IMM: Opcode = Opcode::Extended(0xe2);
-pub const BR_LT_Y_REL_IMM: Opcode = Opcode::Extended(0xe3);
-
-pub const BR_GT_PC_REL: Opcode = Opcode::Extended(0xe4);
-pub const BR_GT_ABS: Opcode = Opcode::Extended(0xe5);
-pub const BR_GT_X_REL_IMM: Opcode = Opcode::Extended(0xe6);
-pub const BR_GT_Y_REL_IMM: Opcode = Opcode::Extended(0xe7);
-
-pub const BR_LE_PC_REL: Opcode = Opcode::Extended(0xe8);
-pub const BR_LE_ABS: Opcode = Opcode::Extended(0xe9);
-pub const BR_LE_X_REL_IMM: Opcode = Opcode::Extended(0xea);
-pub const BR_LE_Y_REL_IMM: Opcode = Opcode::Extended(0xeb);
-
-pub const BR_GE_PC_REL: Opcode = Opcode::Extended(0xec);
-pub const BR_GE_ABS: Opcode = Opcode::Extended(0xed);
-pub const BR_GE_X_REL_IMM: Opcode = Opcode::Extended(0xee);
-pub const BR_GE_Y_REL_IMM: Opcode = Opcode::Extended(0xef);
-
-pub const BR_LTS_PC_REL: Opcode = Opcode::Extended(0xf0);
-pub const BR_LTS_ABS: Opcode = Opcode::Extended(0xf1);
-pub const BR_LTS_X_REL_IMM: Opcode = Opcode::Extended(0xf2);
-pub const BR_LTS_Y_REL_IMM: Opcode = Opcode::Extended(0xf3);
-
-pub const BR_GTS_PC_REL: Opcode = Opcode::Extended(0xf4);
-pub const BR_GTS_ABS: Opcode = Opcode::Extended(0xf5);
-pub const BR_GTS_X_REL_IMM: Opcode = Opcode::Extended(0xf6);
-pub const BR_GTS_Y_REL_IMM: Opcode = Opcode::Extended(0xf7);
-
-pub const BR_LES_PC_REL: Opcode = Opcode::Extended(0xf8);
-pub const BR_LES_ABS: Opcode = Opcode::Extended(0xf9);
-pub const BR_LES_X_REL_IMM: Opcode = Opcode::Extended(0xfa);
-pub const BR_LES_Y_REL_IMM: Opcode = Opcode::Extended(0xfb);
-
-pub const BR_GES_PC_REL: Opcode = Opcode::Extended(0xfc);
-pub const BR_GES_ABS: Opcode = Opcode::Extended(0xfd);
-pub const BR_GES_X_REL_IMM: Opcode = Opcode::Extended(0xfe);
-pub const BR_GES_Y_REL_IMM: Opcode = Opcode::Extended(0xff);
+/// Declares a constant for each opcode, and `OPCODES`, listing them all by
+/// name.
+macro_rules! opcodes {
+    ($($name:ident = $kind:ident($byte:expr),)*) => {
+        $(pub const $name: Opcode = Opcode::$kind($byte);)*
+
+        /// Every opcode, along with the name of its constant.
+        pub const OPCODES: &[(&str, Opcode)] = &[$((stringify!($name), $name),)*];
+    };
+}
+
+opcodes! {
+    NOP = Normal(0x00),
+
+    EXT = Normal(EXTENSION_PREFIX),
+
+    SET_C = Normal(0x02),
+    CLR_C = Normal(0x03),
+
+    SET_I = Normal(0x04),
+    CLR_I = Normal(0x05),
+
+    SET_B = Normal(0x06),
+    CLR_B = Normal(0x07),
+
+    MV_A_BR = Normal(0x08),
+    MV_BR_A = Normal(0x09),
+
+    MV_A_A = Normal(0x0a),
+    MV_A_B = Normal(0x0b),
+    MV_A_C = Normal(0x0c),
+    MV_A_D = Normal(0x0d),
+    MV_B_A = Normal(0x0e),
+    MV_B_B = Normal(0x0f),
+    MV_B_C = Normal(0x10),
+    MV_B_D = Normal(0x11),
+    MV_C_A = Normal(0x12),
+    MV_C_B = Normal(0x13),
+    MV_C_C = Normal(0x14),
+    MV_C_D = Normal(0x15),
+    MV_D_A = Normal(0x16),
+    MV_D_B = Normal(0x17),
+    MV_D_C = Normal(0x18),
+    MV_D_D = Normal(0x19),
+
+    LD_A_IMM = Normal(0x1a),
+    LD_B_IMM = Normal(0x1b),
+    LD_C_IMM = Normal(0x1c),
+    LD_D_IMM = Normal(0x1d),
+
+    LD_A_ABS = Normal(0x1e),
+    LD_A_REL_X_BY_IMM = Normal(0x1f),
+    LD_A_REL_Y_BY_IMM = Normal(0x20),
+    LD_A_REL_SP_BY_IMM = Normal(0x21),
+    LD_A_REL_X_BY_A = Normal(0x22),
+    LD_A_REL_X_BY_B = Normal(0x23),
+    LD_A_REL_X_BY_C = Normal(0x24),
+    LD_A_REL_X_BY_D = Normal(0x25),
+    LD_A_REL_Y_BY_A = Normal(0x26),
+    LD_A_REL_Y_BY_B = Normal(0x27),
+    LD_A_REL_Y_BY_C = Normal(0x28),
+    LD_A_REL_Y_BY_D = Normal(0x29),
+    LD_A_REL_SP_BY_A = Normal(0x2a),
+    LD_A_REL_SP_BY_B = Normal(0x2b),
+    LD_A_REL_SP_BY_C = Normal(0x2c),
+    LD_A_REL_SP_BY_D = Normal(0x2d),
+
+    LD_B_ABS = Normal(0x2e),
+    LD_B_REL_X_BY_IMM = Normal(0x2f),
+    LD_B_REL_Y_BY_IMM = Normal(0x30),
+    LD_B_REL_SP_BY_IMM = Normal(0x31),
+    LD_B_REL_X_BY_A = Normal(0x32),
+    LD_B_REL_X_BY_B = Normal(0x33),
+    LD_B_REL_X_BY_C = Normal(0x34),
+    LD_B_REL_X_BY_D = Normal(0x35),
+    LD_B_REL_Y_BY_A = Normal(0x36),
+    LD_B_REL_Y_BY_B = Normal(0x37),
+    LD_B_REL_Y_BY_C = Normal(0x38),
+    LD_B_REL_Y_BY_D = Normal(0x39),
+    LD_B_REL_SP_BY_A = Normal(0x3a),
+    LD_B_REL_SP_BY_B = Normal(0x3b),
+    LD_B_REL_SP_BY_C = Normal(0x3c),
+    LD_B_REL_SP_BY_D = Normal(0x3d),
+
+    LD_C_ABS = Normal(0x3e),
+    LD_C_REL_X_BY_IMM = Normal(0x3f),
+    LD_C_REL_Y_BY_IMM = Normal(0x40),
+    LD_C_REL_SP_BY_IMM = Normal(0x41),
+    LD_C_REL_X_BY_A = Normal(0x42),
+    LD_C_REL_X_BY_B = Normal(0x43),
+    LD_C_REL_X_BY_C = Normal(0x44),
+    LD_C_REL_X_BY_D = Normal(0x45),
+    LD_C_REL_Y_BY_A = Normal(0x46),
+    LD_C_REL_Y_BY_B = Normal(0x47),
+    LD_C_REL_Y_BY_C = Normal(0x48),
+    LD_C_REL_Y_BY_D = Normal(0x49),
+    LD_C_REL_SP_BY_A = Normal(0x4a),
+    LD_C_REL_SP_BY_B = Normal(0x4b),
+    LD_C_REL_SP_BY_C = Normal(0x4c),
+    LD_C_REL_SP_BY_D = Normal(0x4d),
+
+    LD_D_ABS = Normal(0x4e),
+    LD_D_REL_X_BY_IMM = Normal(0x4f),
+    LD_D_REL_Y_BY_IMM = Normal(0x50),
+    LD_D_REL_SP_BY_IMM = Normal(0x51),
+    LD_D_REL_X_BY_A = Normal(0x52),
+    LD_D_REL_X_BY_B = Normal(0x53),
+    LD_D_REL_X_BY_C = Normal(0x54),
+    LD_D_REL_X_BY_D = Normal(0x55),
+    LD_D_REL_Y_BY_A = Normal(0x56),
+    LD_D_REL_Y_BY_B = Normal(0x57),
+    LD_D_REL_Y_BY_C = Normal(0x58),
+    LD_D_REL_Y_BY_D = Normal(0x59),
+    LD_D_REL_SP_BY_A = Normal(0x5a),
+    LD_D_REL_SP_BY_B = Normal(0x5b),
+    LD_D_REL_SP_BY_C = Normal(0x5c),
+    LD_D_REL_SP_BY_D = Normal(0x5d),
+
+    ST_ABS_A = Normal(0x5e),
+    ST_REL_X_BY_IMM_A = Normal(0x5f),
+    ST_REL_Y_BY_IMM_A = Normal(0x60),
+    ST_REL_SP_BY_IMM_A = Normal(0x61),
+    ST_REL_X_BY_A_A = Normal(0x62),
+    ST_REL_X_BY_B_A = Normal(0x63),
+    ST_REL_X_BY_C_A = Normal(0x64),
+    ST_REL_X_BY_D_A = Normal(0x65),
+    ST_REL_Y_BY_A_A = Normal(0x66),
+    ST_REL_Y_BY_B_A = Normal(0x67),
+    ST_REL_Y_BY_C_A = Normal(0x68),
+    ST_REL_Y_BY_D_A = Normal(0x69),
+    ST_REL_SP_BY_A_A = Normal(0x6a),
+    ST_REL_SP_BY_B_A = Normal(0x6b),
+    ST_REL_SP_BY_C_A = Normal(0x6c),
+    ST_REL_SP_BY_D_A = Normal(0x6d),
+
+    ST_ABS_B = Normal(0x6e),
+    ST_REL_X_BY_IMM_B = Normal(0x6f),
+    ST_REL_Y_BY_IMM_B = Normal(0x70),
+    ST_REL_SP_BY_IMM_B = Normal(0x71),
+    ST_REL_X_BY_A_B = Normal(0x72),
+    ST_REL_X_BY_B_B = Normal(0x73),
+    ST_REL_X_BY_C_B = Normal(0x74),
+    ST_REL_X_BY_D_B = Normal(0x75),
+    ST_REL_Y_BY_A_B = Normal(0x76),
+    ST_REL_Y_BY_B_B = Normal(0x77),
+    ST_REL_Y_BY_C_B = Normal(0x78),
+    ST_REL_Y_BY_D_B = Normal(0x79),
+    ST_REL_SP_BY_A_B = Normal(0x7a),
+    ST_REL_SP_BY_B_B = Normal(0x7b),
+    ST_REL_SP_BY_C_B = Normal(0x7c),
+    ST_REL_SP_BY_D_B = Normal(0x7d),
+
+    ST_ABS_C = Normal(0x7e),
+    ST_REL_X_BY_IMM_C = Normal(0x7f),
+    ST_REL_Y_BY_IMM_C = Normal(0x80),
+    ST_REL_SP_BY_IMM_C = Normal(0x81),
+    ST_REL_X_BY_A_C = Normal(0x82),
+    ST_REL_X_BY_B_C = Normal(0x83),
+    ST_REL_X_BY_C_C = Normal(0x84),
+    ST_REL_X_BY_D_C = Normal(0x85),
+    ST_REL_Y_BY_A_C = Normal(0x86),
+    ST_REL_Y_BY_B_C = Normal(0x87),
+    ST_REL_Y_BY_C_C = Normal(0x88),
+    ST_REL_Y_BY_D_C = Normal(0x89),
+    ST_REL_SP_BY_A_C = Normal(0x8a),
+    ST_REL_SP_BY_B_C = Normal(0x8b),
+    ST_REL_SP_BY_C_C = Normal(0x8c),
+    ST_REL_SP_BY_D_C = Normal(0x8d),
+
+    ST_ABS_D = Normal(0x8e),
+    ST_REL_X_BY_IMM_D = Normal(0x8f),
+    ST_REL_Y_BY_IMM_D = Normal(0x90),
+    ST_REL_SP_BY_IMM_D = Normal(0x91),
+    ST_REL_X_BY_A_D = Normal(0x92),
+    ST_REL_X_BY_B_D = Normal(0x93),
+    ST_REL_X_BY_C_D = Normal(0x94),
+    ST_REL_X_BY_D_D = Normal(0x95),
+    ST_REL_Y_BY_A_D = Normal(0x96),
+    ST_REL_Y_BY_B_D = Normal(0x97),
+    ST_REL_Y_BY_C_D = Normal(0x98),
+    ST_REL_Y_BY_D_D = Normal(0x99),
+    ST_REL_SP_BY_A_D = Normal(0x9a),
+    ST_REL_SP_BY_B_D = Normal(0x9b),
+    ST_REL_SP_BY_C_D = Normal(0x9c),
+    ST_REL_SP_BY_D_D = Normal(0x9d),
+
+    IN_A_PORT = Normal(0x9e),
+    IN_A_REL_X_BY_IMM = Normal(0x9f),
+    IN_A_REL_Y_BY_IMM = Normal(0xa0),
+    IN_A_REL_X_BY_A = Normal(0xa1),
+    IN_A_REL_X_BY_B = Normal(0xa2),
+    IN_A_REL_X_BY_C = Normal(0xa3),
+    IN_A_REL_X_BY_D = Normal(0xa4),
+    IN_A_REL_Y_BY_A = Normal(0xa5),
+    IN_A_REL_Y_BY_B = Normal(0xa6),
+    IN_A_REL_Y_BY_C = Normal(0xa7),
+    IN_A_REL_Y_BY_D = Normal(0xa8),
+
+    IN_B_PORT = Normal(0xa9),
+    IN_B_REL_X_BY_IMM = Normal(0xaa),
+    IN_B_REL_Y_BY_IMM = Normal(0xab),
+    IN_B_REL_X_BY_A = Normal(0xac),
+    IN_B_REL_X_BY_B = Normal(0xad),
+    IN_B_REL_X_BY_C = Normal(0xae),
+    IN_B_REL_X_BY_D = Normal(0xaf),
+    IN_B_REL_Y_BY_A = Normal(0xb0),
+    IN_B_REL_Y_BY_B = Normal(0xb1),
+    IN_B_REL_Y_BY_C = Normal(0xb2),
+    IN_B_REL_Y_BY_D = Normal(0xb3),
+
+    IN_C_PORT = Normal(0xb4),
+    IN_C_REL_X_BY_IMM = Normal(0xb5),
+    IN_C_REL_Y_BY_IMM = Normal(0xb6),
+    IN_C_REL_X_BY_A = Normal(0xb7),
+    IN_C_REL_X_BY_B = Normal(0xb8),
+    IN_C_REL_X_BY_C = Normal(0xb9),
+    IN_C_REL_X_BY_D = Normal(0xba),
+    IN_C_REL_Y_BY_A = Normal(0xbb),
+    IN_C_REL_Y_BY_B = Normal(0xbc),
+    IN_C_REL_Y_BY_C = Normal(0xbd),
+    IN_C_REL_Y_BY_D = Normal(0xbe),
+
+    IN_D_PORT = Normal(0xbf),
+    IN_D_REL_X_BY_IMM = Normal(0xc0),
+    IN_D_REL_Y_BY_IMM = Normal(0xc1),
+    IN_D_REL_X_BY_A = Normal(0xc2),
+    IN_D_REL_X_BY_B = Normal(0xc3),
+    IN_D_REL_X_BY_C = Normal(0xc4),
+    IN_D_REL_X_BY_D = Normal(0xc5),
+    IN_D_REL_Y_BY_A = Normal(0xc6),
+    IN_D_REL_Y_BY_B = Normal(0xc7),
+    IN_D_REL_Y_BY_C = Normal(0xc8),
+    IN_D_REL_Y_BY_D = Normal(0xc9),
+
+    OUT_PORT_A = Normal(0xca),
+    OUT_REL_X_BY_IMM_A = Normal(0xcb),
+    OUT_REL_Y_BY_IMM_A = Normal(0xcc),
+    OUT_REL_X_BY_A_A = Normal(0xcd),
+    OUT_REL_X_BY_B_A = Normal(0xce),
+    OUT_REL_X_BY_C_A = Normal(0xcf),
+    OUT_REL_X_BY_D_A = Normal(0xd0),
+    OUT_REL_Y_BY_A_A = Normal(0xd1),
+    OUT_REL_Y_BY_B_A = Normal(0xd2),
+    OUT_REL_Y_BY_C_A = Normal(0xd3),
+    OUT_REL_Y_BY_D_A = Normal(0xd4),
+
+    OUT_PORT_B = Normal(0xd5),
+    OUT_REL_X_BY_IMM_B = Normal(0xd6),
+    OUT_REL_Y_BY_IMM_B = Normal(0xd7),
+    OUT_REL_X_BY_A_B = Normal(0xd8),
+    OUT_REL_X_BY_B_B = Normal(0xd9),
+    OUT_REL_X_BY_C_B = Normal(0xda),
+    OUT_REL_X_BY_D_B = Normal(0xdb),
+    OUT_REL_Y_BY_A_B = Normal(0xdc),
+    OUT_REL_Y_BY_B_B = Normal(0xdd),
+    OUT_REL_Y_BY_C_B = Normal(0xde),
+    OUT_REL_Y_BY_D_B = Normal(0xdf),
+
+    OUT_PORT_C = Normal(0xe0),
+    OUT_REL_X_BY_IMM_C = Normal(0xe1),
+    OUT_REL_Y_BY_IMM_C = Normal(0xe2),
+    OUT_REL_X_BY_A_C = Normal(0xe3),
+    OUT_REL_X_BY_B_C = Normal(0xe4),
+    OUT_REL_X_BY_C_C = Normal(0xe5),
+    OUT_REL_X_BY_D_C = Normal(0xe6),
+    OUT_REL_Y_BY_A_C = Normal(0xe7),
+    OUT_REL_Y_BY_B_C = Normal(0xe8),
+    OUT_REL_Y_BY_C_C = Normal(0xe9),
+    OUT_REL_Y_BY_D_C = Normal(0xea),
+
+    OUT_PORT_D = Normal(0xeb),
+    OUT_REL_X_BY_IMM_D = Normal(0xec),
+    OUT_REL_Y_BY_IMM_D = Normal(0xed),
+    OUT_REL_X_BY_A_D = Normal(0xee),
+    OUT_REL_X_BY_B_D = Normal(0xef),
+    OUT_REL_X_BY_C_D = Normal(0xf0),
+    OUT_REL_X_BY_D_D = Normal(0xf1),
+    OUT_REL_Y_BY_A_D = Normal(0xf2),
+    OUT_REL_Y_BY_B_D = Normal(0xf3),
+    OUT_REL_Y_BY_C_D = Normal(0xf4),
+    OUT_REL_Y_BY_D_D = Normal(0xf5),
+
+    MV_X_SP = Normal(0xf6),
+    MV_SP_X = Normal(0xf7),
+
+    MV_X_USP = Normal(0xf8),
+    MV_X_Y = Normal(0xf9),
+    MV_X_AB = Normal(0xfa),
+    MV_X_CD = Normal(0xfb),
+
+    MV_Y_X = Normal(0xfc),
+    MV_USP_X = Normal(0xfd),
+    MV_Y_AB = Normal(0xfe),
+    MV_Y_CD = Normal(0xff),
+
+    MV_AB_X = Extended(0x00),
+    MV_AB_Y = Extended(0x01),
+
+    MV_CD_X = Extended(0x02),
+    MV_CD_Y = Extended(0x03),
+
+    LD_X_IMM = Extended(0x04),
+    LD_Y_IMM = Extended(0x05),
+
+    LD_X_ABS = Extended(0x06),
+    LD_X_REL_X_BY_IMM = Extended(0x07),
+    LD_X_REL_Y_BY_IMM = Extended(0x08),
+    LD_X_REL_SP_BY_IMM = Extended(0x09),
+
+    LD_Y_ABS = Extended(0x0a),
+    LD_Y_REL_X_BY_IMM = Extended(0x0b),
+    LD_Y_REL_Y_BY_IMM = Extended(0x0c),
+    LD_Y_REL_SP_BY_IMM = Extended(0x0d),
+
+    ST_ABS_X = Extended(0x0e),
+    ST_REL_X_BY_IMM_X = Extended(0x0f),
+    ST_REL_Y_BY_IMM_X = Extended(0x10),
+    ST_REL_SP_BY_IMM_X = Extended(0x11),
+
+    ST_ABS_Y = Extended(0x12),
+    ST_REL_X_BY_IMM_Y = Extended(0x13),
+    ST_REL_Y_BY_IMM_Y = Extended(0x14),
+    ST_REL_SP_BY_IMM_Y = Extended(0x15),
+
+    LEA_X_BY_A = Extended(0x16),
+    LEA_X_BY_B = Extended(0x17),
+    LEA_X_BY_C = Extended(0x18),
+    LEA_X_BY_D = Extended(0x19),
+    LEA_X_BY_IMM = Extended(0x1a),
+
+    LEA_Y_BY_A = Extended(0x1b),
+    LEA_Y_BY_B = Extended(0x1c),
+    LEA_Y_BY_C = Extended(0x1d),
+    LEA_Y_BY_D = Extended(0x1e),
+    LEA_Y_BY_IMM = Extended(0x1f),
+
+    LEA_SP_BY_A = Extended(0x20),
+    LEA_SP_BY_B = Extended(0x21),
+    LEA_SP_BY_C = Extended(0x22),
+    LEA_SP_BY_D = Extended(0x23),
+    LEA_SP_BY_IMM = Extended(0x24),
+
+    INC_X = Extended(0x25),
+    INC_Y = Extended(0x26),
+
+    DEC_X = Extended(0x27),
+    DEC_Y = Extended(0x28),
+
+    ADDC_A_A = Extended(0x29),
+    ADDC_A_B = Extended(0x2a),
+    ADDC_A_C = Extended(0x2b),
+    ADDC_A_D = Extended(0x2c),
+
+    ADDC_B_A = Extended(0x2d),
+    ADDC_B_B = Extended(0x2e),
+    ADDC_B_C = Extended(0x2f),
+    ADDC_B_D = Extended(0x30),
+
+    ADDC_C_A = Extended(0x31),
+    ADDC_C_B = Extended(0x32),
+    ADDC_C_C = Extended(0x33),
+    ADDC_C_D = Extended(0x34),
+
+    ADDC_D_B = Extended(0x36),
+    ADDC_D_A = Extended(0x35),
+    ADDC_D_C = Extended(0x37),
+    ADDC_D_D = Extended(0x38),
+
+    ADDC_A_IMM = Extended(0x39),
+    ADDC_B_IMM = Extended(0x3a),
+    ADDC_C_IMM = Extended(0x3b),
+    ADDC_D_IMM = Extended(0x3c),
+
+    SUBB_A_A = Extended(0x3d),
+    SUBB_A_B = Extended(0x3e),
+    SUBB_A_C = Extended(0x3f),
+    SUBB_A_D = Extended(0x40),
+
+    SUBB_B_A = Extended(0x41),
+    SUBB_B_B = Extended(0x42),
+    SUBB_B_C = Extended(0x43),
+    SUBB_B_D = Extended(0x44),
+
+    SUBB_C_A = Extended(0x45),
+    SUBB_C_B = Extended(0x46),
+    SUBB_C_C = Extended(0x47),
+    SUBB_C_D = Extended(0x48),
+
+    SUBB_D_A = Extended(0x49),
+    SUBB_D_B = Extended(0x4a),
+    SUBB_D_C = Extended(0x4b),
+    SUBB_D_D = Extended(0x4c),
+
+    SUBB_A_IMM = Extended(0x4d),
+    SUBB_B_IMM = Extended(0x4e),
+    SUBB_C_IMM = Extended(0x4f),
+    SUBB_D_IMM = Extended(0x50),
+
+    AND_A_A = Extended(0x51),
+    AND_A_B = Extended(0x52),
+    AND_A_C = Extended(0x53),
+    AND_A_D = Extended(0x54),
+
+    AND_B_A = Extended(0x55),
+    AND_B_B = Extended(0x56),
+    AND_B_C = Extended(0x57),
+    AND_B_D = Extended(0x58),
+
+    AND_C_A = Extended(0x59),
+    AND_C_B = Extended(0x5a),
+    AND_C_C = Extended(0x5b),
+    AND_C_D = Extended(0x5c),
+
+    AND_D_A = Extended(0x5d),
+    AND_D_B = Extended(0x5e),
+    AND_D_C = Extended(0x5f),
+    AND_D_D = Extended(0x60),
+
+    AND_A_IMM = Extended(0x61),
+    AND_B_IMM = Extended(0x62),
+    AND_C_IMM = Extended(0x63),
+    AND_D_IMM = Extended(0x64),
+
+    OR_A_A = Extended(0x65),
+    OR_A_B = Extended(0x66),
+    OR_A_C = Extended(0x67),
+    OR_A_D = Extended(0x68),
+
+    OR_B_A = Extended(0x69),
+    OR_B_B = Extended(0x6a),
+    OR_B_C = Extended(0x6b),
+    OR_B_D = Extended(0x6c),
+
+    OR_C_A = Extended(0x6d),
+    OR_C_B = Extended(0x6e),
+    OR_C_C = Extended(0x6f),
+    OR_C_D = Extended(0x70),
+
+    OR_D_A = Extended(0x71),
+    OR_D_B = Extended(0x72),
+    OR_D_C = Extended(0x73),
+    OR_D_D = Extended(0x74),
+
+    OR_A_IMM = Extended(0x75),
+    OR_B_IMM = Extended(0x76),
+    OR_C_IMM = Extended(0x77),
+    OR_D_IMM = Extended(0x78),
+
+    XOR_A_A = Extended(0x79),
+    XOR_A_B = Extended(0x7a),
+    XOR_A_C = Extended(0x7b),
+    XOR_A_D = Extended(0x7c),
+
+    XOR_B_A = Extended(0x7d),
+    XOR_B_B = Extended(0x7e),
+    XOR_B_C = Extended(0x7f),
+    XOR_B_D = Extended(0x80),
+
+    XOR_C_A = Extended(0x81),
+    XOR_C_B = Extended(0x82),
+    XOR_C_C = Extended(0x83),
+    XOR_C_D = Extended(0x84),
+
+    XOR_D_A = Extended(0x85),
+    XOR_D_B = Extended(0x86),
+    XOR_D_C = Extended(0x87),
+    XOR_D_D = Extended(0x88),
+
+    XOR_A_IMM = Extended(0x89),
+    XOR_B_IMM = Extended(0x8a),
+    XOR_C_IMM = Extended(0x8b),
+    XOR_D_IMM = Extended(0x8c),
+
+    SHL_A = Extended(0x8d),
+    SHL_B = Extended(0x8e),
+    SHL_C = Extended(0x8f),
+    SHL_D = Extended(0x90),
+
+    SHR_A = Extended(0x91),
+    SHR_B = Extended(0x92),
+    SHR_C = Extended(0x93),
+    SHR_D = Extended(0x94),
+
+    ASR_A = Extended(0x95),
+    ASR_B = Extended(0x96),
+    ASR_C = Extended(0x97),
+    ASR_D = Extended(0x98),
+
+    NOT_A = Extended(0x99),
+    NOT_B = Extended(0x9a),
+    NOT_C = Extended(0x9b),
+    NOT_D = Extended(0x9c),
+
+    NEG_A = Extended(0x9d),
+    NEG_B = Extended(0x9e),
+    NEG_C = Extended(0x9f),
+    NEG_D = Extended(0xa0),
+
+    INC_A = Extended(0xa1),
+    INC_B = Extended(0xa2),
+    INC_C = Extended(0xa3),
+    INC_D = Extended(0xa4),
+
+    DEC_A = Extended(0xa5),
+    DEC_B = Extended(0xa6),
+    DEC_C = Extended(0xa7),
+    DEC_D = Extended(0xa8),
+
+    CMP_A_A = Extended(0xa9),
+    CMP_A_B = Extended(0xaa),
+    CMP_A_C = Extended(0xab),
+    CMP_A_D = Extended(0xac),
+
+    CMP_B_A = Extended(0xad),
+    CMP_B_B = Extended(0xae),
+    CMP_B_C = Extended(0xaf),
+    CMP_B_D = Extended(0xb0),
+
+    CMP_C_A = Extended(0xb1),
+    CMP_C_B = Extended(0xb2),
+    CMP_C_C = Extended(0xb3),
+    CMP_C_D = Extended(0xb4),
+
+    CMP_D_A = Extended(0xb5),
+    CMP_D_B = Extended(0xb6),
+    CMP_D_C = Extended(0xb7),
+    CMP_D_D = Extended(0xb8),
+
+    CMP_A_IMM = Extended(0xb9),
+    CMP_B_IMM = Extended(0xba),
+    CMP_C_IMM = Extended(0xbb),
+    CMP_D_IMM = Extended(0xbc),
+
+    TEST_A = Extended(0xbd),
+    TEST_B = Extended(0xbe),
+    TEST_C = Extended(0xbf),
+    TEST_D = Extended(0xc0),
+
+    PUSH_A = Extended(0xc1),
+    PUSH_B = Extended(0xc2),
+    PUSH_C = Extended(0xc3),
+    PUSH_D = Extended(0xc4),
+
+    PUSH_X = Extended(0xc5),
+    PUSH_Y = Extended(0xc6),
+
+    POP_A = Extended(0xc7),
+    POP_B = Extended(0xc8),
+    POP_C = Extended(0xc9),
+    POP_D = Extended(0xca),
+
+    POP_X = Extended(0xcb),
+    POP_Y = Extended(0xcc),
+
+    CALL_PC_REL = Extended(0xcd),
+    CALL_ABS = Extended(0xce),
+    CALL_X_REL_IMM = Extended(0xcf),
+    CALL_Y_REL_IMM = Extended(0xd0),
+    RET = Extended(0xd1),
+
+    SWI = Extended(0xd2),
+    RETI = Extended(0xd3),
+
+    JMP_PC_REL = Extended(0xd4),
+    JMP_ABS = Extended(0xd5),
+    JMP_X_REL_IMM = Extended(0xd6),
+    JMP_Y_REL_IMM = Extended(0xd7),
+
+    BR_EQ_PC_REL = Extended(0xd8),
+    BR_EQ_ABS = Extended(0xd9),
+    BR_EQ_X_REL_IMM = Extended(0xda),
+    BR_EQ_Y_REL_IMM = Extended(0xdb),
+
+    BR_NE_PC_REL = Extended(0xdc),
+    BR_NE_ABS = Extended(0xdd),
+    BR_NE_X_REL_IMM = Extended(0xde),
+    BR_NE_Y_REL_IMM = Extended(0xdf),
+
+    BR_LT_PC_REL = Extended(0xe0),
+    BR_LT_ABS = Extended(0xe1),
+    BR_LT_X_REL_IMM = Extended(0xe2),
+    BR_LT_Y_REL_IMM = Extended(0xe3),
+
+    BR_GT_PC_REL = Extended(0xe4),
+    BR_GT_ABS = Extended(0xe5),
+    BR_GT_X_REL_IMM = Extended(0xe6),
+    BR_GT_Y_REL_IMM = Extended(0xe7),
+
+    BR_LE_PC_REL = Extended(0xe8),
+    BR_LE_ABS = Extended(0xe9),
+    BR_LE_X_REL_IMM = Extended(0xea),
+    BR_LE_Y_REL_IMM = Extended(0xeb),
+
+    BR_GE_PC_REL = Extended(0xec),
+    BR_GE_ABS = Extended(0xed),
+    BR_GE_X_REL_IMM = Extended(0xee),
+    BR_GE_Y_REL_IMM = Extended(0xef),
+
+    BR_LTS_PC_REL = Extended(0xf0),
+    BR_LTS_ABS = Extended(0xf1),
+    BR_LTS_X_REL_IMM = Extended(0xf2),
+    BR_LTS_Y_REL_IMM = Extended(0xf3),
+
+    BR_GTS_PC_REL = Extended(0xf4),
+    BR_GTS_ABS = Extended(0xf5),
+    BR_GTS_X_REL_IMM = Extended(0xf6),
+    BR_GTS_Y_REL_IMM = Extended(0xf7),
+
+    BR_LES_PC_REL = Extended(0xf8),
+    BR_LES_ABS = Extended(0xf9),
+    BR_LES_X_REL_IMM = Extended(0xfa),
+    BR_LES_Y_REL_IMM = Extended(0xfb),
+
+    BR_GES_PC_REL = Extended(0xfc),
+    BR_GES_ABS = Extended(0xfd),
+    BR_GES_X_REL_IMM = Extended(0xfe),
+    BR_GES_Y_REL_IMM = Extended(0xff),
+}
@@ -0,0 +1,213 @@
+use isa::*;
+
+const POINTERS: [Pointer; 3] = [Pointer::X, Pointer::Y, Pointer::SP];
+const REGISTER8S: [Register8; 4] = [Register8::A, Register8::B, Register8::C, Register8::D];
+const REGISTER16S: [Register16; 2] = [Register16::X, Register16::Y];
+const PAIRS: [RegisterPair; 2] = [RegisterPair::Ab, RegisterPair::Cd];
+
+const ALU2_OPS: [Alu2Op; 6] = [
+    Alu2Op::Addc,
+    Alu2Op::Subb,
+    Alu2Op::And,
+    Alu2Op::Or,
+    Alu2Op::Xor,
+    Alu2Op::Cmp,
+];
+
+const ALU1_OPS: [Alu1Op; 8] = [
+    Alu1Op::Shl,
+    Alu1Op::Shr,
+    Alu1Op::Asr,
+    Alu1Op::Not,
+    Alu1Op::Neg,
+    Alu1Op::Inc,
+    Alu1Op::Dec,
+    Alu1Op::Test,
+];
+
+const CONDITIONS: [Condition; 11] = [
+    Condition::Always,
+    Condition::Equal,
+    Condition::NotEqual,
+    Condition::LessThan,
+    Condition::GreaterThan,
+    Condition::LessEqual,
+    Condition::GreaterEqual,
+    Condition::LessThanSigned,
+    Condition::GreaterThanSigned,
+    Condition::LessEqualSigned,
+    Condition::GreaterEqualSigned,
+];
+
+fn bytes() -> impl Iterator<Item = Byte> + Clone {
+    Byte::MIN..=Byte::MAX
+}
+
+fn addresses() -> impl Iterator<Item = Address> + Clone {
+    Address::MIN..=Address::MAX
+}
+
+fn memory8_modes() -> Vec<Memory8Mode> {
+    let mut modes: Vec<_> = addresses().map(Memory8Mode::Absolute).collect();
+    for pointer in POINTERS {
+        modes.extend(bytes().map(|offset| Memory8Mode::ConstantOffset(pointer, offset)));
+        modes.extend(REGISTER8S.map(|offset| Memory8Mode::RegisterOffset(pointer, offset)));
+    }
+    modes
+}
+
+fn memory16_modes() -> Vec<Memory16Mode> {
+    let mut modes: Vec<_> = addresses().map(Memory16Mode::Absolute).collect();
+    for pointer in POINTERS {
+        modes.extend(bytes().map(|offset| Memory16Mode::ConstantOffset(pointer, offset)));
+    }
+    modes
+}
+
+fn io_modes() -> Vec<IOMode> {
+    let mut modes: Vec<_> = bytes().map(IOMode::Port).collect();
+    for base in REGISTER16S {
+        modes.extend(bytes().map(|offset| IOMode::ConstantOffset(base, offset)));
+        modes.extend(REGISTER8S.map(|offset| IOMode::RegisterOffset(base, offset)));
+    }
+    modes
+}
+
+fn jump_modes() -> Vec<JumpMode> {
+    let mut modes: Vec<_> = bytes().map(JumpMode::Relative).collect();
+    modes.extend(addresses().map(JumpMode::Absolute));
+    for base in REGISTER16S {
+        modes.extend(bytes().map(|offset| JumpMode::Indirect(base, offset)));
+    }
+    modes
+}
+
+/// Every value of `Instruction`, including every operand value, except for
+/// moves of a pointer to itself, which are encoded as `nop`.
+fn instructions() -> Vec<Instruction> {
+    use Instruction as Inst;
+
+    let mut instructions = vec![
+        Inst::Nop,
+        Inst::SetCarry,
+        Inst::ClearCarry,
+        Inst::SetInterruptEnable,
+        Inst::ClearInterruptEnable,
+        Inst::SetBankEnable,
+        Inst::ClearBankEnable,
+        Inst::ReadBankRegister,
+        Inst::WriteBankRegister,
+        Inst::ReadStackPointer,
+        Inst::WriteStackPointer,
+        Inst::ReadUserStackPointer,
+        Inst::WriteUserStackPointer,
+        Inst::Ret,
+        Inst::Swi,
+        Inst::Reti,
+    ];
+
+    let memory8_modes = memory8_modes();
+    let memory16_modes = memory16_modes();
+    let io_modes = io_modes();
+    let jump_modes = jump_modes();
+
+    for register in REGISTER8S {
+        for src in REGISTER8S {
+            instructions.push(Inst::Move8(register, src));
+        }
+        instructions.extend(bytes().map(|value| Inst::Load8Immediate(register, value)));
+        for &mode in &memory8_modes {
+            instructions.push(Inst::Load8(register, mode));
+            instructions.push(Inst::Store8(mode, register));
+        }
+        for &mode in &io_modes {
+            instructions.push(Inst::In(register, mode));
+            instructions.push(Inst::Out(mode, register));
+        }
+        for op in ALU2_OPS {
+            instructions
+                .extend(REGISTER8S.map(|src| Inst::Alu2(op, register, Alu2OpMode::Register(src))));
+            instructions
+                .extend(bytes().map(|value| Inst::Alu2(op, register, Alu2OpMode::Constant(value))));
+        }
+        instructions.extend(ALU1_OPS.map(|op| Inst::Alu1(op, register)));
+        instructions.push(Inst::Push8(register));
+        instructions.push(Inst::Pop8(register));
+    }
+
+    for register in REGISTER16S {
+        for src in REGISTER16S.into_iter().filter(|&src| src != register) {
+            instructions.push(Inst::Move16(register, src));
+        }
+        for pair in PAIRS {
+            instructions.push(Inst::Move16FromPair(register, pair));
+            instructions.push(Inst::Move16ToPair(pair, register));
+        }
+        instructions.extend(addresses().map(|value| Inst::Load16Immediate(register, value)));
+        for &mode in &memory16_modes {
+            instructions.push(Inst::Load16(register, mode));
+            instructions.push(Inst::Store16(mode, register));
+        }
+        instructions.push(Inst::Inc16(register));
+        instructions.push(Inst::Dec16(register));
+        instructions.push(Inst::Push16(register));
+        instructions.push(Inst::Pop16(register));
+    }
+
+    for pointer in POINTERS {
+        instructions.extend(REGISTER8S.map(|offset| Inst::Lea(pointer, LeaMode::Register(offset))));
+        instructions.extend(bytes().map(|offset| Inst::Lea(pointer, LeaMode::Constant(offset))));
+    }
+
+    for &mode in &jump_modes {
+        instructions.push(Inst::Call(mode));
+        instructions.extend(CONDITIONS.map(|condition| Inst::Jmp(condition, mode)));
+    }
+
+    instructions
+}
+
+#[test]
+fn decode_inverts_encode() {
+    for instruction in instructions() {
+        let bytes = encode(instruction).to_vec();
+        let mut stream = bytes.iter().copied();
+
+        assert_eq!(
+            decode(ExtensionMode::Normal, &mut stream),
+            Some(instruction),
+            "{:?} encoded as {:02x?}",
+            instruction,
+            bytes,
+        );
+        assert_eq!(
+            stream.next(),
+            None,
+            "{:?} encoded as {:02x?} but decoding left bytes unread",
+            instruction,
+            bytes,
+        );
+    }
+}
+
+#[test]
+fn size_is_the_length_of_the_encoding() {
+    for instruction in instructions() {
+        assert_eq!(
+            instruction.size(),
+            encode(instruction).to_vec().len(),
+            "{:?}",
+            instruction,
+        );
+    }
+}
+
+#[test]
+fn moving_a_pointer_to_itself_is_encoded_as_nop() {
+    for register in REGISTER16S {
+        assert_eq!(
+            encode(Instruction::Move16(register, register)),
+            encode(Instruction::Nop)
+        );
+    }
+}
@@ -1,45 +1,127 @@
-use modular_bitfield::{bitfield, BitfieldSpecifier};
+// The accessors `#[bitfield]` generates wrap field types in parentheses.
+#![allow(unused_parens)]
 
-#[derive(BitfieldSpecifier)]
-#[bits = 4]
+pub mod lockstep;
+mod processor;
+mod rom;
+mod state;
+
+pub use processor::*;
+pub use rom::*;
+pub use state::*;
+
+use modular_bitfield::{bitfield, specifiers::B5, BitfieldSpecifier};
+
+/// The device driving the 8-bit data bus.
+#[derive(BitfieldSpecifier, Clone, Copy, Debug, PartialEq, Eq)]
+#[bits = 5]
 pub enum DataBusAssert {
+    None,
     A,
     B,
     C,
     D,
     Memory, // This is a Memory READ
+    Io,     // This is an I/O READ
     Alu,
     Bank,
     Status,
     Temp1,
     Temp2,
+    PcLow,
+    PcHigh,
+    SpLow,
+    SpHigh,
+    XLow,
+    XHigh,
+    YLow,
+    YHigh,
+    /// The address of the instruction being executed, latched when its first
+    /// byte was fetched; a fault returns to it.
+    InstructionLow,
+    InstructionHigh,
+    /// The user's stack pointer, whatever the privilege level, for `mv x, usp`.
+    UserSpLow,
+    UserSpHigh,
 }
 
-#[derive(BitfieldSpecifier)]
-#[bits = 4]
+/// The device latching the data bus at the end of the cycle.
+#[derive(BitfieldSpecifier, Clone, Copy, Debug, PartialEq, Eq)]
+#[bits = 5]
 pub enum DataBusLoad {
+    None,
     A,
     B,
     C,
     D,
     Memory, // This is a Memory WRITE
+    Io,     // This is an I/O WRITE
     Bank,
     Status,
     Temp1,
     Temp2,
+    PcLow,
+    PcHigh,
+    SpLow,
+    SpHigh,
+    XLow,
+    XHigh,
+    YLow,
+    YHigh,
+    Opcode,
+    /// The user's stack pointer, whatever the privilege level, for `mv usp, x`.
+    UserSpLow,
+    UserSpHigh,
 }
 
-#[derive(BitfieldSpecifier)]
-#[bits = 3]
+/// The source of the 16-bit address bus, which addresses memory and I/O and
+/// feeds the address unit.
+#[derive(BitfieldSpecifier, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[bits = 4]
 pub enum AddressBusAssert {
+    None,
     ProgramCounter,
     StackPointer,
     X,
     Y,
+    /// `Temp2:Temp1`, an address formed by the microcode.
+    Temp,
+    /// `Temp1` alone, the port number of `in` and `out`.
     IOAddress,
+    NmiVector,
+    IrqVector,
+    SwiVector,
+    FaultVector,
 }
 
-#[derive(BitfieldSpecifier)]
+/// What the address unit does to the address bus before it is latched by
+/// the `AddressBusLoad`.
+#[derive(BitfieldSpecifier, Clone, Copy, Debug, PartialEq, Eq)]
+#[bits = 2]
+pub enum AddressOperation {
+    Nop,
+    Increment,
+    Decrement,
+    /// Adds the data bus, zero-extended.
+    Offset,
+}
+
+/// The 16-bit register latching the address unit's output at the end of the
+/// cycle.
+#[derive(BitfieldSpecifier, Clone, Copy, Debug, PartialEq, Eq)]
+#[bits = 3]
+pub enum AddressBusLoad {
+    None,
+    ProgramCounter,
+    StackPointer,
+    X,
+    Y,
+    Temp,
+}
+
+/// The ALU's operation on the operands selected by the `TransferBusAssert`.
+/// The one-operand operations use the left operand.
+#[derive(BitfieldSpecifier, Clone, Copy, Debug, PartialEq, Eq)]
 #[bits = 4]
 pub enum AluOperation {
     Nop,
@@ -52,11 +134,19 @@ pub enum AluOperation {
     Shl,
     Shr,
     Asr,
+    Neg,
+    Inc,
+    Dec,
+    /// `Sub` with no borrow in.
+    Cmp,
 }
 
-#[derive(BitfieldSpecifier)]
+/// The registers driving the ALU's left and right operands.
+#[allow(non_camel_case_types)]
+#[derive(BitfieldSpecifier, Clone, Copy, Debug, PartialEq, Eq)]
 #[bits = 5]
 pub enum TransferBusAssert {
+    None,
     A_A,
     A_B,
     A_C,
@@ -65,13 +155,74 @@ pub enum TransferBusAssert {
     B_B,
     B_C,
     B_D,
+    C_A,
+    C_B,
+    C_C,
+    C_D,
+    D_A,
+    D_B,
+    D_C,
+    D_D,
+    A_T1,
+    B_T1,
+    C_T1,
+    D_T1,
 }
 
-#[bitfield(bits = 32, filled = false)]
+/// How the status register changes at the end of the cycle.
+#[derive(BitfieldSpecifier, Clone, Copy, Debug, PartialEq, Eq)]
+#[bits = 4]
+pub enum FlagOperation {
+    Nop,
+    /// Latches C, Z, V and N from the ALU.
+    Arithmetic,
+    SetCarry,
+    ClearCarry,
+    SetInterruptEnable,
+    ClearInterruptEnable,
+    SetBankEnable,
+    ClearBankEnable,
+    /// Disables IRQs and bank enable, and enters kernel mode.
+    Interrupt,
+    /// As `Interrupt`, also marking an NMI as being serviced.
+    Nmi,
+    /// Loads the status register from `Temp1`.
+    Restore,
+}
+
+/// How the sequencer advances at the end of the cycle.
+#[derive(BitfieldSpecifier, Clone, Copy, Debug, PartialEq, Eq)]
+#[bits = 2]
+pub enum Sequencer {
+    Next,
+    /// Moves to the next step, decoding the opcode register as an extended
+    /// opcode.
+    Extend,
+    /// Ends the instruction; the next cycle fetches an opcode.
+    Reset,
+}
+
+#[bitfield(bits = 40)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct ControlWord {
-    data_assert: DataBusAssert,
-    data_load: DataBusLoad,
-    address_assert: AddressBusAssert,
-    alu_op: AluOperation,
-    xfer_assert: TransferBusAssert,
+    pub data_assert: DataBusAssert,
+    pub data_load: DataBusLoad,
+    pub address_assert: AddressBusAssert,
+    pub alu_op: AluOperation,
+    pub xfer_assert: TransferBusAssert,
+    pub address_op: AddressOperation,
+    pub address_load: AddressBusLoad,
+    pub flags: FlagOperation,
+    /// Makes the cycle's bus access with kernel privilege, on the kernel's
+    /// stack pointer and in bank 0, as when writing an interrupt frame.
+    pub kernel: bool,
+    pub sequencer: Sequencer,
+    #[skip]
+    pub __: B5,
+}
+
+impl Default for ControlWord {
+    fn default() -> Self {
+        Self::new()
+    }
 }
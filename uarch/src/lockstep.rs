@@ -0,0 +1,304 @@
+//! Runs the microcoded `Processor` alongside the instruction-level
+//! `arch::CpuState`, each on its own copy of a bus, and reports the first
+//! instruction after which the two disagree.
+
+use std::fmt;
+
+use arch::{
+    Address, Architectural16, Architectural8, Bus, BusResult, Byte, CpuState, MemoryAddressKind,
+    PhysicalAddress, PrivilegeLevel, StopReason,
+};
+
+use crate::Processor;
+
+/// A bus that remembers every write made through it, in order.
+pub struct Recorder<B> {
+    bus: B,
+    writes: Vec<Write>,
+}
+
+/// A write made over the bus, to a linear address.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Write {
+    Memory(usize, Byte),
+    Io(usize, Byte),
+}
+
+impl<B> Recorder<B> {
+    pub fn new(bus: B) -> Self {
+        Self {
+            bus,
+            writes: Vec::new(),
+        }
+    }
+
+    pub fn inner(&self) -> &B {
+        &self.bus
+    }
+
+    pub fn inner_mut(&mut self) -> &mut B {
+        &mut self.bus
+    }
+
+    fn take_writes(&mut self) -> Vec<Write> {
+        std::mem::take(&mut self.writes)
+    }
+}
+
+impl<B: Bus> Bus for Recorder<B> {
+    fn memory_read(
+        &self,
+        privilege: PrivilegeLevel,
+        kind: MemoryAddressKind,
+        address: PhysicalAddress,
+    ) -> Byte {
+        self.bus.memory_read(privilege, kind, address)
+    }
+
+    fn memory_write(
+        &mut self,
+        privilege: PrivilegeLevel,
+        kind: MemoryAddressKind,
+        address: PhysicalAddress,
+        data: Byte,
+    ) {
+        self.writes.push(Write::Memory(address.linear(), data));
+        self.bus.memory_write(privilege, kind, address, data);
+    }
+
+    fn io_read(&mut self, privilege: PrivilegeLevel, address: PhysicalAddress) -> BusResult<Byte> {
+        self.bus.io_read(privilege, address)
+    }
+
+    fn io_write(
+        &mut self,
+        privilege: PrivilegeLevel,
+        address: PhysicalAddress,
+        data: Byte,
+    ) -> BusResult<()> {
+        self.writes.push(Write::Io(address.linear(), data));
+        self.bus.io_write(privilege, address, data)
+    }
+
+    fn clock(&mut self) {
+        self.bus.clock();
+    }
+
+    fn is_rst_active(&self) -> bool {
+        self.bus.is_rst_active()
+    }
+
+    fn is_nmi_active(&mut self) -> bool {
+        self.bus.is_nmi_active()
+    }
+
+    fn is_irq_active(&self) -> bool {
+        self.bus.is_irq_active()
+    }
+
+    fn acknowledge_irq(&mut self) {
+        self.bus.acknowledge_irq();
+    }
+
+    fn is_req_active(&self) -> bool {
+        self.bus.is_req_active()
+    }
+}
+
+/// One piece of state on which the two processors disagree.
+#[derive(Debug, PartialEq)]
+pub enum Mismatch {
+    /// A register, the status byte or the bank register, by name.
+    Register(&'static str, u16, u16),
+    Cycles(usize, usize),
+    Stop(Option<StopReason>, Option<StopReason>),
+    Writes(Vec<Write>, Vec<Write>),
+}
+
+/// The reference and microcoded processors disagreed after running the
+/// instruction at `address`. Each mismatch lists the reference's value first.
+#[derive(Debug)]
+pub struct Divergence {
+    pub address: Address,
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "diverged at {:#06X}:", self.address)?;
+
+        for mismatch in &self.mismatches {
+            match mismatch {
+                Mismatch::Register(name, reference, core) => {
+                    write!(f, " {} {:#X} != {:#X};", name, reference, core)?
+                }
+                Mismatch::Cycles(reference, core) => {
+                    write!(f, " cycles {} != {};", reference, core)?
+                }
+                Mismatch::Stop(reference, core) => {
+                    write!(f, " stop {:?} != {:?};", reference, core)?
+                }
+                Mismatch::Writes(reference, core) => {
+                    write!(f, " writes {:?} != {:?};", reference, core)?
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for Divergence {}
+
+/// The reference and microcoded processors, stepped an instruction at a time.
+pub struct Lockstep<B> {
+    reference: CpuState,
+    reference_bus: Recorder<B>,
+    core: Processor,
+    core_bus: Recorder<B>,
+}
+
+impl<B: Bus> Lockstep<B> {
+    /// Starts both processors from reset. The buses should start out alike.
+    pub fn new(reference_bus: B, core_bus: B) -> Self {
+        Self {
+            reference: CpuState::new(),
+            reference_bus: Recorder::new(reference_bus),
+            core: Processor::new(),
+            core_bus: Recorder::new(core_bus),
+        }
+    }
+
+    pub fn reference(&self) -> &CpuState {
+        &self.reference
+    }
+
+    pub fn reference_mut(&mut self) -> &mut CpuState {
+        &mut self.reference
+    }
+
+    pub fn core(&self) -> &Processor {
+        &self.core
+    }
+
+    pub fn core_mut(&mut self) -> &mut Processor {
+        &mut self.core
+    }
+
+    pub fn reference_bus(&mut self) -> &mut B {
+        self.reference_bus.inner_mut()
+    }
+
+    pub fn core_bus(&mut self) -> &mut B {
+        self.core_bus.inner_mut()
+    }
+
+    /// Runs one instruction, interrupt entry, reset or stall on each
+    /// processor, then compares their registers, the cycles taken, why they
+    /// stopped and the writes they made.
+    pub fn step(&mut self) -> Result<Option<StopReason>, Divergence> {
+        self.compare().map(|(_, stop)| stop)
+    }
+
+    /// Steps both processors until at least `cycles` cycles have elapsed or
+    /// they stop, failing at the first divergence.
+    pub fn run(&mut self, cycles: usize) -> Result<StopReason, Divergence> {
+        let mut elapsed = 0;
+
+        while elapsed < cycles {
+            let (taken, stop) = self.compare()?;
+            elapsed += taken;
+
+            if let Some(reason) = stop {
+                return Ok(reason);
+            }
+        }
+
+        Ok(StopReason::CycleLimit)
+    }
+
+    fn compare(&mut self) -> Result<(usize, Option<StopReason>), Divergence> {
+        let address = self.reference[Architectural16::PC];
+
+        let (_, reference_stop, reference_cycles) = self.reference.run(&mut self.reference_bus, 1);
+        let reference_stop = match reference_stop {
+            StopReason::CycleLimit => None,
+            reason => Some(reason),
+        };
+        let (core_cycles, core_stop) = self.core.step(&mut self.core_bus);
+
+        let mut mismatches = Vec::new();
+
+        let (reference, core) = (&self.reference, &self.core);
+        let registers = [
+            (
+                "A",
+                reference[Architectural8::A] as u16,
+                core[Architectural8::A] as u16,
+            ),
+            (
+                "B",
+                reference[Architectural8::B] as u16,
+                core[Architectural8::B] as u16,
+            ),
+            (
+                "C",
+                reference[Architectural8::C] as u16,
+                core[Architectural8::C] as u16,
+            ),
+            (
+                "D",
+                reference[Architectural8::D] as u16,
+                core[Architectural8::D] as u16,
+            ),
+            (
+                "PC",
+                reference[Architectural16::PC],
+                core[Architectural16::PC],
+            ),
+            (
+                "SP",
+                reference[Architectural16::SP],
+                core[Architectural16::SP],
+            ),
+            ("banked SP", reference.banked_sp(), core.banked_sp()),
+            ("X", reference[Architectural16::X], core[Architectural16::X]),
+            ("Y", reference[Architectural16::Y], core[Architectural16::Y]),
+            (
+                "status",
+                reference.status().to_byte() as u16,
+                core.status().to_byte() as u16,
+            ),
+            ("BR", reference.br().as_inner() as u16, core.br() as u16),
+        ];
+        for (name, reference, core) in registers {
+            if reference != core {
+                mismatches.push(Mismatch::Register(name, reference, core));
+            }
+        }
+
+        if reference_cycles != core_cycles {
+            mismatches.push(Mismatch::Cycles(reference_cycles, core_cycles));
+        }
+
+        if reference_stop != core_stop {
+            mismatches.push(Mismatch::Stop(reference_stop, core_stop));
+        }
+
+        let writes = (
+            self.reference_bus.take_writes(),
+            self.core_bus.take_writes(),
+        );
+        if writes.0 != writes.1 {
+            mismatches.push(Mismatch::Writes(writes.0, writes.1));
+        }
+
+        match mismatches.is_empty() {
+            true => Ok((core_cycles, core_stop)),
+            false => Err(Divergence {
+                address,
+                mismatches,
+            }),
+        }
+    }
+}
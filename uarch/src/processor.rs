@@ -0,0 +1,513 @@
+use arch::{
+    Address, Architectural16, Architectural8, Bus, BusResult, Byte, EnvironmentAction,
+    MemoryAddressKind, Nibble, PhysicalAddress, PrivilegeLevel, Status, StopReason, Word,
+};
+use isa::is_privileged_io;
+
+use crate::*;
+
+const CARRY: Byte = 0b0000_0001;
+const ZERO: Byte = 0b0000_0010;
+const OVERFLOW: Byte = 0b0000_0100;
+const NEGATIVE: Byte = 0b0000_1000;
+const IRQ_ENABLE: Byte = 0b0001_0000;
+const BANK_ENABLE: Byte = 0b0010_0000;
+const USER: Byte = 0b0100_0000;
+const NMI_ACTIVE: Byte = 0b1000_0000;
+
+const NMI_VECTOR: Address = 0x0004;
+const IRQ_VECTOR: Address = 0x0008;
+const SWI_VECTOR: Address = 0x000C;
+const FAULT_VECTOR: Address = 0x0010;
+
+/// The processor as built from its datapath and sequencer, stepping one
+/// clock cycle at a time through the control words of `control_rom`.
+#[derive(Default)]
+pub struct Processor {
+    a: Byte,
+    b: Byte,
+    c: Byte,
+    d: Byte,
+    temp1: Byte,
+    temp2: Byte,
+    program_counter: Address,
+    /// Each privilege level has its own stack pointer; a step uses the one of
+    /// the privilege it runs with.
+    user_stack_pointer: Address,
+    kernel_stack_pointer: Address,
+    x: Address,
+    y: Address,
+    /// The address the current instruction was fetched from.
+    instruction_address: Address,
+    status: Byte,
+    bank_register: Byte,
+    opcode: Byte,
+    extended: bool,
+    interrupt: Interrupt,
+    step: u8,
+}
+
+impl Processor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    pub fn status(&self) -> Status {
+        Status::from_byte(self.status)
+    }
+
+    pub fn br(&self) -> Byte {
+        self.bank_register
+    }
+
+    /// The stack pointer of the privilege level that is not running.
+    pub fn banked_sp(&self) -> Address {
+        match self.status & USER != 0 {
+            true => self.kernel_stack_pointer,
+            false => self.user_stack_pointer,
+        }
+    }
+
+    /// The control ROM address of the next cycle.
+    pub fn address(&self) -> MicroAddress {
+        let state = StateVector::new()
+            .with_opcode(self.opcode)
+            .with_extended(self.extended)
+            .with_interrupt(self.interrupt)
+            .with_carry(self.status & CARRY != 0)
+            .with_zero(self.status & ZERO != 0)
+            .with_overflow(self.status & OVERFLOW != 0)
+            .with_negative(self.status & NEGATIVE != 0)
+            .with_user(self.status & USER != 0)
+            .with_kernel_address(is_privileged_io(self.temp()));
+
+        MicroAddress::new().with_step(self.step).with_state(state)
+    }
+
+    /// Runs until at least `cycles` clock cycles have elapsed, or the program
+    /// halts or reaches a breakpoint, as `arch::CpuState::run` does. Returns
+    /// why it stopped and the number of cycles that elapsed.
+    pub fn run<B: Bus>(&mut self, bus: &mut B, cycles: usize) -> (StopReason, usize) {
+        let mut elapsed = 0;
+
+        while elapsed < cycles {
+            let (taken, stop) = self.step(bus);
+            elapsed += taken;
+
+            if let Some(reason) = stop {
+                return (reason, elapsed);
+            }
+        }
+
+        (StopReason::CycleLimit, elapsed)
+    }
+
+    /// Runs cycles until the sequencer is back at the start of an
+    /// instruction: through one instruction or interrupt entry, or a single
+    /// reset or bus stall cycle. Returns the cycles taken and why the run
+    /// must stop, if it must.
+    pub fn step<B: Bus>(&mut self, bus: &mut B) -> (usize, Option<StopReason>) {
+        let mut cycles = 0;
+        let mut stop = None;
+
+        loop {
+            let action = self.cycle(bus);
+            stop = stop.or(action);
+            cycles += 1;
+
+            if self.step == 0 {
+                return (cycles, stop);
+            }
+        }
+    }
+
+    /// Runs one clock cycle, driving the control word for the current state
+    /// and then clocking the bus.
+    pub fn cycle<B: Bus>(&mut self, bus: &mut B) -> Option<StopReason> {
+        if self.step == 0 && !self.sample_lines(bus) {
+            bus.clock();
+            return None;
+        }
+
+        let word = control_rom()[self.address().index()];
+        let stop = self.execute(word, bus);
+        bus.clock();
+
+        stop
+    }
+
+    /// Samples the reset and interrupt lines before an instruction, in the
+    /// same order as `arch`, latching an interrupt to enter in its place.
+    /// Returns false if the cycle goes to a reset or a bus stall instead.
+    fn sample_lines<B: Bus>(&mut self, bus: &mut B) -> bool {
+        if bus.is_rst_active() {
+            self.reset();
+            return false;
+        }
+
+        if bus.is_nmi_active() {
+            if self.status & NMI_ACTIVE != 0 {
+                self.reset();
+                return false;
+            }
+            self.interrupt = Interrupt::Nmi;
+        } else if bus.is_irq_active() {
+            // A masked IRQ lets the instruction run, even over a bus request.
+            if self.status & IRQ_ENABLE != 0 {
+                self.interrupt = Interrupt::Irq;
+                bus.acknowledge_irq();
+            }
+        } else if bus.is_req_active() {
+            return false;
+        }
+
+        self.instruction_address = self.program_counter;
+        true
+    }
+
+    /// The privilege level the status register holds.
+    fn privilege(&self) -> PrivilegeLevel {
+        match self.status & USER != 0 {
+            true => PrivilegeLevel::User,
+            false => PrivilegeLevel::Kernel,
+        }
+    }
+
+    fn execute<B: Bus>(&mut self, word: ControlWord, bus: &mut B) -> Option<StopReason> {
+        let privilege = match word.kernel() {
+            true => PrivilegeLevel::Kernel,
+            false => self.privilege(),
+        };
+        let kind = match word.address_assert() {
+            AddressBusAssert::ProgramCounter => MemoryAddressKind::Code,
+            _ => MemoryAddressKind::Data,
+        };
+        let address = self.address_bus(word.address_assert(), privilege);
+        let physical = PhysicalAddress::new(self.bank(privilege, kind, word.kernel()), address);
+
+        let (left, right) = self.operands(word.xfer_assert());
+        let (result, carry, overflow) = alu(word.alu_op(), left, right, self.status & CARRY != 0);
+
+        let mut action = None;
+
+        // An I/O read that makes the environment act leaves nothing to load.
+        let data = match word.data_assert() {
+            // An undriven bus reads high.
+            DataBusAssert::None => Some(0xFF),
+            DataBusAssert::A => Some(self.a),
+            DataBusAssert::B => Some(self.b),
+            DataBusAssert::C => Some(self.c),
+            DataBusAssert::D => Some(self.d),
+            DataBusAssert::Memory => Some(bus.memory_read(privilege, kind, physical)),
+            DataBusAssert::Io => match bus.io_read(privilege, physical) {
+                BusResult::Data(data) => Some(data),
+                BusResult::Action(taken) => {
+                    action = Some(taken);
+                    None
+                }
+            },
+            DataBusAssert::Alu => Some(result),
+            DataBusAssert::Bank => Some(self.bank_register),
+            DataBusAssert::Status => Some(self.status),
+            DataBusAssert::Temp1 => Some(self.temp1),
+            DataBusAssert::Temp2 => Some(self.temp2),
+            DataBusAssert::PcLow => Some(low(self.program_counter)),
+            DataBusAssert::PcHigh => Some(high(self.program_counter)),
+            DataBusAssert::SpLow => Some(low(*self.stack_pointer(privilege))),
+            DataBusAssert::SpHigh => Some(high(*self.stack_pointer(privilege))),
+            DataBusAssert::XLow => Some(low(self.x)),
+            DataBusAssert::XHigh => Some(high(self.x)),
+            DataBusAssert::YLow => Some(low(self.y)),
+            DataBusAssert::YHigh => Some(high(self.y)),
+            DataBusAssert::InstructionLow => Some(low(self.instruction_address)),
+            DataBusAssert::InstructionHigh => Some(high(self.instruction_address)),
+            DataBusAssert::UserSpLow => Some(low(self.user_stack_pointer)),
+            DataBusAssert::UserSpHigh => Some(high(self.user_stack_pointer)),
+        };
+
+        if let Some(data) = data {
+            match word.data_load() {
+                DataBusLoad::None => {}
+                DataBusLoad::A => self.a = data,
+                DataBusLoad::B => self.b = data,
+                DataBusLoad::C => self.c = data,
+                DataBusLoad::D => self.d = data,
+                DataBusLoad::Memory => bus.memory_write(privilege, kind, physical, data),
+                DataBusLoad::Io => {
+                    if let BusResult::Action(taken) = bus.io_write(privilege, physical, data) {
+                        action = Some(taken);
+                    }
+                }
+                DataBusLoad::Bank => self.bank_register = data & 0b0000_1111,
+                DataBusLoad::Status => self.status = data,
+                DataBusLoad::Temp1 => self.temp1 = data,
+                DataBusLoad::Temp2 => self.temp2 = data,
+                DataBusLoad::PcLow => set_low(&mut self.program_counter, data),
+                DataBusLoad::PcHigh => set_high(&mut self.program_counter, data),
+                DataBusLoad::SpLow => set_low(self.stack_pointer_mut(privilege), data),
+                DataBusLoad::SpHigh => set_high(self.stack_pointer_mut(privilege), data),
+                DataBusLoad::XLow => set_low(&mut self.x, data),
+                DataBusLoad::XHigh => set_high(&mut self.x, data),
+                DataBusLoad::YLow => set_low(&mut self.y, data),
+                DataBusLoad::YHigh => set_high(&mut self.y, data),
+                DataBusLoad::Opcode => self.opcode = data,
+                DataBusLoad::UserSpLow => set_low(&mut self.user_stack_pointer, data),
+                DataBusLoad::UserSpHigh => set_high(&mut self.user_stack_pointer, data),
+            }
+        }
+
+        let next_address = match word.address_op() {
+            AddressOperation::Nop => address,
+            AddressOperation::Increment => address.wrapping_add(1),
+            AddressOperation::Decrement => address.wrapping_sub(1),
+            AddressOperation::Offset => address.wrapping_add(data.unwrap_or(0) as Address),
+        };
+        match word.address_load() {
+            AddressBusLoad::None => {}
+            AddressBusLoad::ProgramCounter => self.program_counter = next_address,
+            AddressBusLoad::StackPointer => *self.stack_pointer_mut(privilege) = next_address,
+            AddressBusLoad::X => self.x = next_address,
+            AddressBusLoad::Y => self.y = next_address,
+            AddressBusLoad::Temp => {
+                self.temp1 = low(next_address);
+                self.temp2 = high(next_address);
+            }
+        }
+
+        match word.flags() {
+            FlagOperation::Nop => {}
+            FlagOperation::Arithmetic => {
+                let mut flags = 0;
+                if carry {
+                    flags |= CARRY;
+                }
+                if result == 0 {
+                    flags |= ZERO;
+                }
+                if overflow {
+                    flags |= OVERFLOW;
+                }
+                if result & 0x80 != 0 {
+                    flags |= NEGATIVE;
+                }
+                self.status = (self.status & !(CARRY | ZERO | OVERFLOW | NEGATIVE)) | flags;
+            }
+            FlagOperation::SetCarry => self.status |= CARRY,
+            FlagOperation::ClearCarry => self.status &= !CARRY,
+            FlagOperation::SetInterruptEnable => self.status |= IRQ_ENABLE,
+            FlagOperation::ClearInterruptEnable => self.status &= !IRQ_ENABLE,
+            FlagOperation::SetBankEnable => self.status |= BANK_ENABLE,
+            FlagOperation::ClearBankEnable => self.status &= !BANK_ENABLE,
+            FlagOperation::Interrupt => self.status &= !(IRQ_ENABLE | BANK_ENABLE | USER),
+            FlagOperation::Nmi => {
+                self.status = (self.status & !(IRQ_ENABLE | BANK_ENABLE | USER)) | NMI_ACTIVE
+            }
+            FlagOperation::Restore => self.status = self.temp1,
+        }
+
+        match word.sequencer() {
+            Sequencer::Next => self.step += 1,
+            Sequencer::Extend => {
+                self.extended = true;
+                self.step += 1;
+            }
+            Sequencer::Reset => {
+                self.extended = false;
+                self.interrupt = Interrupt::None;
+                self.step = 0;
+            }
+        }
+
+        match action? {
+            EnvironmentAction::Halt(code) => Some(StopReason::Halt(code)),
+            EnvironmentAction::Break => Some(StopReason::Breakpoint),
+        }
+    }
+
+    fn temp(&self) -> Address {
+        (self.temp2 as Address) << 8 | self.temp1 as Address
+    }
+
+    fn stack_pointer(&self, privilege: PrivilegeLevel) -> &Address {
+        match privilege {
+            PrivilegeLevel::User => &self.user_stack_pointer,
+            PrivilegeLevel::Kernel => &self.kernel_stack_pointer,
+        }
+    }
+
+    fn stack_pointer_mut(&mut self, privilege: PrivilegeLevel) -> &mut Address {
+        match privilege {
+            PrivilegeLevel::User => &mut self.user_stack_pointer,
+            PrivilegeLevel::Kernel => &mut self.kernel_stack_pointer,
+        }
+    }
+
+    fn address_bus(&self, source: AddressBusAssert, privilege: PrivilegeLevel) -> Address {
+        match source {
+            AddressBusAssert::None => 0,
+            AddressBusAssert::ProgramCounter => self.program_counter,
+            AddressBusAssert::StackPointer => *self.stack_pointer(privilege),
+            AddressBusAssert::X => self.x,
+            AddressBusAssert::Y => self.y,
+            AddressBusAssert::Temp => self.temp(),
+            AddressBusAssert::IOAddress => self.temp1 as Address,
+            AddressBusAssert::NmiVector => NMI_VECTOR,
+            AddressBusAssert::IrqVector => IRQ_VECTOR,
+            AddressBusAssert::SwiVector => SWI_VECTOR,
+            AddressBusAssert::FaultVector => FAULT_VECTOR,
+        }
+    }
+
+    /// The bank an access is made in: the bank register for user code, and
+    /// for the kernel's data accesses while bank enable is set, except for
+    /// the interrupt frame, which is always in bank 0.
+    fn bank(&self, privilege: PrivilegeLevel, kind: MemoryAddressKind, frame: bool) -> Nibble {
+        let bank = match (privilege, kind) {
+            (PrivilegeLevel::User, _) => self.bank_register,
+            (PrivilegeLevel::Kernel, MemoryAddressKind::Data)
+                if self.status & BANK_ENABLE != 0 && !frame =>
+            {
+                self.bank_register
+            }
+            (PrivilegeLevel::Kernel, _) => 0,
+        };
+
+        Nibble::new(bank).unwrap()
+    }
+
+    fn operands(&self, source: TransferBusAssert) -> (Byte, Byte) {
+        use TransferBusAssert as Xfer;
+
+        let (a, b, c, d, t1) = (self.a, self.b, self.c, self.d, self.temp1);
+
+        match source {
+            Xfer::None => (0, 0),
+            Xfer::A_A => (a, a),
+            Xfer::A_B => (a, b),
+            Xfer::A_C => (a, c),
+            Xfer::A_D => (a, d),
+            Xfer::B_A => (b, a),
+            Xfer::B_B => (b, b),
+            Xfer::B_C => (b, c),
+            Xfer::B_D => (b, d),
+            Xfer::C_A => (c, a),
+            Xfer::C_B => (c, b),
+            Xfer::C_C => (c, c),
+            Xfer::C_D => (c, d),
+            Xfer::D_A => (d, a),
+            Xfer::D_B => (d, b),
+            Xfer::D_C => (d, c),
+            Xfer::D_D => (d, d),
+            Xfer::A_T1 => (a, t1),
+            Xfer::B_T1 => (b, t1),
+            Xfer::C_T1 => (c, t1),
+            Xfer::D_T1 => (d, t1),
+        }
+    }
+}
+
+/// The ALU's result, carry out and overflow for `op`. The logical
+/// operations pass the carry in through unchanged.
+fn alu(op: AluOperation, left: Byte, right: Byte, carry: bool) -> (Byte, bool, bool) {
+    let add = |left: Byte, right: Byte, carry: bool| {
+        let sum = left as Word + right as Word + carry as Word;
+        let result = sum as Byte;
+        let overflow = (!(left ^ right) & (left ^ result) & 0x80) != 0;
+        (result, sum > 0xFF, overflow)
+    };
+
+    match op {
+        AluOperation::Nop => (0, carry, false),
+        AluOperation::Add => add(left, right, carry),
+        AluOperation::Sub => add(left, !right, carry),
+        AluOperation::Cmp => add(left, !right, true),
+        AluOperation::And => (left & right, carry, false),
+        AluOperation::Or => (left | right, carry, false),
+        AluOperation::Xor => (left ^ right, carry, false),
+        AluOperation::Not => (!left, carry, false),
+        AluOperation::Shl => (
+            left << 1,
+            left & 0x80 != 0,
+            (left ^ (left << 1)) & 0x80 != 0,
+        ),
+        AluOperation::Shr => (left >> 1, left & 0x01 != 0, false),
+        AluOperation::Asr => (((left as i8) >> 1) as Byte, left & 0x01 != 0, false),
+        AluOperation::Neg => add(0, !left, true),
+        AluOperation::Inc => {
+            let (result, _, overflow) = add(left, 1, false);
+            (result, carry, overflow)
+        }
+        AluOperation::Dec => {
+            let (result, _, overflow) = add(left, !1, true);
+            (result, carry, overflow)
+        }
+    }
+}
+
+fn low(word: Word) -> Byte {
+    word as Byte
+}
+
+fn high(word: Word) -> Byte {
+    (word >> 8) as Byte
+}
+
+fn set_low(word: &mut Word, data: Byte) {
+    *word = (*word & 0xFF00) | data as Word;
+}
+
+fn set_high(word: &mut Word, data: Byte) {
+    *word = (*word & 0x00FF) | (data as Word) << 8;
+}
+
+impl std::ops::Index<Architectural8> for Processor {
+    type Output = Byte;
+
+    fn index(&self, index: Architectural8) -> &Self::Output {
+        match index {
+            Architectural8::A => &self.a,
+            Architectural8::B => &self.b,
+            Architectural8::C => &self.c,
+            Architectural8::D => &self.d,
+        }
+    }
+}
+
+impl std::ops::IndexMut<Architectural8> for Processor {
+    fn index_mut(&mut self, index: Architectural8) -> &mut Self::Output {
+        match index {
+            Architectural8::A => &mut self.a,
+            Architectural8::B => &mut self.b,
+            Architectural8::C => &mut self.c,
+            Architectural8::D => &mut self.d,
+        }
+    }
+}
+
+impl std::ops::Index<Architectural16> for Processor {
+    type Output = Word;
+
+    fn index(&self, index: Architectural16) -> &Self::Output {
+        match index {
+            Architectural16::PC => &self.program_counter,
+            Architectural16::SP => self.stack_pointer(self.privilege()),
+            Architectural16::X => &self.x,
+            Architectural16::Y => &self.y,
+        }
+    }
+}
+
+impl std::ops::IndexMut<Architectural16> for Processor {
+    fn index_mut(&mut self, index: Architectural16) -> &mut Self::Output {
+        match index {
+            Architectural16::PC => &mut self.program_counter,
+            Architectural16::SP => self.stack_pointer_mut(self.privilege()),
+            Architectural16::X => &mut self.x,
+            Architectural16::Y => &mut self.y,
+        }
+    }
+}
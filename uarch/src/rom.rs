@@ -0,0 +1,34 @@
+//! The control ROMs the processor runs: the chip images `uasm` writes from
+//! `uasm/bw8.uasm`, kept in `uarch/rom` so that the model runs the same bytes
+//! that are burned. After changing the source, rebuild them with
+//! `cargo run -p uasm -- uasm/bw8.uasm -o uarch/rom/bw8`.
+
+use std::sync::OnceLock;
+
+use crate::{ControlWord, MicroAddress};
+
+/// The number of words in each ROM, one for every `MicroAddress`.
+pub const ROM_SIZE: usize = 1 << MicroAddress::BITS;
+
+/// The number of ROM chips, each holding eight bits of every control word,
+/// least significant first.
+pub const CHIP_COUNT: usize = 5;
+
+const CHIPS: [&[u8; ROM_SIZE]; CHIP_COUNT] = [
+    include_bytes!("../rom/bw8.0.bin"),
+    include_bytes!("../rom/bw8.1.bin"),
+    include_bytes!("../rom/bw8.2.bin"),
+    include_bytes!("../rom/bw8.3.bin"),
+    include_bytes!("../rom/bw8.4.bin"),
+];
+
+/// The control word at every ROM address, joined from the chip images.
+pub fn control_rom() -> &'static [ControlWord] {
+    static ROM: OnceLock<Vec<ControlWord>> = OnceLock::new();
+
+    ROM.get_or_init(|| {
+        (0..ROM_SIZE)
+            .map(|index| ControlWord::from_bytes(CHIPS.map(|chip| chip[index])))
+            .collect()
+    })
+}
@@ -0,0 +1,118 @@
+//! The sequencer's state, which addresses the control ROM: the opcode
+//! register, the extension bit, the interrupt latch, the flag inputs and the
+//! step counter.
+
+// The specifier `#[bitfield]` generates for an unfilled struct casts its
+// width to the type it already has.
+#![allow(clippy::unnecessary_cast)]
+
+use modular_bitfield::{
+    bitfield,
+    specifiers::{B3, B8},
+    BitfieldSpecifier,
+};
+
+/// The number of steps the sequencer counts through; no instruction takes
+/// more.
+pub const STEP_COUNT: u8 = 8;
+
+/// An interrupt the sequencer is entering instead of fetching an instruction.
+/// It is latched when the interrupt is taken and cleared with the sequencer.
+#[derive(BitfieldSpecifier, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[bits = 2]
+pub enum Interrupt {
+    #[default]
+    None,
+    Nmi,
+    Irq,
+}
+
+/// Everything the control word depends on besides the step: the inputs
+/// shared by every step of an instruction or interrupt entry.
+#[bitfield(filled = false)]
+#[derive(BitfieldSpecifier, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StateVector {
+    pub opcode: B8,
+    pub extended: bool,
+    #[bits = 2]
+    pub interrupt: Interrupt,
+    pub carry: bool,
+    pub zero: bool,
+    pub overflow: bool,
+    pub negative: bool,
+    pub user: bool,
+    /// The address in `Temp` is reserved for the kernel when used for I/O.
+    pub kernel_address: bool,
+}
+
+impl Default for StateVector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StateVector {
+    /// The number of bits in a state vector.
+    pub const BITS: u32 = 17;
+
+    /// Every state vector the sequencer can be in, in the order of their ROM
+    /// addresses.
+    ///
+    /// An interrupt is latched only before an opcode is fetched and the
+    /// extension bit set only after, and a sequencer reset clears both, so no
+    /// state has both.
+    pub fn reachable() -> impl Iterator<Item = StateVector> {
+        (0..1u32 << Self::BITS).filter_map(|bits| {
+            let [low, middle, high, _] = bits.to_le_bytes();
+            let state = Self::from_bytes([low, middle, high]).ok()?;
+            let interrupt = state.interrupt_or_err().ok()?;
+
+            (interrupt == Interrupt::None || !state.extended()).then_some(state)
+        })
+    }
+
+    /// The address of every step of this state, from the opcode fetch on.
+    pub fn addresses(self) -> impl Iterator<Item = MicroAddress> {
+        (0..STEP_COUNT).map(move |step| MicroAddress::new().with_step(step).with_state(self))
+    }
+}
+
+/// A control ROM address: the step in the least significant bits, then the
+/// state vector.
+#[bitfield(filled = false)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MicroAddress {
+    pub step: B3,
+    #[bits = 17]
+    pub state: StateVector,
+}
+
+impl Default for MicroAddress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MicroAddress {
+    /// The width of a control ROM address.
+    pub const BITS: u32 = 20;
+
+    /// The address as an index into a ROM image.
+    pub fn index(self) -> usize {
+        let [low, middle, high] = self.into_bytes();
+        u32::from_le_bytes([low, middle, high, 0]) as usize
+    }
+
+    /// The address at `index` into a ROM image, or `None` if no state vector
+    /// packs to it.
+    pub fn from_index(index: usize) -> Option<Self> {
+        let [low, middle, high, top] = u32::try_from(index).ok()?.to_le_bytes();
+        if top != 0 {
+            return None;
+        }
+
+        let address = Self::from_bytes([low, middle, high]).ok()?;
+        address.state().interrupt_or_err().ok()?;
+        Some(address)
+    }
+}
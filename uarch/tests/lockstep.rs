@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use arch::Architectural16::{PC, SP, X, Y};
+use arch::Architectural8::{A, B, C, D};
+use arch::{
+    Bus, BusResult, Byte, EnvironmentAction, MemoryAddressKind, PhysicalAddress, PrivilegeLevel,
+    StopReason,
+};
+use isa::EXTENSION_PREFIX;
+use uarch::lockstep::{Lockstep, Mismatch};
+
+/// Memory and I/O that read back arbitrary but repeatable values wherever
+/// nothing was written. Port 0 halts and port 3 breaks, as in the emulator,
+/// and an IRQ can be raised after a number of clocks.
+#[derive(Clone)]
+struct TestBus {
+    seed: u32,
+    memory: HashMap<usize, Byte>,
+    clocks: usize,
+    irq_at: Option<usize>,
+}
+
+impl TestBus {
+    fn new(seed: u32) -> Self {
+        Self {
+            seed,
+            memory: HashMap::new(),
+            clocks: 0,
+            irq_at: None,
+        }
+    }
+
+    fn load(&mut self, linear: usize, bytes: &[Byte]) {
+        for (offset, byte) in bytes.iter().enumerate() {
+            self.memory.insert(linear + offset, *byte);
+        }
+    }
+
+    fn noise(&self, value: usize) -> Byte {
+        let mut x = (value as u32 ^ self.seed).wrapping_mul(0x9E37_79B1);
+        x ^= x >> 15;
+        x = x.wrapping_mul(0x85EB_CA6B);
+        (x >> 13) as Byte
+    }
+}
+
+impl Bus for TestBus {
+    fn memory_read(
+        &self,
+        _privilege: PrivilegeLevel,
+        _kind: MemoryAddressKind,
+        address: PhysicalAddress,
+    ) -> Byte {
+        let linear = address.linear();
+        match self.memory.get(&linear) {
+            Some(byte) => *byte,
+            None => self.noise(linear),
+        }
+    }
+
+    fn memory_write(
+        &mut self,
+        _privilege: PrivilegeLevel,
+        _kind: MemoryAddressKind,
+        address: PhysicalAddress,
+        data: Byte,
+    ) {
+        self.memory.insert(address.linear(), data);
+    }
+
+    fn io_read(&mut self, _privilege: PrivilegeLevel, address: PhysicalAddress) -> BusResult<Byte> {
+        BusResult::Data(self.noise(!address.linear()))
+    }
+
+    fn io_write(
+        &mut self,
+        _privilege: PrivilegeLevel,
+        address: PhysicalAddress,
+        data: Byte,
+    ) -> BusResult<()> {
+        match address.base {
+            0x00 => BusResult::Action(EnvironmentAction::Halt(data)),
+            0x03 => BusResult::Action(EnvironmentAction::Break),
+            _ => BusResult::Data(()),
+        }
+    }
+
+    fn clock(&mut self) {
+        self.clocks += 1;
+    }
+
+    fn is_rst_active(&self) -> bool {
+        false
+    }
+
+    fn is_nmi_active(&mut self) -> bool {
+        false
+    }
+
+    fn is_irq_active(&self) -> bool {
+        self.irq_at.is_some_and(|at| self.clocks >= at)
+    }
+
+    fn acknowledge_irq(&mut self) {
+        self.irq_at = None;
+    }
+
+    fn is_req_active(&self) -> bool {
+        false
+    }
+}
+
+fn assemble(source: &str) -> Vec<Byte> {
+    match asm::assemble_source(Path::new("<snippet>"), source) {
+        Ok(assembly) => assembly.bytes,
+        Err(error) => panic!("failed to assemble snippet: {}", error),
+    }
+}
+
+/// Runs `instruction` on both processors from a state drawn from `seed`:
+/// random registers, and a random status and bank entered through `reti`.
+fn check_instruction(instruction: &[Byte], seed: u32) {
+    let mut bus = TestBus::new(seed);
+    let noise = |n: usize| bus.noise(n);
+
+    let registers = [noise(2), noise(3), noise(4)];
+    let bank = noise(5) & 0b0000_1111;
+    let status = noise(6);
+    let target = 0x1000 + (noise(7) as u16) * 0x80 + noise(8) as u16 % 0x80;
+    let pointers = [
+        u16::from_le_bytes([noise(9), noise(10)]),
+        u16::from_le_bytes([noise(11), noise(12)]),
+    ];
+
+    bus.load(0, &assemble("mv br, a\nreti"));
+    let [high, low] = target.to_be_bytes();
+    bus.load(0xFF01, &[status, low, high]);
+    bus.load(target as usize, instruction);
+    bus.load((bank as usize) << 16 | target as usize, instruction);
+
+    let mut lockstep = Lockstep::new(bus.clone(), bus);
+    set_registers(&mut lockstep, registers, bank, pointers);
+
+    for step in 0..3 {
+        if let Err(divergence) = lockstep.step() {
+            panic!(
+                "{:02X?} with seed {} diverged in step {}: {}",
+                instruction, seed, step, divergence
+            );
+        }
+    }
+}
+
+fn set_registers(
+    lockstep: &mut Lockstep<TestBus>,
+    registers: [Byte; 3],
+    bank: Byte,
+    pointers: [u16; 2],
+) {
+    macro_rules! set {
+        ($cpu:expr) => {{
+            let cpu = $cpu;
+            cpu[A] = bank;
+            cpu[B] = registers[0];
+            cpu[C] = registers[1];
+            cpu[D] = registers[2];
+            cpu[SP] = 0xFF00;
+            cpu[X] = pointers[0];
+            cpu[Y] = pointers[1];
+        }};
+    }
+
+    set!(lockstep.reference_mut());
+    set!(lockstep.core_mut());
+}
+
+#[test]
+fn every_opcode_matches_the_reference() {
+    for seed in 0..8 {
+        for opcode in 0..=0xFF {
+            if opcode != EXTENSION_PREFIX {
+                check_instruction(&[opcode, 0x12, 0x34, 0x56], seed);
+                check_instruction(&[opcode, 0x81, 0x7F, 0xFE], seed);
+            }
+            check_instruction(&[EXTENSION_PREFIX, opcode, 0x12, 0x34], seed);
+            check_instruction(&[EXTENSION_PREFIX, opcode, 0x81, 0x7F], seed);
+        }
+    }
+}
+
+#[test]
+fn interrupted_program_runs_to_a_halt_in_lockstep() {
+    let program = assemble(
+        "
+        #addr 0x0000
+            jmp.abs boot
+        #addr 0x0008
+            jmp.abs isr
+
+        boot:
+            ld x, #0xFF00
+            mv sp, x
+            ld x, #0x9000
+            ld a, #0
+            ld b, #0
+            set.i
+        loop:
+            inc a
+            st [x, #0], a
+            push a
+            pop c
+            cmp b, 0
+            br.eq.abs loop
+            out [0x00], a
+
+        isr:
+            ld b, #1
+            reti
+        ",
+    );
+    let mut bus = TestBus::new(0);
+    bus.load(0, &program);
+    bus.irq_at = Some(200);
+
+    let mut lockstep = Lockstep::new(bus.clone(), bus);
+
+    let stop = lockstep.run(10_000).unwrap_or_else(|d| panic!("{}", d));
+
+    assert!(matches!(stop, StopReason::Halt(_)));
+    assert_eq!(lockstep.core()[B], 1);
+    assert_eq!(lockstep.core()[A], lockstep.reference()[A]);
+}
+
+#[test]
+fn a_tampered_register_is_reported() {
+    let mut bus = TestBus::new(0);
+    bus.load(0, &assemble("ld a, #1\nnop"));
+
+    let mut lockstep = Lockstep::new(bus.clone(), bus);
+    lockstep.step().unwrap();
+    lockstep.core_mut()[D] = 0x55;
+
+    let divergence = lockstep.step().unwrap_err();
+
+    assert_eq!(divergence.address, 2);
+    assert_eq!(
+        divergence.mismatches,
+        vec![Mismatch::Register("D", 0x00, 0x55)]
+    );
+    assert_eq!(lockstep.reference()[PC], lockstep.core()[PC]);
+}
@@ -0,0 +1,46 @@
+use uarch::{Interrupt, MicroAddress, StateVector, STEP_COUNT};
+
+#[test]
+fn addresses_pack_the_step_below_the_state() {
+    let state = StateVector::new()
+        .with_opcode(0xA5)
+        .with_interrupt(Interrupt::Irq)
+        .with_zero(true)
+        .with_kernel_address(true);
+    let address = MicroAddress::new().with_step(5).with_state(state);
+
+    let expected = 5 | 0xA5 << 3 | 2 << 12 | 1 << 15 | 1 << 19;
+    assert_eq!(address.index(), expected);
+    assert_eq!(MicroAddress::from_index(expected), Some(address));
+    assert_eq!(address.state().interrupt(), Interrupt::Irq);
+}
+
+#[test]
+fn indices_no_state_packs_to_are_rejected() {
+    assert_eq!(MicroAddress::from_index(3 << 12), None);
+    assert_eq!(MicroAddress::from_index(1 << MicroAddress::BITS), None);
+    assert_eq!(
+        MicroAddress::from_index(0),
+        Some(MicroAddress::new().with_state(StateVector::new()))
+    );
+}
+
+#[test]
+fn reachable_states_never_interrupt_an_extended_opcode() {
+    let states: Vec<StateVector> = StateVector::reachable().collect();
+
+    // Every opcode and flag input, either fetching an instruction (plain or
+    // extended) or entering one of the two interrupts.
+    assert_eq!(states.len(), 256 * 64 * 4);
+    assert!(states
+        .iter()
+        .all(|state| state.interrupt() == Interrupt::None || !state.extended()));
+
+    let indices: Vec<usize> = states
+        .iter()
+        .flat_map(|state| state.addresses())
+        .map(MicroAddress::index)
+        .collect();
+    assert!(indices.windows(2).all(|pair| pair[0] < pair[1]));
+    assert_eq!(indices.len(), states.len() * STEP_COUNT as usize);
+}
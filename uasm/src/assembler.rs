@@ -0,0 +1,800 @@
+use std::collections::HashMap;
+
+use isa::{Byte, Opcode, EXTENSION_PREFIX, OPCODES};
+use uarch::*;
+
+use crate::parser::{self, AddressOffset, Expression, Line, Signal, Statement};
+use crate::{Error, Location};
+
+/// How deeply sequences may `use` one another, which stops a sequence that
+/// uses itself.
+const MAX_NESTING: usize = 16;
+
+/// Assembled microcode: the steps of every opcode and interrupt entry, for
+/// each of the states they depend on.
+pub struct Microcode {
+    fetch: Vec<Rule>,
+    nmi: Vec<Rule>,
+    irq: Vec<Rule>,
+    normal: Vec<Vec<Rule>>,
+    extended: Vec<Vec<Rule>>,
+}
+
+/// The steps of one block, for one of the opcodes it names.
+struct Rule {
+    location: Location,
+    guard: Option<Guard>,
+    steps: Vec<Step>,
+}
+
+#[derive(Clone, Copy)]
+struct Step {
+    word: ControlWord,
+    /// The step says how the sequencer advances, so ending a sequence with
+    /// it does not reset the sequencer.
+    sequenced: bool,
+}
+
+#[derive(Clone, Debug)]
+enum Guard {
+    Constant(bool),
+    Input(Input),
+    Not(Box<Guard>),
+    And(Box<Guard>, Box<Guard>),
+    Or(Box<Guard>, Box<Guard>),
+    Equal(Box<Guard>, Box<Guard>),
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Input {
+    Carry,
+    Zero,
+    Overflow,
+    Negative,
+    User,
+    KernelAddress,
+}
+
+impl Guard {
+    fn holds(&self, state: StateVector) -> bool {
+        match self {
+            Guard::Constant(value) => *value,
+            Guard::Input(Input::Carry) => state.carry(),
+            Guard::Input(Input::Zero) => state.zero(),
+            Guard::Input(Input::Overflow) => state.overflow(),
+            Guard::Input(Input::Negative) => state.negative(),
+            Guard::Input(Input::User) => state.user(),
+            Guard::Input(Input::KernelAddress) => state.kernel_address(),
+            Guard::Not(guard) => !guard.holds(state),
+            Guard::And(left, right) => left.holds(state) && right.holds(state),
+            Guard::Or(left, right) => left.holds(state) || right.holds(state),
+            Guard::Equal(left, right) => left.holds(state) == right.holds(state),
+        }
+    }
+}
+
+impl Microcode {
+    /// The control word at `address`. Steps past the end of a sequence,
+    /// which the sequencer never reaches, reset it.
+    pub fn control_word(&self, address: MicroAddress) -> ControlWord {
+        self.sequence(address.state())
+            .get(address.step() as usize)
+            .copied()
+            .unwrap_or_else(|| ControlWord::new().with_sequencer(Sequencer::Reset))
+    }
+
+    /// Every step of the instruction or interrupt entry `state` addresses,
+    /// from the opcode fetch on.
+    pub fn sequence(&self, state: StateVector) -> Vec<ControlWord> {
+        let mut steps = match state.interrupt() {
+            Interrupt::Nmi => select(&self.nmi, state).to_vec(),
+            Interrupt::Irq => select(&self.irq, state).to_vec(),
+            Interrupt::None => {
+                let mut steps = select(&self.fetch, state).to_vec();
+                if state.extended() {
+                    steps.extend(select(&self.normal[EXTENSION_PREFIX as usize], state));
+                    steps.extend(select(&self.extended[state.opcode() as usize], state));
+                } else {
+                    steps.extend(select(&self.normal[state.opcode() as usize], state));
+                }
+                steps
+            }
+        };
+
+        let last = steps.last_mut().expect("every sequence has a step");
+        if !last.sequenced {
+            last.word = last.word.with_sequencer(Sequencer::Reset);
+        }
+
+        steps.into_iter().map(|step| step.word).collect()
+    }
+}
+
+/// The steps of the first rule whose guard holds in `state`.
+fn select(rules: &[Rule], state: StateVector) -> &[Step] {
+    let rule = rules
+        .iter()
+        .find(|rule| rule.guard.as_ref().is_none_or(|guard| guard.holds(state)))
+        .expect("every target has an unguarded rule");
+
+    &rule.steps
+}
+
+#[derive(Clone, Copy)]
+enum Target {
+    Fetch,
+    Nmi,
+    Irq,
+    Opcode(Opcode),
+}
+
+struct Sequence<'a> {
+    parameters: &'a [String],
+    body: &'a [Line],
+}
+
+struct Assembler<'a> {
+    sets: HashMap<&'a str, &'a [String]>,
+    conditions: HashMap<&'a str, Guard>,
+    sequences: HashMap<&'a str, Sequence<'a>>,
+    opcodes: HashMap<&'static str, Opcode>,
+    microcode: Microcode,
+}
+
+pub fn assemble(file: &str, statements: &[(Location, Statement)]) -> Result<Microcode, Error> {
+    let mut assembler = Assembler {
+        sets: HashMap::new(),
+        conditions: HashMap::new(),
+        sequences: HashMap::new(),
+        opcodes: OPCODES.iter().copied().collect(),
+        microcode: Microcode {
+            fetch: Vec::new(),
+            nmi: Vec::new(),
+            irq: Vec::new(),
+            normal: (0..256).map(|_| Vec::new()).collect(),
+            extended: (0..256).map(|_| Vec::new()).collect(),
+        },
+    };
+
+    // Sequences may be used before they are defined.
+    for (location, statement) in statements {
+        if let Statement::Sequence {
+            name,
+            parameters,
+            body,
+        } = statement
+        {
+            let sequence = Sequence { parameters, body };
+            if assembler.sequences.insert(name, sequence).is_some() {
+                return Err(error(
+                    location,
+                    format!("sequence `{}` is already defined", name),
+                ));
+            }
+        }
+    }
+
+    for (location, statement) in statements {
+        match statement {
+            Statement::Set { name, values } => {
+                if assembler.sets.insert(name, values).is_some() {
+                    return Err(error(
+                        location,
+                        format!("set `{}` is already defined", name),
+                    ));
+                }
+            }
+            Statement::Condition { name, expression } => {
+                if input(name).is_some() || assembler.conditions.contains_key(name.as_str()) {
+                    return Err(error(location, format!("`{}` is already defined", name)));
+                }
+                let guard = assembler
+                    .guard(expression)
+                    .map_err(|message| error(location, message))?;
+                assembler.conditions.insert(name, guard);
+            }
+            Statement::Sequence { .. } => {}
+            Statement::Block {
+                targets,
+                guard,
+                body,
+            } => assembler.block(location, targets, guard.as_deref(), body)?,
+        }
+    }
+
+    assembler.check(file)?;
+
+    Ok(assembler.microcode)
+}
+
+impl<'a> Assembler<'a> {
+    /// Adds a rule for every opcode the block names, once for each value of
+    /// the placeholders in its header.
+    fn block(
+        &mut self,
+        location: &Location,
+        targets: &str,
+        guard: Option<&str>,
+        body: &[Line],
+    ) -> Result<(), Error> {
+        let header = match guard {
+            Some(guard) => format!("{} if {}", targets, guard),
+            None => targets.to_string(),
+        };
+        let placeholders = placeholders(&header).map_err(|message| error(location, message))?;
+
+        let mut domains = Vec::new();
+        for (name, set) in &placeholders {
+            match self.sets.get(set.as_str()) {
+                Some(values) => domains.push((name.clone(), *values)),
+                None => return Err(error(location, format!("unknown set `{}`", set))),
+            }
+        }
+
+        for bindings in product(&domains) {
+            let targets =
+                substitute(targets, &bindings).map_err(|message| error(location, message))?;
+            let guard = match guard {
+                Some(guard) => {
+                    let guard = substitute(guard, &bindings)
+                        .and_then(|guard| parser::parse_expression(&guard))
+                        .and_then(|guard| self.guard(&guard))
+                        .map_err(|message| error(location, message))?;
+                    Some(guard)
+                }
+                None => None,
+            };
+            let steps = self.steps(body, &bindings, 0)?;
+
+            for target in targets.split(',') {
+                let target = self
+                    .target(target.trim())
+                    .map_err(|message| error(location, message))?;
+                let rule = Rule {
+                    location: location.clone(),
+                    guard: guard.clone(),
+                    steps: steps.clone(),
+                };
+
+                match target {
+                    Target::Fetch => self.microcode.fetch.push(rule),
+                    Target::Nmi => self.microcode.nmi.push(rule),
+                    Target::Irq => self.microcode.irq.push(rule),
+                    Target::Opcode(Opcode::Normal(byte)) => {
+                        self.microcode.normal[byte as usize].push(rule)
+                    }
+                    Target::Opcode(Opcode::Extended(byte)) => {
+                        self.microcode.extended[byte as usize].push(rule)
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The steps of `body`, with its placeholders bound and the sequences it
+    /// uses expanded.
+    fn steps(
+        &self,
+        body: &[Line],
+        bindings: &[(String, String)],
+        depth: usize,
+    ) -> Result<Vec<Step>, Error> {
+        let mut steps = Vec::new();
+
+        for line in body {
+            let at = |message| error(&line.location, message);
+
+            let step = substitute(&line.text, bindings)
+                .and_then(|text| parser::parse_step(&text))
+                .map_err(at)?;
+
+            match step {
+                parser::Step::Use {
+                    sequence,
+                    arguments,
+                } => {
+                    let used = self
+                        .sequences
+                        .get(sequence.as_str())
+                        .ok_or_else(|| at(format!("unknown sequence `{}`", sequence)))?;
+                    if used.parameters.len() != arguments.len() {
+                        return Err(at(format!(
+                            "sequence `{}` takes {} arguments, not {}",
+                            sequence,
+                            used.parameters.len(),
+                            arguments.len()
+                        )));
+                    }
+                    if depth == MAX_NESTING {
+                        return Err(at(format!("sequence `{}` uses itself", sequence)));
+                    }
+
+                    let bindings: Vec<_> = used.parameters.iter().cloned().zip(arguments).collect();
+                    steps.extend(self.steps(used.body, &bindings, depth + 1)?);
+                }
+                parser::Step::Signals(signals) => steps.push(step_word(&signals).map_err(at)?),
+            }
+        }
+
+        Ok(steps)
+    }
+
+    fn target(&self, name: &str) -> Result<Target, String> {
+        match name {
+            "fetch" => Ok(Target::Fetch),
+            "nmi" => Ok(Target::Nmi),
+            "irq" => Ok(Target::Irq),
+            _ => match self.opcodes.get(name.to_uppercase().as_str()) {
+                Some(opcode) => Ok(Target::Opcode(*opcode)),
+                None => Err(format!("unknown opcode `{}`", name)),
+            },
+        }
+    }
+
+    fn guard(&self, expression: &Expression) -> Result<Guard, String> {
+        let pair = |left: &Expression, right: &Expression| {
+            Ok::<_, String>((Box::new(self.guard(left)?), Box::new(self.guard(right)?)))
+        };
+
+        Ok(match expression {
+            Expression::Name(name) => match (name.as_str(), input(name)) {
+                ("true", _) => Guard::Constant(true),
+                ("false", _) => Guard::Constant(false),
+                (_, Some(input)) => Guard::Input(input),
+                (_, None) => match self.conditions.get(name.as_str()) {
+                    Some(guard) => guard.clone(),
+                    None => return Err(format!("unknown condition `{}`", name)),
+                },
+            },
+            Expression::Not(inner) => Guard::Not(Box::new(self.guard(inner)?)),
+            Expression::And(left, right) => {
+                let (left, right) = pair(left, right)?;
+                Guard::And(left, right)
+            }
+            Expression::Or(left, right) => {
+                let (left, right) = pair(left, right)?;
+                Guard::Or(left, right)
+            }
+            Expression::Equal(left, right) => {
+                let (left, right) = pair(left, right)?;
+                Guard::Equal(left, right)
+            }
+            Expression::NotEqual(left, right) => {
+                let (left, right) = pair(left, right)?;
+                Guard::Not(Box::new(Guard::Equal(left, right)))
+            }
+        })
+    }
+
+    /// Checks that every state has a rule, and that no sequence is longer
+    /// than the sequencer counts.
+    fn check(&self, file: &str) -> Result<(), Error> {
+        let microcode = &self.microcode;
+        let whole_file = Location {
+            file: file.to_string(),
+            line: 0,
+        };
+
+        let named = |rules: &[Rule], name: &str| match rules.iter().any(|rule| rule.guard.is_none())
+        {
+            true => Ok(()),
+            false => Err(error(
+                &whole_file,
+                format!("`{}` needs a block without a guard", name),
+            )),
+        };
+
+        named(&microcode.fetch, "fetch")?;
+        named(&microcode.nmi, "nmi")?;
+        named(&microcode.irq, "irq")?;
+        for (name, opcode) in OPCODES {
+            named(self.rules(*opcode), name)?;
+        }
+
+        let longest = |rules: &[Rule]| rules.iter().map(|rule| rule.steps.len()).max();
+        let fetch = longest(&microcode.fetch).unwrap_or(0);
+        let prefix = fetch + longest(&microcode.normal[EXTENSION_PREFIX as usize]).unwrap_or(0);
+
+        let fits = |rules: &[Rule], before: usize| {
+            for rule in rules {
+                if before + rule.steps.len() > STEP_COUNT as usize {
+                    return Err(error(
+                        &rule.location,
+                        format!(
+                            "sequence takes {} steps; the sequencer counts {}",
+                            before + rule.steps.len(),
+                            STEP_COUNT
+                        ),
+                    ));
+                }
+            }
+            Ok(())
+        };
+
+        fits(&microcode.nmi, 0)?;
+        fits(&microcode.irq, 0)?;
+        for byte in 0..=Byte::MAX {
+            fits(&microcode.normal[byte as usize], fetch)?;
+            fits(&microcode.extended[byte as usize], prefix)?;
+        }
+
+        Ok(())
+    }
+
+    fn rules(&self, opcode: Opcode) -> &[Rule] {
+        match opcode {
+            Opcode::Normal(byte) => &self.microcode.normal[byte as usize],
+            Opcode::Extended(byte) => &self.microcode.extended[byte as usize],
+        }
+    }
+}
+
+fn error(location: &Location, message: String) -> Error {
+    Error {
+        location: location.clone(),
+        message,
+    }
+}
+
+fn input(name: &str) -> Option<Input> {
+    match name {
+        "c" => Some(Input::Carry),
+        "z" => Some(Input::Zero),
+        "v" => Some(Input::Overflow),
+        "n" => Some(Input::Negative),
+        "user" => Some(Input::User),
+        "kernel_address" => Some(Input::KernelAddress),
+        _ => None,
+    }
+}
+
+/// The `{name:set}` placeholders in a block's header, each listed once.
+fn placeholders(header: &str) -> Result<Vec<(String, String)>, String> {
+    let mut placeholders: Vec<(String, String)> = Vec::new();
+    let mut rest = header;
+
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("unclosed `{{` in `{}`", header))?;
+        let inner = &rest[start + 1..start + end];
+        rest = &rest[start + end + 1..];
+
+        let (name, set) = match inner.split_once(':') {
+            Some((name, set)) => (name.trim(), set.trim()),
+            None => match placeholders.iter().find(|(name, _)| name == inner.trim()) {
+                Some(_) => continue,
+                None => {
+                    return Err(format!(
+                        "placeholder `{{{}}}` needs a set, as in `{{{}:<set>}}`",
+                        inner, inner
+                    ))
+                }
+            },
+        };
+
+        match placeholders.iter().find(|(existing, _)| existing == name) {
+            Some((_, existing)) if existing != set => {
+                return Err(format!("placeholder `{}` ranges over two sets", name))
+            }
+            Some(_) => {}
+            None => placeholders.push((name.to_string(), set.to_string())),
+        }
+    }
+
+    Ok(placeholders)
+}
+
+/// Every combination of values of the placeholders.
+fn product(domains: &[(String, &[String])]) -> Vec<Vec<(String, String)>> {
+    let mut bindings = vec![Vec::new()];
+
+    for (name, values) in domains {
+        bindings = bindings
+            .into_iter()
+            .flat_map(|bound: Vec<(String, String)>| {
+                values.iter().map(move |value| {
+                    let mut bound = bound.clone();
+                    bound.push((name.clone(), value.clone()));
+                    bound
+                })
+            })
+            .collect();
+    }
+
+    bindings
+}
+
+/// Replaces each `{name}` or `{name:set}` in `text` with the value bound to
+/// `name`.
+fn substitute(text: &str, bindings: &[(String, String)]) -> Result<String, String> {
+    let mut substituted = String::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("unclosed `{{` in `{}`", text))?;
+        let inner = &rest[start + 1..start + end];
+        let name = inner.split(':').next().unwrap_or_default().trim();
+
+        let value = bindings
+            .iter()
+            .find(|(bound, _)| bound == name)
+            .map(|(_, value)| value)
+            .ok_or_else(|| format!("unknown placeholder `{{{}}}`", name))?;
+
+        substituted.push_str(&rest[..start]);
+        substituted.push_str(value);
+        rest = &rest[start + end + 1..];
+    }
+
+    substituted.push_str(rest);
+    Ok(substituted)
+}
+
+/// The control word driving `signals`, each field of which may be set only
+/// once.
+fn step_word(signals: &[Signal]) -> Result<Step, String> {
+    let mut step = StepBuilder {
+        step: Step {
+            word: ControlWord::new(),
+            sequenced: false,
+        },
+        claimed: Vec::new(),
+    };
+
+    for signal in signals {
+        match signal {
+            Signal::Keyword(keyword) => match keyword.as_str() {
+                "nop" => {}
+                "kernel" => {
+                    step.claim("kernel")?;
+                    step.step.word.set_kernel(true);
+                }
+                "ext" | "reset" => {
+                    step.claim("sequencer")?;
+                    step.step.sequenced = true;
+                    step.step.word.set_sequencer(match keyword.as_str() {
+                        "ext" => Sequencer::Extend,
+                        _ => Sequencer::Reset,
+                    });
+                }
+                _ => return Err(format!("unknown signal `{}`", keyword)),
+            },
+            Signal::Flags(operation) => {
+                step.claim("flags")?;
+                step.step.word.set_flags(flag_operation(operation)?);
+            }
+            Signal::Alu {
+                operation,
+                operands,
+                load,
+            } => {
+                step.claim("ALU")?;
+                step.claim("data bus")?;
+                step.step.word.set_alu_op(alu_operation(operation)?);
+                step.step.word.set_xfer_assert(transfer_assert(operands)?);
+                step.step.word.set_data_assert(DataBusAssert::Alu);
+                if let Some(load) = load {
+                    step.claim("data load")?;
+                    step.step.word.set_data_load(data_load(load)?);
+                }
+            }
+            Signal::Address {
+                assert,
+                operation,
+                load,
+            } => {
+                step.claim("address bus")?;
+                step.step.word.set_address_assert(address_assert(assert)?);
+                match operation {
+                    None => {}
+                    Some(AddressOffset::Increment) => {
+                        step.claim("address unit")?;
+                        step.step.word.set_address_op(AddressOperation::Increment);
+                    }
+                    Some(AddressOffset::Decrement) => {
+                        step.claim("address unit")?;
+                        step.step.word.set_address_op(AddressOperation::Decrement);
+                    }
+                    Some(AddressOffset::By(by)) => {
+                        step.claim("address unit")?;
+                        step.claim("data bus")?;
+                        step.step.word.set_address_op(AddressOperation::Offset);
+                        step.step.word.set_data_assert(data_assert(by)?);
+                    }
+                }
+                if let Some(load) = load {
+                    step.claim("address load")?;
+                    step.step.word.set_address_load(address_load(load)?);
+                }
+            }
+            Signal::Transfer { assert, load } => {
+                step.claim("data bus")?;
+                step.claim("data load")?;
+                step.step.word.set_data_assert(data_assert(assert)?);
+                step.step.word.set_data_load(data_load(load)?);
+            }
+        }
+    }
+
+    Ok(step.step)
+}
+
+struct StepBuilder {
+    step: Step,
+    claimed: Vec<&'static str>,
+}
+
+impl StepBuilder {
+    fn claim(&mut self, field: &'static str) -> Result<(), String> {
+        match self.claimed.contains(&field) {
+            true => Err(format!("the {} is driven twice in one step", field)),
+            false => {
+                self.claimed.push(field);
+                Ok(())
+            }
+        }
+    }
+}
+
+fn data_assert(name: &str) -> Result<DataBusAssert, String> {
+    use DataBusAssert::*;
+
+    Ok(match name {
+        "a" => A,
+        "b" => B,
+        "c" => C,
+        "d" => D,
+        "mem" => Memory,
+        "io" => Io,
+        "br" => Bank,
+        "status" => Status,
+        "t1" => Temp1,
+        "t2" => Temp2,
+        "pcl" => PcLow,
+        "pch" => PcHigh,
+        "spl" => SpLow,
+        "sph" => SpHigh,
+        "xl" => XLow,
+        "xh" => XHigh,
+        "yl" => YLow,
+        "yh" => YHigh,
+        "ial" => InstructionLow,
+        "iah" => InstructionHigh,
+        "uspl" => UserSpLow,
+        "usph" => UserSpHigh,
+        _ => return Err(format!("`{}` cannot drive the data bus", name)),
+    })
+}
+
+fn data_load(name: &str) -> Result<DataBusLoad, String> {
+    use DataBusLoad::*;
+
+    Ok(match name {
+        "a" => A,
+        "b" => B,
+        "c" => C,
+        "d" => D,
+        "mem" => Memory,
+        "io" => Io,
+        "br" => Bank,
+        "status" => Status,
+        "t1" => Temp1,
+        "t2" => Temp2,
+        "pcl" => PcLow,
+        "pch" => PcHigh,
+        "spl" => SpLow,
+        "sph" => SpHigh,
+        "xl" => XLow,
+        "xh" => XHigh,
+        "yl" => YLow,
+        "yh" => YHigh,
+        "opcode" => Opcode,
+        "uspl" => UserSpLow,
+        "usph" => UserSpHigh,
+        _ => return Err(format!("`{}` cannot load the data bus", name)),
+    })
+}
+
+fn address_assert(name: &str) -> Result<AddressBusAssert, String> {
+    use AddressBusAssert::*;
+
+    Ok(match name {
+        "pc" => ProgramCounter,
+        "sp" => StackPointer,
+        "x" => X,
+        "y" => Y,
+        "temp" => Temp,
+        "port" => IOAddress,
+        "nmi" => NmiVector,
+        "irq" => IrqVector,
+        "swi" => SwiVector,
+        "fault" => FaultVector,
+        _ => return Err(format!("`{}` cannot drive the address bus", name)),
+    })
+}
+
+fn address_load(name: &str) -> Result<AddressBusLoad, String> {
+    use AddressBusLoad::*;
+
+    Ok(match name {
+        "pc" => ProgramCounter,
+        "sp" => StackPointer,
+        "x" => X,
+        "y" => Y,
+        "temp" => Temp,
+        _ => return Err(format!("`{}` cannot load the address bus", name)),
+    })
+}
+
+fn alu_operation(name: &str) -> Result<AluOperation, String> {
+    use AluOperation::*;
+
+    Ok(match name {
+        "add" => Add,
+        "sub" => Sub,
+        "and" => And,
+        "or" => Or,
+        "xor" => Xor,
+        "not" => Not,
+        "shl" => Shl,
+        "shr" => Shr,
+        "asr" => Asr,
+        "neg" => Neg,
+        "inc" => Inc,
+        "dec" => Dec,
+        "cmp" => Cmp,
+        _ => return Err(format!("unknown ALU operation `{}`", name)),
+    })
+}
+
+/// The operands `<left>_<right>`, where `right` may be `t1`.
+fn transfer_assert(name: &str) -> Result<TransferBusAssert, String> {
+    use TransferBusAssert::*;
+
+    Ok(match name {
+        "a_a" => A_A,
+        "a_b" => A_B,
+        "a_c" => A_C,
+        "a_d" => A_D,
+        "b_a" => B_A,
+        "b_b" => B_B,
+        "b_c" => B_C,
+        "b_d" => B_D,
+        "c_a" => C_A,
+        "c_b" => C_B,
+        "c_c" => C_C,
+        "c_d" => C_D,
+        "d_a" => D_A,
+        "d_b" => D_B,
+        "d_c" => D_C,
+        "d_d" => D_D,
+        "a_t1" => A_T1,
+        "b_t1" => B_T1,
+        "c_t1" => C_T1,
+        "d_t1" => D_T1,
+        _ => return Err(format!("unknown ALU operands `{}`", name)),
+    })
+}
+
+fn flag_operation(name: &str) -> Result<FlagOperation, String> {
+    use FlagOperation::*;
+
+    Ok(match name {
+        "arithmetic" => Arithmetic,
+        "set_c" => SetCarry,
+        "clr_c" => ClearCarry,
+        "set_i" => SetInterruptEnable,
+        "clr_i" => ClearInterruptEnable,
+        "set_b" => SetBankEnable,
+        "clr_b" => ClearBankEnable,
+        "interrupt" => Interrupt,
+        "nmi" => Nmi,
+        "restore" => Restore,
+        _ => return Err(format!("unknown flag operation `{}`", name)),
+    })
+}
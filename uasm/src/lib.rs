@@ -0,0 +1,55 @@
+mod assembler;
+mod parser;
+pub mod rom;
+pub mod verify;
+
+use std::path::Path;
+
+pub use assembler::Microcode;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Location {
+    pub file: String,
+    pub line: usize,
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)
+    }
+}
+
+#[derive(Debug)]
+pub struct Error {
+    pub location: Location,
+    pub message: String,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Assembles a microcode source file.
+pub fn assemble_file(path: &Path) -> Result<Microcode, Error> {
+    let source = std::fs::read_to_string(path).map_err(|error| Error {
+        location: Location {
+            file: path.display().to_string(),
+            line: 0,
+        },
+        message: error.to_string(),
+    })?;
+
+    assemble_source(path, &source)
+}
+
+/// Assembles microcode source held in memory. `path` names the source in
+/// errors; it need not exist.
+pub fn assemble_source(path: &Path, source: &str) -> Result<Microcode, Error> {
+    let file = path.display().to_string();
+    let statements = parser::parse(&file, source)?;
+    assembler::assemble(&file, &statements)
+}
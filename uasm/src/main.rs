@@ -1,3 +1,62 @@
+use std::path::{Path, PathBuf};
+
+use uasm::{rom, verify};
+
+fn usage() -> ! {
+    eprintln!("usage: uasm <source> [-o <output>]");
+    std::process::exit(2);
+}
+
+fn write(path: &Path, contents: impl AsRef<[u8]>) {
+    if let Err(error) = std::fs::write(path, contents) {
+        eprintln!("error: {}: {}", path.display(), error);
+        std::process::exit(1);
+    }
+}
+
+/// Assembles and verifies the source, writing each control ROM chip's image
+/// to `<output>.<chip>.bin` and `<output>.<chip>.hex`.
 fn main() {
-    println!("Hello, world!");
+    let mut args = std::env::args().skip(1);
+    let mut source = None;
+    let mut output = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" => output = Some(PathBuf::from(args.next().unwrap_or_else(|| usage()))),
+            _ if source.is_none() => source = Some(PathBuf::from(arg)),
+            _ => usage(),
+        }
+    }
+
+    let source = source.unwrap_or_else(|| usage());
+    let output = output.unwrap_or_else(|| source.with_extension(""));
+
+    let microcode = match uasm::assemble_file(&source) {
+        Ok(microcode) => microcode,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            std::process::exit(1);
+        }
+    };
+
+    let image = rom::image(&microcode);
+    let violations = verify::verify(&image);
+    for violation in &violations {
+        eprintln!("error: {}", violation);
+    }
+    if !violations.is_empty() {
+        std::process::exit(1);
+    }
+
+    for (chip, contents) in rom::chips(&image).iter().enumerate() {
+        let path = |extension: &str| {
+            let mut path = output.clone().into_os_string();
+            path.push(format!(".{}.{}", chip, extension));
+            PathBuf::from(path)
+        };
+
+        write(&path("bin"), contents);
+        write(&path("hex"), rom::intel_hex(contents));
+    }
 }
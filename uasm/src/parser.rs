@@ -0,0 +1,468 @@
+//! Splits microcode source into statements, and parses the steps and
+//! expressions within them. Placeholders are substituted by the assembler
+//! before the text holding them is parsed, so steps and guards are kept as
+//! text here.
+
+use crate::{Error, Location};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Line {
+    pub location: Location,
+    pub text: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Statement {
+    /// `set <name> = <value>, ...`: the values a placeholder may take.
+    Set { name: String, values: Vec<String> },
+    /// `condition <name> = <expression>`: a name for an expression over the
+    /// state, usable in guards.
+    Condition {
+        name: String,
+        expression: Expression,
+    },
+    /// `sequence <name>[(<parameter>, ...)]:`: steps that other steps `use`.
+    Sequence {
+        name: String,
+        parameters: Vec<String>,
+        body: Vec<Line>,
+    },
+    /// `<target>, ... [if <guard>]:`: the steps of the opcodes or interrupt
+    /// entries named, in the states where the guard holds.
+    Block {
+        targets: String,
+        guard: Option<String>,
+        body: Vec<Line>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Step {
+    /// `use <sequence>[(<argument>, ...)]`
+    Use {
+        sequence: String,
+        arguments: Vec<String>,
+    },
+    /// The signals driven in one step, separated by commas.
+    Signals(Vec<Signal>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Signal {
+    /// A bare word: `nop`, `kernel`, `ext` or `reset`.
+    Keyword(String),
+    /// `flags <operation>`
+    Flags(String),
+    /// `alu <operation> <operands> [-> <load>]`, which drives the ALU onto
+    /// the data bus.
+    Alu {
+        operation: String,
+        operands: String,
+        load: Option<String>,
+    },
+    /// `@<assert> [+ 1 | - 1 | + <data assert>] [-> <load>]`
+    Address {
+        assert: String,
+        operation: Option<AddressOffset>,
+        load: Option<String>,
+    },
+    /// `<assert> -> <load>` over the data bus.
+    Transfer { assert: String, load: String },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AddressOffset {
+    Increment,
+    Decrement,
+    /// Adds the data bus, driven by the named device.
+    By(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expression {
+    Name(String),
+    Not(Box<Expression>),
+    And(Box<Expression>, Box<Expression>),
+    Or(Box<Expression>, Box<Expression>),
+    Equal(Box<Expression>, Box<Expression>),
+    NotEqual(Box<Expression>, Box<Expression>),
+}
+
+/// Splits `source` into statements. A statement starts at the beginning of a
+/// line, and those ending in `:` take the indented lines after them as their
+/// body. Comments run from `#` to the end of the line.
+pub fn parse(file: &str, source: &str) -> Result<Vec<(Location, Statement)>, Error> {
+    let mut statements: Vec<(Location, Statement)> = Vec::new();
+    let mut open = false;
+
+    for (index, line) in source.lines().enumerate() {
+        let location = Location {
+            file: file.to_string(),
+            line: index + 1,
+        };
+        let text = match line.find('#') {
+            Some(comment) => &line[..comment],
+            None => line,
+        };
+
+        if text.trim().is_empty() {
+            continue;
+        }
+
+        let error = |message: String| Error {
+            location: location.clone(),
+            message,
+        };
+
+        if text.starts_with(char::is_whitespace) {
+            let body = match statements.last_mut() {
+                Some((_, Statement::Sequence { body, .. } | Statement::Block { body, .. }))
+                    if open =>
+                {
+                    body
+                }
+                _ => return Err(error("indented line outside a block".to_string())),
+            };
+            body.push(Line {
+                location: location.clone(),
+                text: text.trim().to_string(),
+            });
+            continue;
+        }
+
+        check_body(statements.last(), open)?;
+
+        let statement = parse_statement(text.trim()).map_err(error)?;
+        open = matches!(
+            statement,
+            Statement::Sequence { .. } | Statement::Block { .. }
+        );
+        statements.push((location, statement));
+    }
+
+    check_body(statements.last(), open)?;
+
+    Ok(statements)
+}
+
+fn check_body(statement: Option<&(Location, Statement)>, open: bool) -> Result<(), Error> {
+    match statement {
+        Some((location, Statement::Sequence { body, .. } | Statement::Block { body, .. }))
+            if open && body.is_empty() =>
+        {
+            Err(Error {
+                location: location.clone(),
+                message: "block has no steps".to_string(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+fn parse_statement(text: &str) -> Result<Statement, String> {
+    if let Some(rest) = keyword(text, "set") {
+        let (name, values) = definition(rest)?;
+        let values = values
+            .split(',')
+            .map(|value| identifier(value.trim()).map(str::to_string))
+            .collect::<Result<_, _>>()?;
+        return Ok(Statement::Set { name, values });
+    }
+
+    if let Some(rest) = keyword(text, "condition") {
+        let (name, expression) = definition(rest)?;
+        let expression = parse_expression(expression)?;
+        return Ok(Statement::Condition { name, expression });
+    }
+
+    let header = match text.strip_suffix(':') {
+        Some(header) => header.trim(),
+        None => return Err(format!("expected `:` at the end of `{}`", text)),
+    };
+
+    if let Some(rest) = keyword(header, "sequence") {
+        let (name, parameters) = call(rest)?;
+        return Ok(Statement::Sequence {
+            name,
+            parameters,
+            body: Vec::new(),
+        });
+    }
+
+    let (targets, guard) = match header.split_once(" if ") {
+        Some((targets, guard)) => (targets.trim(), Some(guard.trim().to_string())),
+        None => (header, None),
+    };
+
+    Ok(Statement::Block {
+        targets: targets.to_string(),
+        guard,
+        body: Vec::new(),
+    })
+}
+
+/// Parses one line of a block's body, once its placeholders are substituted.
+pub fn parse_step(text: &str) -> Result<Step, String> {
+    if let Some(rest) = keyword(text, "use") {
+        let (sequence, arguments) = call(rest)?;
+        return Ok(Step::Use {
+            sequence,
+            arguments,
+        });
+    }
+
+    let mut tokens = Tokens::new(text)?;
+    let mut signals = Vec::new();
+
+    loop {
+        signals.push(parse_signal(&mut tokens)?);
+
+        match tokens.next() {
+            None => return Ok(Step::Signals(signals)),
+            Some(Token::Symbol(",")) => {}
+            Some(token) => return Err(format!("expected `,`, found {}", token)),
+        }
+    }
+}
+
+fn parse_signal(tokens: &mut Tokens) -> Result<Signal, String> {
+    match tokens.next() {
+        Some(Token::Symbol("@")) => {
+            let assert = tokens.identifier()?;
+            let operation = match tokens.peek() {
+                Some(Token::Symbol(sign @ ("+" | "-"))) => {
+                    let sign = *sign;
+                    tokens.next();
+                    match (sign, tokens.identifier()?.as_str()) {
+                        ("+", "1") => Some(AddressOffset::Increment),
+                        ("-", "1") => Some(AddressOffset::Decrement),
+                        ("+", by) => Some(AddressOffset::By(by.to_string())),
+                        (_, by) => {
+                            return Err(format!("the address unit cannot subtract `{}`", by))
+                        }
+                    }
+                }
+                _ => None,
+            };
+            let load = tokens.load()?;
+            Ok(Signal::Address {
+                assert,
+                operation,
+                load,
+            })
+        }
+        Some(Token::Identifier(word)) if word == "alu" => {
+            let operation = tokens.identifier()?;
+            let operands = tokens.identifier()?;
+            let load = tokens.load()?;
+            Ok(Signal::Alu {
+                operation,
+                operands,
+                load,
+            })
+        }
+        Some(Token::Identifier(word)) if word == "flags" => Ok(Signal::Flags(tokens.identifier()?)),
+        Some(Token::Identifier(word)) => match tokens.load()? {
+            Some(load) => Ok(Signal::Transfer { assert: word, load }),
+            None => Ok(Signal::Keyword(word)),
+        },
+        Some(token) => Err(format!("expected a signal, found {}", token)),
+        None => Err("expected a signal".to_string()),
+    }
+}
+
+/// Parses an expression over the state, once its placeholders are
+/// substituted. `||` binds loosest, then `&&`, then `==` and `!=`.
+pub fn parse_expression(text: &str) -> Result<Expression, String> {
+    let mut tokens = Tokens::new(text)?;
+    let expression = parse_or(&mut tokens)?;
+
+    match tokens.next() {
+        None => Ok(expression),
+        Some(token) => Err(format!("unexpected {} in expression", token)),
+    }
+}
+
+fn parse_or(tokens: &mut Tokens) -> Result<Expression, String> {
+    let mut left = parse_and(tokens)?;
+
+    while tokens.eat("||") {
+        left = Expression::Or(Box::new(left), Box::new(parse_and(tokens)?));
+    }
+
+    Ok(left)
+}
+
+fn parse_and(tokens: &mut Tokens) -> Result<Expression, String> {
+    let mut left = parse_comparison(tokens)?;
+
+    while tokens.eat("&&") {
+        left = Expression::And(Box::new(left), Box::new(parse_comparison(tokens)?));
+    }
+
+    Ok(left)
+}
+
+fn parse_comparison(tokens: &mut Tokens) -> Result<Expression, String> {
+    let left = parse_unary(tokens)?;
+
+    if tokens.eat("==") {
+        Ok(Expression::Equal(
+            Box::new(left),
+            Box::new(parse_unary(tokens)?),
+        ))
+    } else if tokens.eat("!=") {
+        Ok(Expression::NotEqual(
+            Box::new(left),
+            Box::new(parse_unary(tokens)?),
+        ))
+    } else {
+        Ok(left)
+    }
+}
+
+fn parse_unary(tokens: &mut Tokens) -> Result<Expression, String> {
+    match tokens.next() {
+        Some(Token::Symbol("!")) => Ok(Expression::Not(Box::new(parse_unary(tokens)?))),
+        Some(Token::Symbol("(")) => {
+            let expression = parse_or(tokens)?;
+            match tokens.eat(")") {
+                true => Ok(expression),
+                false => Err("expected `)`".to_string()),
+            }
+        }
+        Some(Token::Identifier(name)) => Ok(Expression::Name(name)),
+        Some(token) => Err(format!("expected a condition, found {}", token)),
+        None => Err("expected a condition".to_string()),
+    }
+}
+
+/// The rest of `text` if it starts with the word `keyword`.
+fn keyword<'a>(text: &'a str, keyword: &str) -> Option<&'a str> {
+    let rest = text.strip_prefix(keyword)?;
+    match rest.starts_with(char::is_whitespace) {
+        true => Some(rest.trim()),
+        false => None,
+    }
+}
+
+/// Splits `<name> = <value>`.
+fn definition(text: &str) -> Result<(String, &str), String> {
+    match text.split_once('=') {
+        Some((name, value)) => Ok((identifier(name.trim())?.to_string(), value.trim())),
+        None => Err(format!("expected `=` in `{}`", text)),
+    }
+}
+
+/// Splits `<name>` or `<name>(<argument>, ...)`.
+fn call(text: &str) -> Result<(String, Vec<String>), String> {
+    let (name, arguments) = match text.split_once('(') {
+        Some((name, rest)) => match rest.strip_suffix(')') {
+            Some(arguments) => (name.trim(), arguments.split(',').collect()),
+            None => return Err(format!("expected `)` at the end of `{}`", text)),
+        },
+        None => (text, Vec::new()),
+    };
+
+    let arguments = arguments
+        .into_iter()
+        .map(|argument| identifier(argument.trim()).map(str::to_string))
+        .collect::<Result<_, _>>()?;
+
+    Ok((identifier(name)?.to_string(), arguments))
+}
+
+fn identifier(text: &str) -> Result<&str, String> {
+    match !text.is_empty() && text.chars().all(is_identifier_char) {
+        true => Ok(text),
+        false => Err(format!("expected a name, found `{}`", text)),
+    }
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Identifier(String),
+    Symbol(&'static str),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Identifier(name) => write!(f, "`{}`", name),
+            Token::Symbol(symbol) => write!(f, "`{}`", symbol),
+        }
+    }
+}
+
+struct Tokens {
+    tokens: std::iter::Peekable<std::vec::IntoIter<Token>>,
+}
+
+impl Tokens {
+    const SYMBOLS: [&'static str; 12] = [
+        "->", "&&", "||", "==", "!=", "@", "+", "-", ",", "(", ")", "!",
+    ];
+
+    fn new(text: &str) -> Result<Self, String> {
+        let mut tokens = Vec::new();
+        let mut rest = text.trim_start();
+
+        while let Some(c) = rest.chars().next() {
+            if is_identifier_char(c) {
+                let end = rest.find(|c| !is_identifier_char(c)).unwrap_or(rest.len());
+                tokens.push(Token::Identifier(rest[..end].to_string()));
+                rest = &rest[end..];
+            } else {
+                let symbol = Self::SYMBOLS
+                    .into_iter()
+                    .find(|symbol| rest.starts_with(symbol))
+                    .ok_or_else(|| format!("unexpected `{}`", c))?;
+                tokens.push(Token::Symbol(symbol));
+                rest = &rest[symbol.len()..];
+            }
+            rest = rest.trim_start();
+        }
+
+        Ok(Self {
+            tokens: tokens.into_iter().peekable(),
+        })
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        self.tokens.next()
+    }
+
+    fn peek(&mut self) -> Option<&Token> {
+        self.tokens.peek()
+    }
+
+    fn eat(&mut self, symbol: &str) -> bool {
+        match self.peek() {
+            Some(Token::Symbol(next)) if *next == symbol => {
+                self.next();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn identifier(&mut self) -> Result<String, String> {
+        match self.next() {
+            Some(Token::Identifier(name)) => Ok(name),
+            Some(token) => Err(format!("expected a name, found {}", token)),
+            None => Err("expected a name".to_string()),
+        }
+    }
+
+    /// An optional `-> <load>`.
+    fn load(&mut self) -> Result<Option<String>, String> {
+        match self.eat("->") {
+            true => self.identifier().map(Some),
+            false => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! The control ROMs: the images written to each chip.
+
+use std::fmt::Write;
+
+use uarch::{ControlWord, Sequencer, StateVector};
+
+pub use uarch::{CHIP_COUNT, ROM_SIZE};
+
+use crate::Microcode;
+
+/// The control word at every ROM address. Addresses the sequencer never
+/// reaches hold a word that resets it.
+pub fn image(microcode: &Microcode) -> Vec<ControlWord> {
+    let unused = ControlWord::new().with_sequencer(Sequencer::Reset);
+    let mut image = vec![unused; ROM_SIZE];
+
+    // Every step of a sequence shares the state it depends on, so each one is
+    // expanded once and laid out across its steps.
+    for state in StateVector::reachable() {
+        let sequence = microcode.sequence(state);
+        for (address, word) in state.addresses().zip(sequence) {
+            image[address.index()] = word;
+        }
+    }
+
+    image
+}
+
+/// Splits `image` into the contents of each ROM chip.
+pub fn chips(image: &[ControlWord]) -> Vec<Vec<u8>> {
+    let mut chips: Vec<Vec<u8>> = (0..CHIP_COUNT)
+        .map(|_| Vec::with_capacity(image.len()))
+        .collect();
+
+    for word in image {
+        for (chip, byte) in chips.iter_mut().zip(word.into_bytes()) {
+            chip.push(byte);
+        }
+    }
+
+    chips
+}
+
+/// Formats `data` as Intel HEX, for EEPROM programmers, with an extended
+/// linear address record before each 64 KiB.
+pub fn intel_hex(data: &[u8]) -> String {
+    let mut hex = String::new();
+
+    for (index, chunk) in data.chunks(16).enumerate() {
+        let address = index * 16;
+        if address % 0x1_0000 == 0 && address != 0 {
+            let segment = (address >> 16) as u16;
+            record(&mut hex, 0, 0x04, &segment.to_be_bytes());
+        }
+        record(&mut hex, address as u16, 0x00, chunk);
+    }
+
+    record(&mut hex, 0, 0x01, &[]);
+    hex
+}
+
+fn record(hex: &mut String, address: u16, kind: u8, data: &[u8]) {
+    let [high, low] = address.to_be_bytes();
+    let mut bytes = vec![data.len() as u8, high, low, kind];
+    bytes.extend_from_slice(data);
+
+    let checksum = bytes
+        .iter()
+        .fold(0u8, |sum, byte| sum.wrapping_add(*byte))
+        .wrapping_neg();
+
+    hex.push(':');
+    for byte in bytes.iter().chain([&checksum]) {
+        write!(hex, "{:02X}", byte).expect("writing to a string succeeds");
+    }
+    hex.push('\n');
+}
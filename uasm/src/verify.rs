@@ -0,0 +1,195 @@
+//! Checks a control ROM image against the rules the datapath relies on, in
+//! every state vector the sequencer can reach.
+
+use std::collections::HashSet;
+
+use isa::{ExtensionMode, Opcode, EXTENSION_PREFIX};
+use uarch::*;
+
+/// A rule a control word breaks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Fault {
+    /// The sequence runs off the last step without resetting the sequencer.
+    NoReset,
+    /// A field holds a bit pattern that names no signal.
+    UndefinedField(&'static str),
+    /// Memory or I/O is read in the same step as it is written, so the device
+    /// being read drives the data bus alongside the source of the write.
+    BusContention,
+    /// The ALU computes a result that neither the data bus nor the flags
+    /// take.
+    UnusedAluResult,
+    /// The data bus or the flags take the ALU's result, but it has no
+    /// operation or no operands.
+    MissingAluOperation,
+    /// Memory is written at an address bus source that holds no data
+    /// address.
+    BogusWriteAddress(AddressBusAssert),
+}
+
+impl std::fmt::Display for Fault {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Fault::NoReset => write!(f, "the sequencer is never reset"),
+            Fault::UndefinedField(field) => write!(f, "the {} field is undefined", field),
+            Fault::BusContention => write!(f, "memory or I/O is read and written in one step"),
+            Fault::UnusedAluResult => write!(f, "the ALU's result is not used"),
+            Fault::MissingAluOperation => {
+                write!(f, "the ALU's result is used, but it has no operation")
+            }
+            Fault::BogusWriteAddress(source) => {
+                write!(f, "memory is written at the {:?} address", source)
+            }
+        }
+    }
+}
+
+/// A fault at one step of an opcode or interrupt entry.
+#[derive(Debug)]
+pub struct Violation {
+    /// The opcode's instruction as the assembler writes it, such as
+    /// `addc a, b`, or `nmi` or `irq`.
+    pub name: String,
+    /// The first address the fault was found at.
+    pub address: MicroAddress,
+    pub fault: Fault,
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}, step {} (address {:#07X}): {}",
+            self.name,
+            self.address.step(),
+            self.address.index(),
+            self.fault
+        )
+    }
+}
+
+/// Checks every step the sequencer runs through in `image`, which holds a
+/// control word for every `MicroAddress`. A fault found at the same step of
+/// an opcode in several states is reported once.
+pub fn verify(image: &[ControlWord]) -> Vec<Violation> {
+    let mut seen = HashSet::new();
+    let mut violations = Vec::new();
+
+    for state in StateVector::reachable() {
+        let name = name(state);
+
+        for (address, fault) in walk(image, state) {
+            if seen.insert((name.clone(), address.step(), fault)) {
+                violations.push(Violation {
+                    name: name.clone(),
+                    address,
+                    fault,
+                });
+            }
+        }
+    }
+
+    violations
+}
+
+/// Follows the sequencer from the first step of `state` until it resets,
+/// checking each word on the way.
+fn walk(image: &[ControlWord], state: StateVector) -> Vec<(MicroAddress, Fault)> {
+    let mut faults = Vec::new();
+
+    for step in 0..STEP_COUNT {
+        let address = MicroAddress::new().with_step(step).with_state(state);
+        let word = image[address.index()];
+
+        if let Err(fault) = check(word) {
+            faults.push((address, fault));
+        }
+
+        match word.sequencer_or_err() {
+            // The steps after the prefix run in the extended state of the
+            // opcode it loads, which is walked on its own.
+            Ok(Sequencer::Extend) if !state.extended() => return faults,
+            Ok(Sequencer::Next | Sequencer::Extend) => {}
+            Ok(Sequencer::Reset) => return faults,
+            // `check` has reported it, and the step it leads to is unknown.
+            Err(_) => return faults,
+        }
+    }
+
+    let last = MicroAddress::new()
+        .with_step(STEP_COUNT - 1)
+        .with_state(state);
+    faults.push((last, Fault::NoReset));
+    faults
+}
+
+fn check(word: ControlWord) -> Result<(), Fault> {
+    let undefined = |field| move |_| Fault::UndefinedField(field);
+
+    let data_assert = word
+        .data_assert_or_err()
+        .map_err(undefined("data bus source"))?;
+    let data_load = word
+        .data_load_or_err()
+        .map_err(undefined("data bus destination"))?;
+    let address_assert = word
+        .address_assert_or_err()
+        .map_err(undefined("address bus source"))?;
+    let alu_op = word.alu_op_or_err().map_err(undefined("ALU operation"))?;
+    let xfer_assert = word
+        .xfer_assert_or_err()
+        .map_err(undefined("ALU operand"))?;
+    word.address_load_or_err()
+        .map_err(undefined("address bus destination"))?;
+    let flags = word.flags_or_err().map_err(undefined("flag operation"))?;
+    word.sequencer_or_err().map_err(undefined("sequencer"))?;
+
+    let reads = matches!(data_assert, DataBusAssert::Memory | DataBusAssert::Io);
+    let writes = matches!(data_load, DataBusLoad::Memory | DataBusLoad::Io);
+    if reads && writes {
+        return Err(Fault::BusContention);
+    }
+
+    let takes_result = data_assert == DataBusAssert::Alu || flags == FlagOperation::Arithmetic;
+    if alu_op != AluOperation::Nop && !takes_result {
+        return Err(Fault::UnusedAluResult);
+    }
+    if takes_result && (alu_op == AluOperation::Nop || xfer_assert == TransferBusAssert::None) {
+        return Err(Fault::MissingAluOperation);
+    }
+
+    let data_address = matches!(
+        address_assert,
+        AddressBusAssert::StackPointer
+            | AddressBusAssert::X
+            | AddressBusAssert::Y
+            | AddressBusAssert::Temp
+    );
+    if data_load == DataBusLoad::Memory && !data_address {
+        return Err(Fault::BogusWriteAddress(address_assert));
+    }
+
+    Ok(())
+}
+
+/// The name violations in `state` are reported under: the interrupt being
+/// entered, or the instruction its opcode decodes to, as the assembler writes
+/// it. The sequencer never sees an instruction's operands, so they are shown
+/// as zero.
+fn name(state: StateVector) -> String {
+    let (mode, opcode) = match (state.interrupt(), state.extended()) {
+        (Interrupt::Nmi, _) => return "nmi".to_string(),
+        (Interrupt::Irq, _) => return "irq".to_string(),
+        (Interrupt::None, false) if state.opcode() == EXTENSION_PREFIX => {
+            return "extension prefix".to_string()
+        }
+        (Interrupt::None, false) => (ExtensionMode::Normal, Opcode::Normal(state.opcode())),
+        (Interrupt::None, true) => (ExtensionMode::Extended, Opcode::Extended(state.opcode())),
+    };
+
+    let mut bytes = std::iter::once(state.opcode()).chain(std::iter::repeat(0));
+    match isa::decode(mode, &mut bytes) {
+        Some(instruction) => instruction.to_string(),
+        None => format!("{:?}", opcode),
+    }
+}
@@ -0,0 +1,132 @@
+use std::path::Path;
+
+use uarch::{control_rom, Interrupt, MicroAddress, StateVector};
+use uasm::rom;
+
+fn bundled() -> uasm::Microcode {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("bw8.uasm");
+    uasm::assemble_file(&path).unwrap_or_else(|error| panic!("{}", error))
+}
+
+fn error(source: &str) -> String {
+    match uasm::assemble_source(Path::new("test.uasm"), source) {
+        Ok(_) => panic!("assembled `{}`", source),
+        Err(error) => error.to_string(),
+    }
+}
+
+/// A source covering every state with `nop`, after `extra`.
+fn minimal(extra: &str) -> String {
+    let mut source = format!("{}\nfetch, nmi, irq:\n    nop\n", extra);
+    for (name, _) in isa::OPCODES {
+        source.push_str(&format!("{}:\n    nop\n", name));
+    }
+    source
+}
+
+#[test]
+fn bundled_source_assembles_to_the_committed_rom() {
+    let image = rom::image(&bundled());
+
+    if let Some(index) = (0..rom::ROM_SIZE).find(|&index| image[index] != control_rom()[index]) {
+        panic!(
+            "uarch/rom differs from uasm/bw8.uasm at {:?}; rebuild it with \
+             `cargo run -p uasm -- uasm/bw8.uasm -o uarch/rom/bw8`",
+            MicroAddress::from_index(index)
+        );
+    }
+}
+
+#[test]
+fn image_lays_out_every_step_of_a_state() {
+    let microcode = bundled();
+    let image = rom::image(&microcode);
+    assert_eq!(image.len(), rom::ROM_SIZE);
+
+    let state = StateVector::new()
+        .with_interrupt(Interrupt::Irq)
+        .with_user(true);
+    for address in state.addresses() {
+        assert_eq!(image[address.index()], microcode.control_word(address));
+    }
+
+    // Extended opcodes are never interrupted, so those addresses go unused.
+    let unreachable = MicroAddress::new().with_state(state.with_extended(true));
+    assert_eq!(
+        image[unreachable.index()],
+        uarch::ControlWord::new().with_sequencer(uarch::Sequencer::Reset)
+    );
+
+    let chips = rom::chips(&image);
+    assert_eq!(chips.len(), rom::CHIP_COUNT);
+    let address = MicroAddress::new().with_step(3).with_state(state).index();
+    let bytes: Vec<u8> = chips.iter().map(|chip| chip[address]).collect();
+    assert_eq!(bytes, image[address].into_bytes());
+}
+
+#[test]
+fn intel_hex_records_carry_addresses_and_checksums() {
+    let mut data = vec![0u8; 0x1_0010];
+    data[0] = 0x01;
+    data[1] = 0x02;
+    data[0x1_0000] = 0xAA;
+
+    let hex = rom::intel_hex(&data);
+    let lines: Vec<&str> = hex.lines().collect();
+
+    assert_eq!(lines[0], ":1000000001020000000000000000000000000000ED");
+    assert_eq!(lines[0x1000], ":020000040001F9");
+    assert_eq!(lines[0x1001], ":10000000AA00000000000000000000000000000046");
+    assert_eq!(lines.last(), Some(&":00000001FF"));
+}
+
+#[test]
+fn placeholders_expand_over_their_sets() {
+    let source = minimal(
+        "set registers = a, b\n\nMV_{dst:registers}_{src:registers}:\n    {src} -> {dst}\n",
+    );
+    let microcode = uasm::assemble_source(Path::new("test.uasm"), &source).unwrap();
+
+    // `mv a, b` is opcode 0x0B; its block comes before the catch-all `nop`.
+    let address = MicroAddress::new()
+        .with_step(1)
+        .with_state(StateVector::new().with_opcode(0x0B));
+    let word = microcode.control_word(address);
+    assert_eq!(word.data_assert(), uarch::DataBusAssert::B);
+    assert_eq!(word.data_load(), uarch::DataBusLoad::A);
+    assert_eq!(word.sequencer(), uarch::Sequencer::Reset);
+}
+
+#[test]
+fn errors_name_the_offending_line() {
+    assert_eq!(
+        error(&minimal("NOP:\n    a -> b, c -> d")),
+        "test.uasm:2: the data bus is driven twice in one step"
+    );
+    assert_eq!(
+        error(&minimal("NOP:\n    @pc + 1 -> q")),
+        "test.uasm:2: `q` cannot load the address bus"
+    );
+    assert_eq!(
+        error(&minimal("MV_{dst:registers}_A:\n    a -> {dst}")),
+        "test.uasm:1: unknown set `registers`"
+    );
+    assert_eq!(
+        error(&minimal("NOP if q:\n    nop")),
+        "test.uasm:1: unknown condition `q`"
+    );
+    assert_eq!(
+        error(&minimal("NOP:\n    use missing")),
+        "test.uasm:2: unknown sequence `missing`"
+    );
+    assert_eq!(
+        error(&minimal(
+            "NOP:\n    nop\n    nop\n    nop\n    nop\n    nop\n    nop\n    nop\n    nop"
+        )),
+        "test.uasm:1: sequence takes 9 steps; the sequencer counts 8"
+    );
+    assert_eq!(
+        error("fetch, nmi, irq:\n    nop"),
+        "test.uasm:0: `NOP` needs a block without a guard"
+    );
+}
@@ -0,0 +1,78 @@
+use isa::Opcode;
+use uarch::*;
+use uasm::verify::{verify, Fault};
+
+/// The image the processor model runs and the ROMs are burned from.
+fn bundled_image() -> Vec<ControlWord> {
+    control_rom().to_vec()
+}
+
+/// Every address of `step` in the states running the opcode `name`.
+fn addresses(name: &str, step: u8) -> impl Iterator<Item = usize> {
+    let (_, opcode) = *isa::OPCODES
+        .iter()
+        .find(|(named, _)| *named == name)
+        .expect("the opcode exists");
+
+    StateVector::reachable()
+        .filter(move |state| {
+            state.interrupt() == Interrupt::None
+                && match opcode {
+                    Opcode::Normal(byte) => !state.extended() && state.opcode() == byte,
+                    Opcode::Extended(byte) => state.extended() && state.opcode() == byte,
+                }
+        })
+        .map(move |state| {
+            MicroAddress::new()
+                .with_step(step)
+                .with_state(state)
+                .index()
+        })
+}
+
+#[test]
+fn bundled_image_has_no_violations() {
+    let violations = verify(&bundled_image());
+    let messages: Vec<String> = violations.iter().map(ToString::to_string).collect();
+    assert!(messages.is_empty(), "{:#?}", messages);
+}
+
+#[test]
+fn violations_name_the_opcode() {
+    let mut image = bundled_image();
+
+    // `nop` never resets the sequencer.
+    for step in 1..STEP_COUNT {
+        for address in addresses("NOP", step) {
+            image[address] = ControlWord::new();
+        }
+    }
+    // `clr.c` writes memory at the program counter, reading it as it does.
+    for address in addresses("CLR_C", 1) {
+        image[address] = image[address]
+            .with_data_assert(DataBusAssert::Memory)
+            .with_data_load(DataBusLoad::Memory)
+            .with_address_assert(AddressBusAssert::ProgramCounter);
+    }
+    // `addc a, b` adds, but neither the data bus nor the flags take the sum.
+    for address in addresses("ADDC_A_B", 2) {
+        image[address] = image[address]
+            .with_data_assert(DataBusAssert::A)
+            .with_flags(FlagOperation::Nop);
+    }
+
+    let mut found: Vec<(String, u8, Fault)> = verify(&image)
+        .into_iter()
+        .map(|violation| (violation.name, violation.address.step(), violation.fault))
+        .collect();
+    found.sort_by_key(|(name, step, _)| (name.clone(), *step));
+
+    assert_eq!(
+        found,
+        [
+            ("addc a, b".to_string(), 2, Fault::UnusedAluResult),
+            ("clr.c".to_string(), 1, Fault::BusContention),
+            ("nop".to_string(), 7, Fault::NoReset),
+        ]
+    );
+}